@@ -5,20 +5,24 @@
 //! ACL 需要事先授权 Safe 对 token 的 transfer 白名单。
 //!
 //! 展示：
-//! - `app::AppConfigBase`（rpc_url / signer_url / signer_project / ed25519_seed /
-//!   cobosafe_address / gas_price_gwei / flashbots_auth_key） + `#[serde(flatten)]`
+//! - `app::AppConfigBase`（rpc_url / signer / cobosafe_address / gas_price_gwei /
+//!   flashbots_auth_key） + `#[serde(flatten)]`
 //! - `app::init_tracing` / `app::load_json`
 //! - `utils::cobosafe::query_safe` / `submit_transactions`
 //! - `utils::erc20` sol 接口 + RPC 查询
-//! - `RemoteSigner` + `RpcSender`
+//! - `build_signer`（生产用 remote；改成 `{"type": "keystore", ...}` 即可本地跑） + `RpcSender`
 //!
 //! config.json 示例：
 //! ```json
 //! {
 //!   "rpc_url": "https://...",
-//!   "signer_url": "https://signer.example.com",
-//!   "signer_project": "my-project",
-//!   "ed25519_seed": "0xabc...",
+//!   "signer": {
+//!     "type": "remote",
+//!     "url": "https://signer.example.com",
+//!     "project": "my-project",
+//!     "seed": "0xabc...",
+//!     "account_index": 0
+//!   },
 //!   "cobosafe_address": "0x...",
 //!   "gas_price_gwei": null,
 //!   "flashbots_auth_key": null,
@@ -81,7 +85,7 @@ async fn main() -> Result<()> {
     tracing::info!("CoboSafe: {cobosafe_addr}");
     tracing::info!("Safe:     {safe}");
 
-    let signer = config.base.build_remote_signer().await?;
+    let signer = config.base.build_signer().await?;
     tracing::info!("Operator: {}", signer.address());
 
     // 查 token decimals 并解析 amount
@@ -2,12 +2,14 @@
 //!
 //! 展示：
 //! - `LocalSigner`（用 `PrivateKeySigner` 私钥）
-//! - `DirectBuilder`（EOA → target，nonce/gas/chain_id 自动组装）
+//! - `DirectBuilder`（EOA → target）
 //! - `RpcSender`（`eth_sendRawTransaction`）
+//! - `Pipeline`（gas = basefee × 1.5，nonce 取 pending 交易数；`DRY_RUN=1` 时
+//!   只签名 + fork 模拟，不广播）
 //! - `utils::decimal::parse_decimal_units`（"1.5" → wei）
 //!
 //! 运行：
-//!   RPC_URL=https://... PRIVATE_KEY=0x... RECIPIENT=0x... AMOUNT=0.01 \
+//!   RPC_URL=https://... PRIVATE_KEY=0x... RECIPIENT=0x... AMOUNT=0.01 [DRY_RUN=1] \
 //!       cargo run --example direct_transfer
 
 use alloy::{
@@ -20,8 +22,9 @@ use eyre::Result;
 
 use flashseal_rs::{
     app,
+    pipeline::{BaseFeeMultiplier, Pipeline, SimulationGate},
     utils::decimal::parse_decimal_units,
-    DirectBuilder, LocalSigner, RpcSender, TxBuilder, TxRequest, TxSender, TxSigner,
+    DirectBuilder, LocalSigner, RpcSender, TxRequest, TxSigner,
 };
 
 const ETH_TRANSFER_GAS_LIMIT: u64 = 21_000;
@@ -37,6 +40,7 @@ async fn main() -> Result<()> {
         .expect("RECIPIENT env required")
         .parse()?;
     let amount_str = std::env::var("AMOUNT").unwrap_or_else(|_| "0.01".into());
+    let dry_run = std::env::var("DRY_RUN").is_ok_and(|v| v == "1");
 
     let provider = ProviderBuilder::new()
         .network::<AnyNetwork>()
@@ -52,38 +56,40 @@ async fn main() -> Result<()> {
     tracing::info!("From:     {from}");
     tracing::info!("To:       {recipient}");
 
-    // gas fee：拿 basefee 建议值，自己决策 × 1.5 作为 EIP-1559 的 max_fee/priority
-    // （两者相等，总付 = 1.5 × base）
-    let base = app::estimate_gas_fee(&provider).await?;
-    let gas = base * 3 / 2;
-    tracing::info!(
-        "Gas:      {:.3} gwei (base {:.3} gwei × 1.5)",
-        gas as f64 / 1_000_000_000.0,
-        base as f64 / 1_000_000_000.0,
-    );
-
     // 解析金额
     let value = parse_decimal_units(&amount_str, ETH_DECIMALS)?;
     tracing::info!("Value:    {amount_str} ETH ({value} wei)");
 
-    // 取 nonce
-    let nonce = provider.get_transaction_count(from).await?;
-
-    // 构造 + 签名 + 发送
+    // fees → build → nonce → sign → (simulate) → send
     let builder = DirectBuilder::new(chain_id);
+    let sender = RpcSender::new(&rpc_url)?;
+    let pipeline = Pipeline::new(
+        &builder,
+        &signer,
+        &sender,
+        BaseFeeMultiplier::new(provider.clone()),
+        provider.clone(),
+    )
+    .dry_run(dry_run);
+    let pipeline = if dry_run {
+        pipeline.with_simulation(SimulationGate::new(&rpc_url))
+    } else {
+        pipeline
+    };
+
     let req = TxRequest {
         to: recipient,
         value,
         data: Bytes::new(),
         gas_limit: ETH_TRANSFER_GAS_LIMIT,
     };
-    let unsigned = builder.build_txs(&[req], nonce, gas, gas)?;
-    let tx = unsigned.into_iter().next().unwrap();
-    let raw = signer.sign(tx).await?;
-
-    let sender = RpcSender::new(&rpc_url)?;
-    let hashes = sender.send_txs(&[raw]).await?;
-    for h in hashes {
+    let outcome = pipeline.submit(&[req]).await?;
+    if outcome.dry_run {
+        for raw in &outcome.raws {
+            tracing::info!("raw tx:   0x{}", alloy::hex::encode(&raw.0));
+        }
+    }
+    for h in &outcome.hashes {
         tracing::info!("tx hash: {h}");
     }
     Ok(())
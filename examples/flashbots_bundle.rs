@@ -57,7 +57,7 @@ async fn main() -> Result<()> {
     let safe = cobosafe::query_safe(&provider, cobosafe_addr).await?;
     tracing::info!("Safe: {safe}");
 
-    let signer = config.base.build_remote_signer().await?;
+    let signer = config.base.build_signer().await?;
     let operator = signer.address();
     let nonce = provider.get_transaction_count(operator).await?;
 
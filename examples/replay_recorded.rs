@@ -0,0 +1,67 @@
+//! 把 `{"type": "record"}` 通道写下的 JSONL 经真实通道重新广播。
+//!
+//! 流程：先用 record sender 跑业务 bin（只签名、不广播）→ 人工审核 signed.jsonl →
+//! 用本 example 按原批次发出。
+//!
+//! 展示：
+//! - `sender::record::read_records`（校验 raw 与 hash 一致）
+//! - `sender::record::replay_records`（发出校验、展示过的那份记录；同批交易原样一起发）
+//! - `AppConfigBase::build_sender`（`sender` 段决定走 public / flashbots / private ...）
+//!
+//! config.json 至少需要 `rpc_url` 和签名器（签名器不会被用到，只为复用同一份
+//! 配置），`sender` 不能是 `record`。
+//!
+//! 运行：
+//!   cargo run --example replay_recorded -- ./config.json ./signed.jsonl
+
+use alloy::providers::Provider;
+use eyre::Result;
+
+use flashseal_rs::{
+    app::{self, AppConfigBase},
+    sender::record,
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    app::init_tracing();
+
+    let mut args = std::env::args().skip(1);
+    let config_path = args.next().unwrap_or_else(|| "config.json".into());
+    let records_path = args.next().unwrap_or_else(|| "signed.jsonl".into());
+    let config: AppConfigBase = app::load_json(&config_path)?;
+
+    let records = record::read_records(&records_path)?;
+    eyre::ensure!(!records.is_empty(), "{records_path} has no records");
+
+    let provider = config.build_provider()?;
+    let chain_id = provider.get_chain_id().await?;
+    if let Some(r) = records.iter().find(|r| r.chain_id != Some(chain_id)) {
+        eyre::bail!(
+            "tx {} is for chain {:?}, rpc is chain {chain_id}",
+            r.hash,
+            r.chain_id
+        );
+    }
+
+    for r in &records {
+        let call = r.decoded.as_ref().map_or("-", |c| c.signature.as_str());
+        tracing::info!(
+            "{} nonce={} from={} to={:?} value={} call={call}",
+            r.hash,
+            r.nonce,
+            r.from,
+            r.to,
+            r.value
+        );
+    }
+
+    let sender = config.build_sender()?;
+    eyre::ensure!(
+        !sender.is_record(),
+        "sender is `record`; configure a broadcasting sender to replay"
+    );
+    let hashes = record::replay_records(&records, &sender).await?;
+    tracing::info!("Replayed {} tx(s) from {records_path}", hashes.len());
+    Ok(())
+}
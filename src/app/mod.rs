@@ -16,7 +16,7 @@
 //!     app::init_tracing();
 //!     let config: Config = app::load_json(config_path)?;
 //!     let provider = config.base.build_provider()?;
-//!     let signer = config.base.build_remote_signer().await?;
+//!     let signer = config.base.build_signer().await?;
 //!     let gas = config.base.resolve_gas_fee(&provider).await?;
 //!     // ...
 //! }
@@ -32,21 +32,36 @@ use alloy::{
 use eyre::{Result, WrapErr};
 use serde::Deserialize;
 
-use crate::RemoteSigner;
+use crate::{
+    sender::{
+        BuilderEndpoint, BundleOptions, BundlePreflight, EscalatingSender, FanoutSender,
+        MevShareSender, Privacy, PrivateTxPreferences, RecordSender, Validity,
+    },
+    AnySender, AnySigner, FlashbotsSender, LocalSigner, PolicySigner, PrivateSender,
+    RemoteSigner, RemoteSignerOptions, RpcSender, TxPolicy, TxSigner,
+};
 
 /// 所有 bin 共享的基础配置。业务 Config 通过 `#[serde(flatten)] base: AppConfigBase`
 /// 嵌入即可白拿下面所有 helper。
 ///
 /// 字段划分原则：
-/// - **必填**：`rpc_url` + 远程签名三件套（signer_url / signer_project / ed25519_seed）
+/// - **必填**：`rpc_url` + 签名器（`signer` 段，或旧式的 signer_url / signer_project /
+///   ed25519_seed 三件套）
 /// - **可选**：`cobosafe_address`（Direct 签发的 bin 不填）、`gas_price_gwei`（不填走
-///   estimate）、`flashbots_auth_key`（不填每次随机）
+///   estimate）、`flashbots_auth_key`（不填每次随机）、`sender`（不填走公共 RPC）
 #[derive(Debug, Deserialize)]
 pub struct AppConfigBase {
     pub rpc_url: String,
-    pub signer_url: String,
-    pub signer_project: String,
-    pub ed25519_seed: String,
+    /// 签名器选择，见 [`SignerConfig`]。填了则优先于下面的旧式三件套。
+    #[serde(default)]
+    pub signer: Option<SignerConfig>,
+    /// 旧式远程签名配置，等价于 `signer: { "type": "remote", account_index: 0 }`。
+    #[serde(default)]
+    pub signer_url: Option<String>,
+    #[serde(default)]
+    pub signer_project: Option<String>,
+    #[serde(default)]
+    pub ed25519_seed: Option<String>,
     #[serde(default)]
     pub cobosafe_address: Option<Address>,
     /// 手动指定 EIP-1559 max_fee / max_priority（gwei，两者相等）。
@@ -57,19 +72,334 @@ pub struct AppConfigBase {
     /// —— relay reputation 从 0 起，生产环境建议配固定 key。
     #[serde(default)]
     pub flashbots_auth_key: Option<String>,
+    /// 发送通道选择，见 [`SenderConfig`]。
+    #[serde(default)]
+    pub sender: Option<SenderConfig>,
 }
 
-impl AppConfigBase {
-    /// 解析 `ed25519_seed` 为 32 字节。支持可选的 `0x` 前缀。
+/// `sender` 配置段：
+///
+/// ```json
+/// { "type": "rpc" }
+/// { "type": "private", "relay_url": "https://rpc.flashbots.net/fast", "block_window": 25,
+///   "preferences": { "fast": true }, "fallback": "public_on_error" }
+/// { "type": "flashbots", "builders": [{ "name": "flashbots", "url": "rpc.flashbots.net" }],
+///   "block_window": 3, "preflight": { "min_coinbase_diff": "1000000000000000" },
+///   "bundle": { "refund_percent": 90 } }
+/// { "type": "mev_share", "block_window": 5,
+///   "privacy": { "hints": ["hash", "calldata"], "builders": ["flashbots"] } }
+/// { "type": "record", "path": "./signed.jsonl" }
+/// ```
+///
+/// builder 的 auth / 超时 / 重试字段见 [`BuilderEndpoint`]。
+///
+/// relay / builder 的 auth 签名 key 自动取 `flashbots_auth_key`。
+#[derive(Debug, Clone, Deserialize)]
+pub struct SenderConfig {
+    #[serde(flatten)]
+    pub kind: SenderKind,
+    #[serde(default)]
+    pub fallback: FallbackPolicy,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SenderKind {
+    /// 公共 mempool。`url` 不填用 `rpc_url`。
+    Rpc {
+        #[serde(default)]
+        url: Option<String>,
+    },
+    /// 同时向多个公共 RPC 广播，任意一个接受即成功。
+    Fanout { urls: Vec<String> },
+    /// `eth_sendPrivateTransaction`。`relay_url` 不填用 Flashbots 默认 relay。
+    Private {
+        #[serde(default)]
+        relay_url: Option<String>,
+        #[serde(default)]
+        block_window: Option<u64>,
+        /// Protect `preferences`（fast / privacy / refund）。
+        #[serde(default)]
+        preferences: Option<PrivateTxPreferences>,
+    },
+    /// `eth_sendBundle` 直接 fan-out 到 builder。`builders` 不填用内置列表。
+    /// 配了 `preflight` 则发送前先经 `relay_url`（默认 Flashbots relay）做 `eth_callBundle`。
+    Flashbots {
+        #[serde(default)]
+        builders: Option<Vec<BuilderEndpoint>>,
+        #[serde(default)]
+        block_window: Option<u64>,
+        #[serde(default)]
+        relay_url: Option<String>,
+        #[serde(default)]
+        preflight: Option<BundlePreflight>,
+        /// `eth_sendBundle` 的可选参数（revertingTxHashes / refund 等）。
+        #[serde(default)]
+        bundle: Option<Box<BundleOptions>>,
+    },
+    /// MEV-Share `mev_sendBundle`，经 `relay_url`（默认 Flashbots relay）转发。
+    MevShare {
+        #[serde(default)]
+        relay_url: Option<String>,
+        #[serde(default)]
+        block_window: Option<u64>,
+        #[serde(default)]
+        privacy: Option<Privacy>,
+        #[serde(default)]
+        validity: Option<Validity>,
+    },
+    /// 不广播，签好的交易追加写入 `path`（JSONL），之后用
+    /// [`crate::sender::record::replay`] 经真实通道发出。不能配 `fallback`。
+    Record { path: String },
+}
+
+/// 主通道失败时的处理。
+///
+/// ```json
+/// "fallback": "public_on_error"
+/// "fallback": { "escalate": { "after_blocks": 3, "public_rpcs": ["https://eth.llamarpc.com"] } }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackPolicy {
+    /// 直接返回错误。
+    #[default]
+    None,
+    /// 主通道返回错误时改用 `rpc_url` 公共广播（会暴露到 mempool）。
+    PublicOnError,
+    /// 主通道发送失败或 `after_blocks` 个块内没上链时公开广播（`rpc_url` +
+    /// `public_rpcs` 并发），见 [`EscalatingSender`](crate::sender::EscalatingSender)。
+    /// 不会重签加价，需要加价请在代码里用 `with_resign`。
+    Escalate {
+        #[serde(default)]
+        after_blocks: Option<u64>,
+        #[serde(default)]
+        public_rpcs: Vec<String>,
+    },
+}
+
+/// `signer` 配置段，按 `type` 区分：
+///
+/// ```json
+/// { "type": "remote", "url": "https://signer.example.com", "project": "p", "seed": "0x..", "account_index": 0,
+///   "timeout_ms": 10000, "retries": 2, "sync_server_time": true, "ca_cert": "./ca.pem", "client_cert": "./client.pem" }
+/// { "type": "local", "key_env": "BOT_PRIVATE_KEY" }
+/// { "type": "keystore", "path": "./key.json", "password_env": "KEYSTORE_PASSWORD" }
+/// { "type": "mnemonic", "phrase_env": "BOT_MNEMONIC", "index": 2 }
+/// { "type": "local", "key_env": "BOT_PRIVATE_KEY", "policy": "./policy.json" }
+/// ```
+///
+/// 本地三种（local / keystore / mnemonic）只适合非生产 bot，私钥不经过 rule.js 审核，
+/// 可以配 `policy` 在本地拦一道。
+#[derive(Debug, Clone, Deserialize)]
+pub struct SignerConfig {
+    #[serde(flatten)]
+    pub kind: SignerKind,
+    /// 签名前的本地策略：策略 JSON 文件路径，或直接内联的 [`TxPolicy`]。
+    /// 配了则签名器包一层 [`PolicySigner`]。
+    #[serde(default)]
+    pub policy: Option<PolicyConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerKind {
+    Remote(RemoteSignerConfig),
+    /// 从环境变量读 hex 私钥。
+    Local { key_env: String },
+    Keystore(KeystoreConfig),
+    Mnemonic(MnemonicConfig),
+}
+
+/// `signer.policy`：文件路径或内联策略。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum PolicyConfig {
+    Path(String),
+    Inline(Box<TxPolicy>),
+}
+
+impl PolicyConfig {
+    pub fn load(&self) -> Result<TxPolicy> {
+        match self {
+            Self::Path(path) => load_json(path),
+            Self::Inline(policy) => Ok((**policy).clone()),
+        }
+    }
+}
+
+/// cs-signer 远程签名配置。
+///
+/// 传输相关字段都可省略，默认值见 [`RemoteSignerOptions`]。
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RemoteSignerConfig {
+    pub url: String,
+    pub project: String,
+    /// ed25519 认证 seed（32 字节 hex，可带 `0x`）。
+    pub seed: String,
+    #[serde(default)]
+    pub account_index: i64,
+    /// 单次请求超时（毫秒）。
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// 失败后的重试次数。
+    #[serde(default)]
+    pub retries: Option<u32>,
+    /// 用签名服务的 `Date` header 校正认证时间戳。
+    #[serde(default)]
+    pub sync_server_time: bool,
+    /// 额外信任的 CA 证书 PEM 文件。
+    #[serde(default)]
+    pub ca_cert: Option<String>,
+    /// mTLS 客户端证书 PEM 文件（证书链 + 私钥）。
+    #[serde(default)]
+    pub client_cert: Option<String>,
+}
+
+impl RemoteSignerConfig {
+    /// 解析 `seed` 为 32 字节。支持可选的 `0x` 前缀。
     pub fn seed_bytes(&self) -> Result<[u8; 32]> {
-        let hex = self
-            .ed25519_seed
-            .strip_prefix("0x")
-            .unwrap_or(&self.ed25519_seed);
-        let bytes = alloy::hex::decode(hex).wrap_err("ed25519_seed must be valid hex")?;
+        let hex = self.seed.strip_prefix("0x").unwrap_or(&self.seed);
+        let bytes = alloy::hex::decode(hex).wrap_err("ed25519 seed must be valid hex")?;
         bytes
             .try_into()
-            .map_err(|v: Vec<u8>| eyre::eyre!("ed25519_seed must be 32 bytes, got {}", v.len()))
+            .map_err(|v: Vec<u8>| eyre::eyre!("ed25519 seed must be 32 bytes, got {}", v.len()))
+    }
+
+    /// 按配置组装 [`RemoteSignerOptions`]，会读取证书文件。
+    pub fn options(&self) -> Result<RemoteSignerOptions> {
+        let read = |path: &str, what: &str| {
+            std::fs::read(path).wrap_err_with(|| format!("failed to read signer {what}: {path}"))
+        };
+        let mut options = RemoteSignerOptions::default();
+        if let Some(ms) = self.timeout_ms {
+            options.timeout = std::time::Duration::from_millis(ms);
+        }
+        if let Some(retries) = self.retries {
+            options.retries = retries;
+        }
+        options.sync_server_time = self.sync_server_time;
+        options.ca_cert_pem = self.ca_cert.as_deref().map(|p| read(p, "CA cert")).transpose()?;
+        options.client_identity_pem = self
+            .client_cert
+            .as_deref()
+            .map(|p| read(p, "client cert"))
+            .transpose()?;
+        Ok(options)
+    }
+
+    pub async fn build_signer(&self) -> Result<RemoteSigner> {
+        RemoteSigner::with_options(
+            self.url.clone(),
+            self.project.clone(),
+            self.seed_bytes()?,
+            self.account_index,
+            self.options()?,
+        )
+        .await
+    }
+}
+
+/// Web3 Secret Storage keystore（geth / `cast wallet import` 产出的 JSON）。
+///
+/// 密码只能从环境变量或文件读，config 里不放明文。
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeystoreConfig {
+    pub path: String,
+    /// 存密码的环境变量名。
+    #[serde(default)]
+    pub password_env: Option<String>,
+    /// 存密码的文件路径（首尾空白会被去掉）。
+    #[serde(default)]
+    pub password_file: Option<String>,
+}
+
+impl KeystoreConfig {
+    /// 读密码并解密出 `LocalSigner`。
+    pub fn build_signer(&self) -> Result<LocalSigner> {
+        let password = read_secret(
+            self.password_env.as_deref(),
+            self.password_file.as_deref(),
+            "keystore password",
+        )?;
+        let signer = LocalSigner::from_keystore(&self.path, &password)?;
+        tracing::info!("Signer:     {} (keystore {})", signer.address(), self.path);
+        Ok(signer)
+    }
+}
+
+/// BIP-39 助记词 + BIP-44 派生（`m/44'/60'/0'/0/{index}`）。
+///
+/// 助记词和 passphrase 同样只从环境变量或文件读。词表和 checksum 会校验；
+/// 填 `address` 可额外核对 index / passphrase 是否配对。
+#[derive(Debug, Clone, Deserialize)]
+pub struct MnemonicConfig {
+    #[serde(default)]
+    pub phrase_env: Option<String>,
+    #[serde(default)]
+    pub phrase_file: Option<String>,
+    /// 可选 BIP-39 passphrase 所在的环境变量名。
+    #[serde(default)]
+    pub passphrase_env: Option<String>,
+    #[serde(default)]
+    pub index: u32,
+    /// 期望派生出的地址，不一致直接报错。
+    #[serde(default)]
+    pub address: Option<Address>,
+}
+
+impl MnemonicConfig {
+    /// 读助记词并派生出 `LocalSigner`，配置了 `address` 时核对。
+    pub fn build_signer(&self) -> Result<LocalSigner> {
+        let phrase = read_secret(
+            self.phrase_env.as_deref(),
+            self.phrase_file.as_deref(),
+            "mnemonic",
+        )?;
+        let passphrase = match self.passphrase_env.as_deref() {
+            Some(var) => std::env::var(var)
+                .wrap_err_with(|| format!("mnemonic passphrase env {var} not set"))?,
+            None => String::new(),
+        };
+        let signer = LocalSigner::from_mnemonic(&phrase, &passphrase, self.index)?;
+        if let Some(expected) = self.address {
+            eyre::ensure!(
+                signer.address() == expected,
+                "mnemonic index {} derives {}, config expects {expected}",
+                self.index,
+                signer.address()
+            );
+        }
+        tracing::info!("Signer:     {} (mnemonic index {})", signer.address(), self.index);
+        Ok(signer)
+    }
+}
+
+impl AppConfigBase {
+    /// 远程签名配置：`signer` 段为 remote 时取之，否则回退旧式三件套。
+    pub fn remote_signer_config(&self) -> Result<RemoteSignerConfig> {
+        if let Some(signer) = &self.signer {
+            return match &signer.kind {
+                SignerKind::Remote(r) => Ok(r.clone()),
+                _ => Err(eyre::eyre!("config.signer is not of type remote")),
+            };
+        }
+        match (&self.signer_url, &self.signer_project, &self.ed25519_seed) {
+            (Some(url), Some(project), Some(seed)) => Ok(RemoteSignerConfig {
+                url: url.clone(),
+                project: project.clone(),
+                seed: seed.clone(),
+                ..Default::default()
+            }),
+            _ => Err(eyre::eyre!(
+                "signer missing in config (set `signer` or signer_url / signer_project / ed25519_seed)"
+            )),
+        }
+    }
+
+    /// 解析远程签名 ed25519 seed 为 32 字节。支持可选的 `0x` 前缀。
+    pub fn seed_bytes(&self) -> Result<[u8; 32]> {
+        self.remote_signer_config()?.seed_bytes()
     }
 
     /// 构造 HTTP provider（AnyNetwork、erased 为 `DynProvider`）。
@@ -80,16 +410,155 @@ impl AppConfigBase {
             .erased())
     }
 
-    /// 构造 `RemoteSigner`，`account_index = 0`。
+    /// 构造 `RemoteSigner`（`account_index` 取 `signer.account_index`，旧式配置为 0）。
     pub async fn build_remote_signer(&self) -> Result<RemoteSigner> {
-        let seed = self.seed_bytes()?;
-        RemoteSigner::new(
-            self.signer_url.clone(),
-            self.signer_project.clone(),
-            seed,
-            0,
-        )
-        .await
+        self.remote_signer_config()?.build_signer().await
+    }
+
+    /// 按 `signer` 段（或旧式三件套）构造签名器，配了 `policy` 时包一层 [`PolicySigner`]。
+    pub async fn build_signer(&self) -> Result<AnySigner> {
+        let Some(signer) = &self.signer else {
+            return Ok(self.build_remote_signer().await?.into());
+        };
+        let inner: AnySigner = match &signer.kind {
+            SignerKind::Remote(r) => r.build_signer().await?.into(),
+            SignerKind::Local { key_env } => {
+                let key = read_secret(Some(key_env), None, "private key")?;
+                let key: PrivateKeySigner = key
+                    .trim()
+                    .parse()
+                    .wrap_err_with(|| format!("env {key_env} is not a valid private key"))?;
+                tracing::info!("Signer:     {} (local key from {key_env})", key.address());
+                LocalSigner::new(key).into()
+            }
+            SignerKind::Keystore(ks) => ks.build_signer()?.into(),
+            SignerKind::Mnemonic(m) => m.build_signer()?.into(),
+        };
+        let Some(policy) = &signer.policy else {
+            return Ok(inner);
+        };
+        let policy = policy.load().wrap_err("failed to load signer policy")?;
+        tracing::info!(
+            "Policy:     {} targets, {} spend limits",
+            policy.allowed_targets.len(),
+            policy.spend_limits.len()
+        );
+        Ok(PolicySigner::new(inner, policy).into())
+    }
+
+    /// 按 `sender` 段构造发送器；不填时等价于 `{ "type": "rpc" }`。
+    pub fn build_sender(&self) -> Result<AnySender> {
+        let Some(cfg) = &self.sender else {
+            return Ok(RpcSender::new(&self.rpc_url)?.into());
+        };
+        let sender: AnySender = match &cfg.kind {
+            SenderKind::Rpc { url } => {
+                RpcSender::new(url.as_deref().unwrap_or(&self.rpc_url))?.into()
+            }
+            SenderKind::Fanout { urls } => FanoutSender::new(urls)?.into(),
+            SenderKind::Private {
+                relay_url,
+                block_window,
+                preferences,
+            } => {
+                let auth = self.resolve_flashbots_auth_signer()?;
+                let mut s = match relay_url {
+                    Some(url) => PrivateSender::with_relay(auth, &self.rpc_url, url)?,
+                    None => PrivateSender::new(auth, &self.rpc_url)?,
+                };
+                if let Some(n) = block_window {
+                    s = s.with_block_window(*n);
+                }
+                if let Some(p) = preferences {
+                    s = s.with_preferences(p.clone());
+                }
+                s.into()
+            }
+            SenderKind::MevShare {
+                relay_url,
+                block_window,
+                privacy,
+                validity,
+            } => {
+                let auth = self.resolve_flashbots_auth_signer()?;
+                let mut s = MevShareSender::new(auth, &self.rpc_url)?;
+                if let Some(url) = relay_url {
+                    s = s.with_relay(url);
+                }
+                if let Some(n) = block_window {
+                    s = s.with_block_window(*n);
+                }
+                if let Some(p) = privacy {
+                    s = s.with_privacy(p.clone());
+                }
+                if let Some(v) = validity {
+                    s = s.with_validity(v.clone());
+                }
+                s.into()
+            }
+            SenderKind::Record { path } => {
+                // fallback 会把本该只记录的交易公开广播出去
+                eyre::ensure!(
+                    cfg.fallback == FallbackPolicy::None,
+                    "sender.fallback must be none for `record` (got {:?})",
+                    cfg.fallback
+                );
+                RecordSender::new(path)?.into()
+            }
+            SenderKind::Flashbots {
+                builders,
+                block_window,
+                relay_url,
+                preflight,
+                bundle,
+            } => {
+                let auth = self.resolve_flashbots_auth_signer()?;
+                let mut s = FlashbotsSender::new(auth, &self.rpc_url)?;
+                if let Some(b) = builders {
+                    eyre::ensure!(!b.is_empty(), "sender.builders must not be empty");
+                    s = s.with_builders(b.clone());
+                }
+                if let Some(n) = block_window {
+                    s = s.with_block_window(*n);
+                }
+                if let Some(url) = relay_url {
+                    s = s.with_relay(url);
+                }
+                if let Some(p) = preflight {
+                    s = s.with_preflight(p.clone());
+                }
+                if let Some(o) = bundle {
+                    s = s.with_bundle_options(o.as_ref().clone());
+                }
+                s.check_options()?;
+                s.into()
+            }
+        };
+        tracing::info!("Sender:     {:?} (fallback {:?})", cfg.kind, cfg.fallback);
+        Ok(match &cfg.fallback {
+            FallbackPolicy::None => sender,
+            FallbackPolicy::PublicOnError => {
+                sender.with_public_fallback(RpcSender::new(&self.rpc_url)?)
+            }
+            FallbackPolicy::Escalate {
+                after_blocks,
+                public_rpcs,
+            } => {
+                let public: AnySender = if public_rpcs.is_empty() {
+                    RpcSender::new(&self.rpc_url)?.into()
+                } else {
+                    let urls: Vec<String> = std::iter::once(self.rpc_url.clone())
+                        .chain(public_rpcs.iter().cloned())
+                        .collect();
+                    FanoutSender::new(&urls)?.into()
+                };
+                let mut s = EscalatingSender::new(sender, public, &self.rpc_url)?;
+                if let Some(n) = after_blocks {
+                    s = s.with_after_blocks(*n);
+                }
+                s.into()
+            }
+        })
     }
 
     /// gas 决策：`gas_price_gwei > 0` 用配置值，否则 `basefee × 1.5`。
@@ -169,6 +638,19 @@ pub fn load_json<T: for<'de> Deserialize<'de>>(path: &str) -> Result<T> {
         .wrap_err_with(|| format!("failed to parse config file: {path}"))
 }
 
+/// 从环境变量（优先）或文件读取密钥类配置；两者都没配则报错。
+fn read_secret(env: Option<&str>, file: Option<&str>, what: &str) -> Result<String> {
+    if let Some(var) = env {
+        return std::env::var(var).wrap_err_with(|| format!("{what} env {var} not set"));
+    }
+    if let Some(path) = file {
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read {what} file: {path}"))?;
+        return Ok(content.trim().to_string());
+    }
+    Err(eyre::eyre!("{what}: neither env nor file configured"))
+}
+
 /// 基于 `eth_feeHistory` 返回**下一个 block 的建议 basefee**（wei / gas）。
 ///
 /// 本函数只给出原始数据。怎么把它转成 `(max_fee, priority_fee)` 由下游按场景决策：
@@ -187,3 +669,183 @@ pub async fn estimate_gas_fee(provider: &DynProvider<AnyNetwork>) -> Result<u128
         .ok_or_else(|| eyre::eyre!("fee_history returned empty base_fee_per_gas"))?;
     Ok(next_base)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    #[test]
+    fn parses_tagged_and_legacy_signer_config() {
+        let base: AppConfigBase = serde_json::from_value(serde_json::json!({
+            "rpc_url": "http://localhost:8545",
+            "signer": { "type": "remote", "url": "http://s", "project": "p", "seed": "0x01", "account_index": 3 },
+        }))
+        .unwrap();
+        let remote = base.remote_signer_config().unwrap();
+        assert_eq!((remote.url.as_str(), remote.account_index), ("http://s", 3));
+        let options = remote.options().unwrap();
+        assert_eq!(options.retries, RemoteSignerOptions::default().retries);
+        assert!(!options.sync_server_time);
+
+        let remote: RemoteSignerConfig = serde_json::from_value(serde_json::json!({
+            "url": "https://s", "project": "p", "seed": "0x01",
+            "timeout_ms": 1500, "retries": 0, "sync_server_time": true,
+        }))
+        .unwrap();
+        let options = remote.options().unwrap();
+        assert_eq!(options.timeout, std::time::Duration::from_millis(1500));
+        assert_eq!((options.retries, options.sync_server_time), (0, true));
+
+        let base: AppConfigBase = serde_json::from_value(serde_json::json!({
+            "rpc_url": "http://localhost:8545",
+            "signer_url": "http://legacy",
+            "signer_project": "p",
+            "ed25519_seed": format!("0x{}", "11".repeat(32)),
+        }))
+        .unwrap();
+        assert_eq!(base.remote_signer_config().unwrap().account_index, 0);
+        assert_eq!(base.seed_bytes().unwrap(), [0x11; 32]);
+
+        let base: AppConfigBase = serde_json::from_value(serde_json::json!({
+            "rpc_url": "http://localhost:8545",
+            "signer": { "type": "local", "key_env": "BOT_KEY" },
+        }))
+        .unwrap();
+        assert!(matches!(
+            base.signer.as_ref().map(|s| &s.kind),
+            Some(SignerKind::Local { .. })
+        ));
+        assert!(base.remote_signer_config().is_err());
+    }
+
+    #[test]
+    fn builds_sender_from_config() {
+        let base: AppConfigBase = serde_json::from_value(serde_json::json!({
+            "rpc_url": "http://localhost:8545",
+            "flashbots_auth_key": format!("0x{}", "22".repeat(32)),
+            "sender": {
+                "type": "flashbots",
+                "builders": [
+                    { "name": "local", "url": "http://127.0.0.1:1" },
+                    {
+                        "name": "bloXroute",
+                        "url": "rpc-builder.blxrbdn.com",
+                        "auth": { "type": "header", "name": "Authorization", "value_env": "BLXR_AUTH" },
+                        "timeout_ms": 2000,
+                        "retries": 1,
+                    },
+                ],
+                "block_window": 2,
+                "fallback": "public_on_error",
+            },
+        }))
+        .unwrap();
+        let sender = base.build_sender().unwrap();
+        let AnySender::PublicOnError { primary, .. } = sender else {
+            panic!("expected public fallback wrapper");
+        };
+        assert!(matches!(*primary, AnySender::Flashbots(_)));
+
+        let base: AppConfigBase = serde_json::from_value(serde_json::json!({
+            "rpc_url": "http://localhost:8545",
+            "sender": {
+                "type": "private",
+                "fallback": { "escalate": { "after_blocks": 2, "public_rpcs": ["http://127.0.0.1:1"] } },
+            },
+        }))
+        .unwrap();
+        assert!(matches!(base.build_sender().unwrap(), AnySender::Escalating(_)));
+
+        let base: AppConfigBase =
+            serde_json::from_value(serde_json::json!({ "rpc_url": "http://localhost:8545" }))
+                .unwrap();
+        assert!(matches!(base.build_sender().unwrap(), AnySender::Rpc(_)));
+
+        let path = std::env::temp_dir().join(format!("flashseal-record-{}", std::process::id()));
+        let record = |fallback: serde_json::Value| -> AppConfigBase {
+            serde_json::from_value(serde_json::json!({
+                "rpc_url": "http://localhost:8545",
+                "sender": { "type": "record", "path": path.to_str().unwrap(), "fallback": fallback },
+            }))
+            .unwrap()
+        };
+        let err = record(serde_json::json!("public_on_error"))
+            .build_sender()
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("fallback must be none"), "{err}");
+        assert!(!path.exists());
+        let sender = record(serde_json::json!("none")).build_sender().unwrap();
+        assert!(sender.is_record());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn builds_mnemonic_signer_from_file() {
+        let path = std::env::temp_dir().join(format!("flashseal-mnemonic-{}", std::process::id()));
+        std::fs::write(&path, "abandon ".repeat(11) + "about\n").unwrap();
+        let base: AppConfigBase = serde_json::from_value(serde_json::json!({
+            "rpc_url": "http://localhost:8545",
+            "signer": {
+                "type": "mnemonic",
+                "phrase_file": path.to_str().unwrap(),
+                "address": "0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+            },
+        }))
+        .unwrap();
+        let signer = base.build_signer().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(signer, AnySigner::Local(_)));
+        assert_eq!(signer.address(), address!("9858EfFD232B4033E47d90003D41EC34EcaEda94"));
+        assert!(signer.sign_message(b"hello").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn wraps_signer_in_policy() {
+        let dir = std::env::temp_dir();
+        let pid = std::process::id();
+        let phrase = dir.join(format!("flashseal-policy-mnemonic-{pid}"));
+        std::fs::write(&phrase, "abandon ".repeat(11) + "about\n").unwrap();
+        let policy_file = dir.join(format!("flashseal-policy-{pid}.json"));
+        let target = address!("1111111111111111111111111111111111111111");
+        std::fs::write(
+            &policy_file,
+            serde_json::json!({ "allowed_targets": [target] }).to_string(),
+        )
+        .unwrap();
+        let config = |policy: serde_json::Value| -> AppConfigBase {
+            serde_json::from_value(serde_json::json!({
+                "rpc_url": "http://localhost:8545",
+                "signer": { "type": "mnemonic", "phrase_file": phrase.to_str().unwrap(), "policy": policy },
+            }))
+            .unwrap()
+        };
+        let tx = |to| alloy::consensus::TxEip1559 {
+            chain_id: 1,
+            to: alloy::primitives::TxKind::Call(to),
+            ..Default::default()
+        };
+        let stranger = address!("2222222222222222222222222222222222222222");
+
+        for policy in [
+            serde_json::json!(policy_file.to_str().unwrap()),
+            serde_json::json!({ "allowed_targets": [target] }),
+        ] {
+            let signer = config(policy).build_signer().await.unwrap();
+            let AnySigner::Policy(inner) = &signer else {
+                panic!("expected policy wrapper");
+            };
+            assert_eq!(inner.policy().allowed_targets, vec![target]);
+            assert!(signer.sign(tx(target)).await.is_ok());
+            let err = signer.sign(tx(stranger)).await.err().unwrap();
+            assert_eq!(
+                err.downcast_ref::<crate::PolicyViolation>(),
+                Some(&crate::PolicyViolation::TargetNotAllowed { to: stranger })
+            );
+            assert!(signer.sign_message(b"hello").await.is_err());
+        }
+        std::fs::remove_file(&phrase).unwrap();
+        std::fs::remove_file(&policy_file).unwrap();
+    }
+}
@@ -80,6 +80,17 @@ fn to_call_data(req: &TxRequest) -> CallData {
     }
 }
 
+/// 解出 `execTransaction(s)` calldata 里的 inner call `(to, value, data)`。
+/// 不是这两个函数时返回 `None`。
+pub(crate) fn decode_inner_calls(input: &[u8]) -> Option<Vec<(Address, U256, Bytes)>> {
+    let calls = if let Ok(call) = execTransactionCall::abi_decode(input) {
+        vec![call.callData]
+    } else {
+        execTransactionsCall::abi_decode(input).ok()?.callDataList
+    };
+    Some(calls.into_iter().map(|c| (c.to, c.value, c.data)).collect())
+}
+
 impl TxBuilder for CoboSafeBuilder {
     fn build_txs(
         &self,
@@ -1,8 +1,11 @@
 mod cobosafe;
 mod direct;
+pub mod user_op;
 
 pub use cobosafe::CoboSafeBuilder;
+pub(crate) use cobosafe::decode_inner_calls;
 pub use direct::DirectBuilder;
+pub use user_op::{AccountKind, UserOpBuilder, UserOperation};
 
 use alloy::{
     consensus::TxEip1559,
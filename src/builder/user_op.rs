@@ -0,0 +1,525 @@
+//! ERC-4337 UserOperation（EntryPoint v0.7 packed 格式）构建、hash 与签名。
+//!
+//! 流程：`TxRequest` → [`UserOpBuilder::build_user_op`]（按账户类型编码 callData）
+//! → bundler 估 gas 后 [`UserOperation::with_gas_estimate`] → [`UserOpBuilder::sign`]
+//! （owner 是任意 [`TxSigner`]）→ fork 上 [`crate::simulator::user_op::handle_ops`]
+//! 验证 / [`crate::sender::BundlerSender`] 提交。
+//!
+//! hash 规则见 EntryPoint v0.7 `getUserOpHash`：
+//! `keccak256(abi.encode(keccak256(packUserOp(op)), entryPoint, chainId))`。
+
+use alloy::{
+    dyn_abi::TypedData,
+    primitives::{
+        address, aliases::U48, eip191_hash_message, keccak256, Address, Bytes, B256, U256,
+    },
+    rpc::types::erc4337::PackedUserOperation as RpcPackedUserOperation,
+    sol,
+    sol_types::{eip712_domain, SolCall, SolValue},
+};
+use eyre::Result;
+
+use super::TxRequest;
+use crate::TxSigner;
+
+/// EntryPoint v0.7 的 canonical 部署地址（所有 EVM 链相同）。
+pub const ENTRY_POINT_V07: Address = address!("0000000071727De22E5E9d8BAf0edAc6f37da032");
+
+/// `build_user_op` 未估 gas 时填的默认 verificationGasLimit。
+const DEFAULT_VERIFICATION_GAS_LIMIT: u128 = 150_000;
+
+/// `build_user_op` 未估 gas 时填的默认 preVerificationGas。
+const DEFAULT_PRE_VERIFICATION_GAS: u64 = 50_000;
+
+sol! {
+    /// EntryPoint v0.7 链上 ABI 的 PackedUserOperation。
+    #[derive(Debug, PartialEq, Eq)]
+    struct PackedUserOperation {
+        address sender;
+        uint256 nonce;
+        bytes initCode;
+        bytes callData;
+        bytes32 accountGasLimits;
+        uint256 preVerificationGas;
+        bytes32 gasFees;
+        bytes paymasterAndData;
+        bytes signature;
+    }
+
+    // EntryPoint v0.7
+    function handleOps(PackedUserOperation[] ops, address beneficiary) external;
+    function getNonce(address sender, uint192 key) external view returns (uint256 nonce);
+
+    // SimpleAccount（eth-infinitism v0.7）
+    function execute(address dest, uint256 value, bytes func) external;
+    function executeBatch(address[] dest, uint256[] value, bytes[] func) external;
+
+    // Safe4337Module v0.3：经 Safe fallback handler 转发
+    function executeUserOp(address to, uint256 value, bytes data, uint8 operation) external;
+
+    /// Safe4337Module 的 EIP-712 签名结构。
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct SafeOp {
+        address safe;
+        uint256 nonce;
+        bytes initCode;
+        bytes callData;
+        uint128 verificationGasLimit;
+        uint128 callGasLimit;
+        uint256 preVerificationGas;
+        uint128 maxPriorityFeePerGas;
+        uint128 maxFeePerGas;
+        bytes paymasterAndData;
+        uint48 validAfter;
+        uint48 validUntil;
+        address entryPoint;
+    }
+}
+
+/// 未打包的 v0.7 UserOperation。字段与 bundler JSON-RPC 一一对应，
+/// 链上格式用 [`UserOperation::pack`]。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserOperation {
+    pub sender: Address,
+    pub nonce: U256,
+    /// 账户尚未部署时的 factory；`None` 表示账户已存在。
+    pub factory: Option<Address>,
+    pub factory_data: Bytes,
+    pub call_data: Bytes,
+    pub call_gas_limit: u128,
+    pub verification_gas_limit: u128,
+    pub pre_verification_gas: U256,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    /// 代付 gas 的 paymaster；`None` 表示账户自付。
+    pub paymaster: Option<Address>,
+    pub paymaster_verification_gas_limit: u128,
+    pub paymaster_post_op_gas_limit: u128,
+    pub paymaster_data: Bytes,
+    pub signature: Bytes,
+}
+
+/// bundler `eth_estimateUserOperationGas` 的结果。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UserOpGasEstimate {
+    pub pre_verification_gas: U256,
+    pub verification_gas_limit: u128,
+    pub call_gas_limit: u128,
+    pub paymaster_verification_gas_limit: Option<u128>,
+    pub paymaster_post_op_gas_limit: Option<u128>,
+}
+
+impl UserOperation {
+    /// `factory ++ factoryData`，无 factory 时为空。
+    pub fn init_code(&self) -> Bytes {
+        match self.factory {
+            Some(f) => [f.as_slice(), &self.factory_data[..]].concat().into(),
+            None => Bytes::new(),
+        }
+    }
+
+    /// `paymaster ++ uint128(pmVerificationGas) ++ uint128(pmPostOpGas) ++ paymasterData`，
+    /// 无 paymaster 时为空。
+    pub fn paymaster_and_data(&self) -> Bytes {
+        match self.paymaster {
+            Some(p) => [
+                p.as_slice(),
+                &self.paymaster_verification_gas_limit.to_be_bytes(),
+                &self.paymaster_post_op_gas_limit.to_be_bytes(),
+                &self.paymaster_data[..],
+            ]
+            .concat()
+            .into(),
+            None => Bytes::new(),
+        }
+    }
+
+    /// 打包成 EntryPoint v0.7 链上 `PackedUserOperation`。
+    pub fn pack(&self) -> PackedUserOperation {
+        PackedUserOperation {
+            sender: self.sender,
+            nonce: self.nonce,
+            initCode: self.init_code(),
+            callData: self.call_data.clone(),
+            accountGasLimits: pack_u128_pair(self.verification_gas_limit, self.call_gas_limit),
+            preVerificationGas: self.pre_verification_gas,
+            gasFees: pack_u128_pair(self.max_priority_fee_per_gas, self.max_fee_per_gas),
+            paymasterAndData: self.paymaster_and_data(),
+            signature: self.signature.clone(),
+        }
+    }
+
+    /// EntryPoint v0.7 `getUserOpHash`（不含 signature）。
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> B256 {
+        let inner = (
+            self.sender,
+            self.nonce,
+            keccak256(self.init_code()),
+            keccak256(&self.call_data),
+            pack_u128_pair(self.verification_gas_limit, self.call_gas_limit),
+            self.pre_verification_gas,
+            pack_u128_pair(self.max_priority_fee_per_gas, self.max_fee_per_gas),
+            keccak256(self.paymaster_and_data()),
+        )
+            .abi_encode();
+        keccak256((keccak256(inner), entry_point, U256::from(chain_id)).abi_encode())
+    }
+
+    /// 用 bundler 估出来的 gas 覆盖对应字段；paymaster 相关字段只在 bundler 返回时覆盖。
+    pub fn with_gas_estimate(mut self, est: &UserOpGasEstimate) -> Self {
+        self.pre_verification_gas = est.pre_verification_gas;
+        self.verification_gas_limit = est.verification_gas_limit;
+        self.call_gas_limit = est.call_gas_limit;
+        if let Some(g) = est.paymaster_verification_gas_limit {
+            self.paymaster_verification_gas_limit = g;
+        }
+        if let Some(g) = est.paymaster_post_op_gas_limit {
+            self.paymaster_post_op_gas_limit = g;
+        }
+        self
+    }
+
+    /// 转成 bundler JSON-RPC 的 v0.7 格式（数值为 hex quantity）。
+    pub fn to_rpc(&self) -> RpcPackedUserOperation {
+        let paymaster = self.paymaster;
+        RpcPackedUserOperation {
+            sender: self.sender,
+            nonce: self.nonce,
+            factory: self.factory,
+            factory_data: self.factory.map(|_| self.factory_data.clone()),
+            call_data: self.call_data.clone(),
+            call_gas_limit: U256::from(self.call_gas_limit),
+            verification_gas_limit: U256::from(self.verification_gas_limit),
+            pre_verification_gas: self.pre_verification_gas,
+            max_fee_per_gas: U256::from(self.max_fee_per_gas),
+            max_priority_fee_per_gas: U256::from(self.max_priority_fee_per_gas),
+            paymaster,
+            paymaster_verification_gas_limit: paymaster
+                .map(|_| U256::from(self.paymaster_verification_gas_limit)),
+            paymaster_post_op_gas_limit: paymaster
+                .map(|_| U256::from(self.paymaster_post_op_gas_limit)),
+            paymaster_data: paymaster.map(|_| self.paymaster_data.clone()),
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+/// `bytes32(uint128(hi) << 128 | uint128(lo))`
+fn pack_u128_pair(hi: u128, lo: u128) -> B256 {
+    let mut out = [0u8; 32];
+    out[..16].copy_from_slice(&hi.to_be_bytes());
+    out[16..].copy_from_slice(&lo.to_be_bytes());
+    B256::from(out)
+}
+
+/// 智能账户类型：决定 callData 编码和签名格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountKind {
+    /// eth-infinitism SimpleAccount 及兼容实现（`execute` / `executeBatch`，
+    /// owner 对 userOpHash 做 EIP-191 personal_sign）。
+    SimpleAccount,
+    /// 挂了 Safe4337Module 的 Safe（`executeUserOp`，owners 对 `SafeOp` 做 EIP-712 签名，
+    /// 签名前缀 `validAfter ++ validUntil`）。
+    Safe4337 {
+        module: Address,
+        valid_after: u64,
+        valid_until: u64,
+    },
+}
+
+/// UserOperation builder：`TxRequest` → 未签名 [`UserOperation`]，并负责按账户类型签名。
+///
+/// 与 [`super::TxBuilder`] 一样只做构建，nonce（`EntryPoint.getNonce`）和 gas
+/// 由调用方提供。
+pub struct UserOpBuilder {
+    sender: Address,
+    entry_point: Address,
+    chain_id: u64,
+    account: AccountKind,
+}
+
+impl UserOpBuilder {
+    /// 默认 EntryPoint v0.7。
+    pub fn new(sender: Address, chain_id: u64, account: AccountKind) -> Self {
+        Self::with_entry_point(sender, ENTRY_POINT_V07, chain_id, account)
+    }
+
+    pub fn with_entry_point(
+        sender: Address,
+        entry_point: Address,
+        chain_id: u64,
+        account: AccountKind,
+    ) -> Self {
+        Self { sender, entry_point, chain_id, account }
+    }
+
+    pub fn entry_point(&self) -> Address {
+        self.entry_point
+    }
+
+    /// 把 `requests` 编码成账户的 callData，`call_gas_limit` = 各 request gas_limit 之和。
+    ///
+    /// verification / preVerification gas 先填保守默认值，发 bundler 前应该用
+    /// `eth_estimateUserOperationGas` 的结果覆盖（[`UserOperation::with_gas_estimate`]）。
+    ///
+    /// Safe4337 一笔 UserOperation 只支持单个 request；批量请先用 MultiSend 包一层。
+    pub fn build_user_op(
+        &self,
+        requests: &[TxRequest],
+        nonce: U256,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    ) -> Result<UserOperation> {
+        eyre::ensure!(!requests.is_empty(), "requests must not be empty");
+
+        let call_data = match self.account {
+            AccountKind::SimpleAccount if requests.len() == 1 => {
+                let r = &requests[0];
+                executeCall { dest: r.to, value: r.value, func: r.data.clone() }.abi_encode()
+            }
+            AccountKind::SimpleAccount => executeBatchCall {
+                dest: requests.iter().map(|r| r.to).collect(),
+                value: requests.iter().map(|r| r.value).collect(),
+                func: requests.iter().map(|r| r.data.clone()).collect(),
+            }
+            .abi_encode(),
+            AccountKind::Safe4337 { .. } => {
+                eyre::ensure!(
+                    requests.len() == 1,
+                    "Safe4337 user operation takes exactly one request (wrap batches with MultiSend)"
+                );
+                let r = &requests[0];
+                // operation=0: CALL
+                executeUserOpCall { to: r.to, value: r.value, data: r.data.clone(), operation: 0 }
+                    .abi_encode()
+            }
+        };
+
+        Ok(UserOperation {
+            sender: self.sender,
+            nonce,
+            call_data: call_data.into(),
+            call_gas_limit: requests.iter().map(|r| r.gas_limit as u128).sum(),
+            verification_gas_limit: DEFAULT_VERIFICATION_GAS_LIMIT,
+            pre_verification_gas: U256::from(DEFAULT_PRE_VERIFICATION_GAS),
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..Default::default()
+        })
+    }
+
+    /// EntryPoint 的 userOpHash（bundler 返回 / `UserOperationEvent` 里的就是它）。
+    pub fn user_op_hash(&self, op: &UserOperation) -> B256 {
+        op.hash(self.entry_point, self.chain_id)
+    }
+
+    /// owner 实际签名的 digest：
+    /// - SimpleAccount：`eip191(userOpHash)`
+    /// - Safe4337：`SafeOp` 的 EIP-712 signing hash（domain = chainId + module）
+    pub fn signing_hash(&self, op: &UserOperation) -> Result<B256> {
+        match self.safe_op_typed_data(op) {
+            Some(typed) => Ok(typed.eip712_signing_hash()?),
+            None => Ok(eip191_hash_message(self.user_op_hash(op))),
+        }
+    }
+
+    /// Safe4337 账户要签的 `SafeOp` typed data；SimpleAccount 返回 `None`。
+    pub fn safe_op_typed_data(&self, op: &UserOperation) -> Option<TypedData> {
+        let AccountKind::Safe4337 { module, valid_after, valid_until } = self.account else {
+            return None;
+        };
+        let safe_op = SafeOp {
+            safe: op.sender,
+            nonce: op.nonce,
+            initCode: op.init_code(),
+            callData: op.call_data.clone(),
+            verificationGasLimit: op.verification_gas_limit,
+            callGasLimit: op.call_gas_limit,
+            preVerificationGas: op.pre_verification_gas,
+            maxPriorityFeePerGas: op.max_priority_fee_per_gas,
+            maxFeePerGas: op.max_fee_per_gas,
+            paymasterAndData: op.paymaster_and_data(),
+            validAfter: U48::from(valid_after),
+            validUntil: U48::from(valid_until),
+            entryPoint: self.entry_point,
+        };
+        let domain = eip712_domain! {
+            chain_id: self.chain_id,
+            verifying_contract: module,
+        };
+        Some(TypedData::from_struct(&safe_op, Some(domain)))
+    }
+
+    /// 由 `owners` 对 op 签名并写入 `signature`。
+    ///
+    /// - SimpleAccount：必须恰好一个 owner，`sign_message(userOpHash)`
+    /// - Safe4337：每个 owner `sign_typed_data(SafeOp)`，按 owner 地址升序拼接 65 字节签名
+    ///   （Safe `checkSignatures` 要求），前缀 `uint48 validAfter ++ uint48 validUntil`
+    ///
+    /// 走的是 `sign_message` / `sign_typed_data`，所以 `RemoteSigner` 也能当 owner。
+    pub async fn sign<S: TxSigner>(
+        &self,
+        mut op: UserOperation,
+        owners: &[&S],
+    ) -> Result<UserOperation> {
+        eyre::ensure!(!owners.is_empty(), "owners must not be empty");
+
+        op.signature = match (self.account, self.safe_op_typed_data(&op)) {
+            (AccountKind::Safe4337 { valid_after, valid_until, .. }, Some(typed)) => {
+                let mut sorted: Vec<&&S> = owners.iter().collect();
+                sorted.sort_by_key(|s| s.address());
+                let mut out = Vec::with_capacity(12 + 65 * sorted.len());
+                out.extend_from_slice(&valid_after.to_be_bytes()[2..]);
+                out.extend_from_slice(&valid_until.to_be_bytes()[2..]);
+                for owner in sorted {
+                    let sig = owner.sign_typed_data(&typed).await?;
+                    out.extend_from_slice(&sig.as_bytes());
+                }
+                Bytes::from(out)
+            }
+            _ => {
+                eyre::ensure!(owners.len() == 1, "SimpleAccount takes exactly one owner");
+                let hash = self.user_op_hash(&op);
+                let sig = owners[0].sign_message(hash.as_slice()).await?;
+                Bytes::from(sig.as_bytes().to_vec())
+            }
+        };
+        Ok(op)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalSigner;
+    use alloy::signers::local::PrivateKeySigner;
+
+    fn sample_op() -> UserOperation {
+        UserOperation {
+            sender: address!("1111111111111111111111111111111111111111"),
+            nonce: U256::from(7),
+            call_data: Bytes::from(vec![0xde, 0xad]),
+            call_gas_limit: 100_000,
+            verification_gas_limit: 200_000,
+            pre_verification_gas: U256::from(50_000),
+            max_fee_per_gas: 30_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pack_places_gas_pairs_high_low() {
+        let packed = sample_op().pack();
+        let gas = packed.accountGasLimits;
+        assert_eq!(u128::from_be_bytes(gas[..16].try_into().unwrap()), 200_000);
+        assert_eq!(u128::from_be_bytes(gas[16..].try_into().unwrap()), 100_000);
+        let fees = packed.gasFees;
+        assert_eq!(u128::from_be_bytes(fees[..16].try_into().unwrap()), 1_000_000_000);
+        assert_eq!(u128::from_be_bytes(fees[16..].try_into().unwrap()), 30_000_000_000);
+        assert!(packed.initCode.is_empty());
+        assert!(packed.paymasterAndData.is_empty());
+    }
+
+    #[test]
+    fn paymaster_and_data_layout() {
+        let pm = address!("2222222222222222222222222222222222222222");
+        let op = UserOperation {
+            paymaster: Some(pm),
+            paymaster_verification_gas_limit: 1,
+            paymaster_post_op_gas_limit: 2,
+            paymaster_data: Bytes::from(vec![0xaa]),
+            ..sample_op()
+        };
+        let pd = op.paymaster_and_data();
+        assert_eq!(pd.len(), 20 + 16 + 16 + 1);
+        assert_eq!(&pd[..20], pm.as_slice());
+        assert_eq!(pd[35], 1);
+        assert_eq!(pd[51], 2);
+        assert_eq!(pd[52], 0xaa);
+    }
+
+    #[test]
+    fn hash_ignores_signature_and_binds_chain() {
+        let op = sample_op();
+        let signed = UserOperation { signature: Bytes::from(vec![1; 65]), ..op.clone() };
+        assert_eq!(op.hash(ENTRY_POINT_V07, 1), signed.hash(ENTRY_POINT_V07, 1));
+        assert_ne!(op.hash(ENTRY_POINT_V07, 1), op.hash(ENTRY_POINT_V07, 10));
+    }
+
+    #[tokio::test]
+    async fn simple_account_signature_recovers_owner() {
+        let owner = LocalSigner::new(PrivateKeySigner::random());
+        let builder = UserOpBuilder::new(sample_op().sender, 1, AccountKind::SimpleAccount);
+        let op = builder.sign(sample_op(), &[&owner]).await.unwrap();
+        let sig = alloy::primitives::Signature::try_from(&op.signature[..]).unwrap();
+        let recovered = sig
+            .recover_address_from_prehash(&builder.signing_hash(&op).unwrap())
+            .unwrap();
+        assert_eq!(recovered, owner.address());
+    }
+
+    #[tokio::test]
+    async fn safe_signatures_are_sorted_and_prefixed() {
+        let a = LocalSigner::new(PrivateKeySigner::random());
+        let b = LocalSigner::new(PrivateKeySigner::random());
+        let account = AccountKind::Safe4337 {
+            module: address!("3333333333333333333333333333333333333333"),
+            valid_after: 0,
+            valid_until: 0x0102,
+        };
+        let builder = UserOpBuilder::new(sample_op().sender, 1, account);
+        let op = builder.sign(sample_op(), &[&a, &b]).await.unwrap();
+        assert_eq!(op.signature.len(), 12 + 65 * 2);
+        assert_eq!(&op.signature[..12], &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2]);
+
+        let digest = builder.signing_hash(&op).unwrap();
+        let first = alloy::primitives::Signature::try_from(&op.signature[12..77]).unwrap();
+        let second = alloy::primitives::Signature::try_from(&op.signature[77..]).unwrap();
+        let r1 = first.recover_address_from_prehash(&digest).unwrap();
+        let r2 = second.recover_address_from_prehash(&digest).unwrap();
+        assert!(r1 < r2);
+    }
+
+    /// 与 `SafeOp` sol struct 的 EIP-712 hash 对照，不经过 `safe_op_typed_data`。
+    #[test]
+    fn safe_op_typed_data_matches_sol_struct_hash() {
+        let module = address!("3333333333333333333333333333333333333333");
+        let account = AccountKind::Safe4337 { module, valid_after: 5, valid_until: 0x0102 };
+        let builder = UserOpBuilder::new(sample_op().sender, 10, account);
+        let op = sample_op();
+        let safe_op = SafeOp {
+            safe: op.sender,
+            nonce: op.nonce,
+            initCode: Bytes::new(),
+            callData: op.call_data.clone(),
+            verificationGasLimit: op.verification_gas_limit,
+            callGasLimit: op.call_gas_limit,
+            preVerificationGas: op.pre_verification_gas,
+            maxPriorityFeePerGas: op.max_priority_fee_per_gas,
+            maxFeePerGas: op.max_fee_per_gas,
+            paymasterAndData: Bytes::new(),
+            validAfter: U48::from(5),
+            validUntil: U48::from(0x0102),
+            entryPoint: ENTRY_POINT_V07,
+        };
+        let domain = eip712_domain! { chain_id: 10, verifying_contract: module, };
+        let expected = alloy::sol_types::SolStruct::eip712_signing_hash(&safe_op, &domain);
+        let typed = builder.safe_op_typed_data(&op).unwrap();
+        assert_eq!(typed.eip712_signing_hash().unwrap(), expected);
+        assert_eq!(builder.signing_hash(&op).unwrap(), expected);
+    }
+
+    #[test]
+    fn safe4337_rejects_batches() {
+        let req = TxRequest {
+            to: Address::ZERO,
+            value: U256::ZERO,
+            data: Bytes::new(),
+            gas_limit: 1,
+        };
+        let account = AccountKind::Safe4337 { module: Address::ZERO, valid_after: 0, valid_until: 0 };
+        let builder = UserOpBuilder::new(Address::ZERO, 1, account);
+        assert!(builder.build_user_op(&[req.clone(), req], U256::ZERO, 1, 1).is_err());
+    }
+}
@@ -1,13 +1,22 @@
 pub mod app;
 pub mod builder;
+pub mod pipeline;
 pub mod sender;
 pub mod signer;
 pub mod simulator;
 pub mod utils;
 
-pub use builder::{CoboSafeBuilder, DirectBuilder, TxBuilder, TxRequest};
-pub use sender::{FlashbotsSender, PrivateSender, RawTx, RpcSender, TxSender};
-pub use signer::{LocalSigner, RemoteSigner, TxSigner};
+pub use builder::{
+    AccountKind, CoboSafeBuilder, DirectBuilder, TxBuilder, TxRequest, UserOpBuilder, UserOperation,
+};
+pub use sender::{
+    AnySender, BundlerSender, EscalatingSender, FanoutSender, FlashbotsSender, MevShareSender,
+    NonceManager, PrivateSender, RawTx, RecordSender, RpcSender, TxOutcome, TxSender, TxTracker,
+};
+pub use signer::{
+    AnySigner, LocalSigner, PolicySigner, PolicyViolation, RemoteSigner, RemoteSignerOptions,
+    TxPolicy, TxSigner,
+};
 pub use simulator::{
     display_result, AbiDecoder, DecodedCall, DecodedEvent, ForkSimulator, SimulationResult,
 };
@@ -0,0 +1,546 @@
+//! 模拟门控：签名之后、广播之前，在 pending 区块的新 fork 上按顺序重放签好的
+//! raw tx，跑调用方断言、检查 gas 余量，全部通过才交给 sender。
+
+use std::collections::HashMap;
+
+use alloy::{
+    consensus::{Transaction, TxEnvelope},
+    eips::{BlockId, Decodable2718},
+    network::AnyNetwork,
+    primitives::{keccak256, Address, Log, B256, I256, U256},
+    providers::{DynProvider, Provider},
+};
+use eyre::Result;
+
+use crate::{
+    simulator::{
+        assertions::{assert_events_in_order, ExpectedEvent},
+        erc20, format_trace, CallFrame,
+    },
+    AbiDecoder, ForkSimulator, RawTx, SimulationResult, TxBuilder, TxRequest, TxSender, TxSigner,
+};
+
+/// 交易本身的基础 gas，不参与 headroom 计算。
+const BASE_TX_GAS: u64 = 21_000;
+
+/// 见 [`SimulationGate::with_gas_headroom_bps`]。
+fn required_gas_limit(gas_used: u64, gas_refunded: u64, headroom_bps: u64) -> u64 {
+    let peak = gas_used.saturating_add(gas_refunded);
+    let headroom = u128::from(peak.saturating_sub(BASE_TX_GAS)) * u128::from(headroom_bps) / 10_000;
+    peak.saturating_add(u64::try_from(headroom).unwrap_or(u64::MAX))
+}
+
+type Check = Box<dyn Fn(&GateSimulation) -> Result<()> + Send + Sync>;
+
+/// 签名后、广播前的模拟门控：fork → 按序重放 → 断言 + gas 余量 → 才广播。
+///
+/// ```ignore
+/// let gate = SimulationGate::new(&rpc_url)
+///     .with_decoder(decoder)
+///     .expect_events(vec![ExpectedEvent { address: pool, event: "Deposit", params: vec![] }])
+///     .expect_balance_delta(Some(USDC), safe, I256::try_from(-1_000_000_000i64)?, I256::ZERO);
+/// let hashes = gate
+///     .submit(&provider, &CoboSafeBuilder::new(cobosafe, 1), &signer, &sender, &requests, max_fee, tip)
+///     .await?;
+/// ```
+pub struct SimulationGate {
+    rpc_url: String,
+    block: BlockId,
+    decoder: Option<AbiDecoder>,
+    gas_headroom_bps: u64,
+    /// (token，`None` 为 ETH；holder)
+    watches: Vec<(Option<Address>, Address)>,
+    events: Vec<ExpectedEvent<'static>>,
+    checks: Vec<(String, Check)>,
+}
+
+impl SimulationGate {
+    /// 默认 fork pending 区块，gas headroom 10%。
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            block: BlockId::pending(),
+            decoder: None,
+            gas_headroom_bps: 1_000,
+            watches: Vec::new(),
+            events: Vec::new(),
+            checks: Vec::new(),
+        }
+    }
+
+    pub fn at_block(mut self, block: BlockId) -> Self {
+        self.block = block;
+        self
+    }
+
+    /// 用于事件断言和失败时解码调用树。
+    pub fn with_decoder(mut self, decoder: AbiDecoder) -> Self {
+        self.decoder = Some(decoder);
+        self
+    }
+
+    /// 要求 `gas_limit >= peak + (peak - 21000) * bps / 10000`，给 pending 状态与上链时
+    /// 状态的差异留余量。`peak = gas_used + gas_refunded`：refund 在交易结束时才返还，
+    /// 执行过程中需要的 gas 比 `gas_used` 多。
+    pub fn with_gas_headroom_bps(mut self, bps: u64) -> Self {
+        self.gas_headroom_bps = bps;
+        self
+    }
+
+    /// 记录 `holder` 的余额变化（`token` 为 `None` 时是 ETH），供
+    /// [`GateSimulation::balance_delta`] 查询。
+    pub fn watch_balance(mut self, token: Option<Address>, holder: Address) -> Self {
+        if !self.watches.contains(&(token, holder)) {
+            self.watches.push((token, holder));
+        }
+        self
+    }
+
+    /// 全部交易执行后 `holder` 的余额变化须落在 `[min, max]`。
+    pub fn expect_balance_delta(
+        self,
+        token: Option<Address>,
+        holder: Address,
+        min: I256,
+        max: I256,
+    ) -> Self {
+        let name = match token {
+            Some(token) => format!("balance delta of {holder} in {token}"),
+            None => format!("ETH balance delta of {holder}"),
+        };
+        self.watch_balance(token, holder).check(name, move |sim| {
+            let delta = sim
+                .balance_delta(token, holder)
+                .expect("watched balance is recorded");
+            eyre::ensure!(
+                delta >= min && delta <= max,
+                "delta {delta} not in [{min}, {max}]"
+            );
+            Ok(())
+        })
+    }
+
+    /// 全部交易的 log 里须按顺序出现 `expected`（需要 [`with_decoder`](Self::with_decoder)），
+    /// 规则同 [`assert_events_in_order`]。
+    pub fn expect_events(mut self, expected: Vec<ExpectedEvent<'static>>) -> Self {
+        self.events.extend(expected);
+        self
+    }
+
+    /// 自定义断言，失败时以 `name` 报告。
+    pub fn check(
+        mut self,
+        name: impl Into<String>,
+        check: impl Fn(&GateSimulation) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.checks.push((name.into(), Box::new(check)));
+        self
+    }
+
+    /// 在新 fork 上按顺序执行 `txs` 并跑全部检查。不通过时返回的错误可
+    /// `downcast_ref::<GateRejection>()`。
+    pub async fn simulate(&self, txs: &[RawTx]) -> Result<GateSimulation> {
+        let mut sim = ForkSimulator::fork(&self.rpc_url, Some(self.block)).await?;
+        let before = self.read_balances(&sim)?;
+
+        let mut results = Vec::with_capacity(txs.len());
+        let mut traces = Vec::with_capacity(txs.len());
+        for (index, raw) in txs.iter().enumerate() {
+            let tx_hash = keccak256(&raw.0);
+            let gas_limit = TxEnvelope::decode_2718(&mut raw.0.as_ref())
+                .map_err(|e| eyre::eyre!("decode 2718 envelope: {e}"))?
+                .gas_limit();
+            let (result, trace) = sim.simulate_raw_tx_traced(&raw.0)?;
+
+            let failure = if !result.success {
+                Some(GateFailure::Reverted)
+            } else {
+                let required =
+                    required_gas_limit(result.gas_used, result.gas_refunded, self.gas_headroom_bps);
+                (required > gas_limit).then_some(GateFailure::GasLimit {
+                    gas_used: result.gas_used,
+                    gas_limit,
+                    required,
+                })
+            };
+            if let Some(failure) = failure {
+                return Err(GateRejection {
+                    tx_index: Some(index),
+                    tx_hash: Some(tx_hash),
+                    failure,
+                    revert_reason: result.revert_reason,
+                    rendered_trace: format_trace(&trace, self.decoder.as_ref()),
+                    trace,
+                }
+                .into());
+            }
+            results.push(result);
+            traces.push(trace);
+        }
+
+        let after = self.read_balances(&sim)?;
+        let balances = before
+            .into_iter()
+            .map(|(key, b)| (key, (b, after[&key])))
+            .collect();
+        let simulation = GateSimulation {
+            results,
+            traces,
+            balances,
+        };
+        self.run_checks(&simulation)?;
+        Ok(simulation)
+    }
+
+    /// build → sign → 模拟门控 → send。nonce 取自 `provider`（同
+    /// [`submit_transactions`](crate::utils::cobosafe::submit_transactions)），
+    /// `builder` 可以是 `CoboSafeBuilder`、`DirectBuilder` 或任意 [`TxBuilder`]。
+    #[allow(clippy::too_many_arguments)]
+    pub async fn submit<B: TxBuilder, Sg: TxSigner, S: TxSender>(
+        &self,
+        provider: &DynProvider<AnyNetwork>,
+        builder: &B,
+        signer: &Sg,
+        sender: &S,
+        requests: &[TxRequest],
+        max_fee_wei: u128,
+        priority_fee_wei: u128,
+    ) -> Result<Vec<B256>> {
+        let nonce = provider.get_transaction_count(signer.address()).await?;
+        let unsigned = builder.build_txs(requests, nonce, max_fee_wei, priority_fee_wei)?;
+        let mut raws = Vec::with_capacity(unsigned.len());
+        for tx in unsigned {
+            raws.push(signer.sign(tx).await?);
+        }
+        tracing::info!("simulating {} tx(s) before broadcast ...", raws.len());
+        let simulation = self.simulate(&raws).await?;
+        for (i, r) in simulation.results.iter().enumerate() {
+            tracing::info!("tx #{i}: gas_used={}, logs={}", r.gas_used, r.logs.len());
+        }
+        tracing::info!("broadcasting ...");
+        let hashes = sender.send_txs(&raws).await?;
+        for h in &hashes {
+            tracing::info!("tx hash: {h}");
+        }
+        Ok(hashes)
+    }
+
+    fn read_balances(
+        &self,
+        sim: &ForkSimulator,
+    ) -> Result<HashMap<(Option<Address>, Address), U256>> {
+        self.watches
+            .iter()
+            .map(|&(token, holder)| {
+                let balance = match token {
+                    Some(token) => erc20::balance(sim, token, holder)?,
+                    None => sim.get_balance(holder)?,
+                };
+                Ok(((token, holder), balance))
+            })
+            .collect()
+    }
+
+    fn run_checks(&self, simulation: &GateSimulation) -> Result<()> {
+        let mut failed = Vec::new();
+        if !self.events.is_empty() {
+            let result = match &self.decoder {
+                Some(decoder) => assert_events_in_order(&simulation.logs(), decoder, &self.events),
+                None => Err(eyre::eyre!("expect_events requires with_decoder")),
+            };
+            if let Err(e) = result {
+                failed.push(("events".to_string(), format!("{e:#}")));
+            }
+        }
+        for (name, check) in &self.checks {
+            if let Err(e) = check(simulation) {
+                failed.push((name.clone(), format!("{e:#}")));
+            }
+        }
+        if failed.is_empty() {
+            return Ok(());
+        }
+        Err(GateRejection {
+            tx_index: None,
+            tx_hash: None,
+            failure: GateFailure::Assertions(failed),
+            revert_reason: None,
+            trace: Vec::new(),
+            rendered_trace: String::new(),
+        }
+        .into())
+    }
+}
+
+/// 门控通过后的模拟结果，也是自定义断言的输入。
+pub struct GateSimulation {
+    /// 与输入交易一一对应。
+    pub results: Vec<SimulationResult>,
+    pub traces: Vec<Vec<CallFrame>>,
+    balances: HashMap<(Option<Address>, Address), (U256, U256)>,
+}
+
+impl GateSimulation {
+    /// 所有交易的 log，按执行顺序拼接。
+    pub fn logs(&self) -> Vec<Log> {
+        self.results.iter().flat_map(|r| r.logs.clone()).collect()
+    }
+
+    pub fn gas_used(&self) -> u64 {
+        self.results.iter().map(|r| r.gas_used).sum()
+    }
+
+    /// 被 [`SimulationGate::watch_balance`] 记录的余额变化，未记录时为 `None`。
+    pub fn balance_delta(&self, token: Option<Address>, holder: Address) -> Option<I256> {
+        let (before, after) = self.balances.get(&(token, holder))?;
+        Some(I256::from_raw(*after).wrapping_sub(I256::from_raw(*before)))
+    }
+}
+
+/// 门控拦下交易的原因。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateFailure {
+    /// 交易 revert 或 halt。
+    Reverted,
+    /// 执行成功但 gas 余量不足。
+    GasLimit {
+        gas_used: u64,
+        gas_limit: u64,
+        required: u64,
+    },
+    /// 未通过的断言 `(name, error)`。
+    Assertions(Vec<(String, String)>),
+}
+
+/// [`SimulationGate`] 以 `eyre::Report` 返回，可 `downcast_ref` 取回。
+#[derive(Debug, Clone)]
+pub struct GateRejection {
+    /// 出问题的交易；断言失败时针对整批交易，为 `None`。
+    pub tx_index: Option<usize>,
+    pub tx_hash: Option<B256>,
+    pub failure: GateFailure,
+    pub revert_reason: Option<String>,
+    pub trace: Vec<CallFrame>,
+    /// [`format_trace`] 的输出（用了 gate 的 decoder）。
+    pub rendered_trace: String,
+}
+
+impl std::fmt::Display for GateRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "simulation rejected ")?;
+        match (self.tx_index, self.tx_hash) {
+            (Some(index), Some(hash)) => write!(f, "tx #{index} ({hash}): ")?,
+            _ => write!(f, "batch: ")?,
+        }
+        match &self.failure {
+            GateFailure::Reverted => write!(
+                f,
+                "reverted: {}",
+                self.revert_reason.as_deref().unwrap_or("unknown")
+            )?,
+            GateFailure::GasLimit {
+                gas_used,
+                gas_limit,
+                required,
+            } => write!(
+                f,
+                "gas_used {gas_used} needs gas_limit >= {required}, tx has {gas_limit}"
+            )?,
+            GateFailure::Assertions(failed) => {
+                let failed: Vec<String> = failed.iter().map(|(n, e)| format!("{n}: {e}")).collect();
+                write!(f, "assertions failed: {}", failed.join("; "))?
+            }
+        }
+        if !self.rendered_trace.is_empty() {
+            write!(f, "\n{}", self.rendered_trace.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for GateRejection {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utils::testing::{testing_delegate, MockChain},
+        CoboSafeBuilder, DirectBuilder, RpcSender,
+    };
+    use alloy::{
+        primitives::{address, hex, Bytes},
+        providers::ProviderBuilder,
+    };
+
+    const EMITTER: Address = address!("e000000000000000000000000000000000000001");
+    const REVERTER: Address = address!("e000000000000000000000000000000000000002");
+    const RECIPIENT: Address = address!("e000000000000000000000000000000000000003");
+    const GWEI: u128 = 1_000_000_000;
+
+    /// `LOG1(topic=1)` 后 STOP。
+    const EMITTER_CODE: &str = "600160006000a100";
+    /// `revert Error("nope")`。
+    const REVERTER_CODE: &str = concat!(
+        "7f08c379a000000000000000000000000000000000000000000000000000000000",
+        "600052602060045260046024527f6e6f706500000000000000000000000000000000",
+        "000000000000000000000000604452606460",
+        "00fd"
+    );
+
+    async fn chain() -> (MockChain, DynProvider<AnyNetwork>) {
+        let chain = MockChain::start(1).await.unwrap();
+        chain.set_balance(testing_delegate().1, U256::from(10u128.pow(18)));
+        chain.set_code(EMITTER, Bytes::from(hex::decode(EMITTER_CODE).unwrap()));
+        chain.set_code(REVERTER, Bytes::from(hex::decode(REVERTER_CODE).unwrap()));
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_http(chain.url().parse().unwrap())
+            .erased();
+        (chain, provider)
+    }
+
+    #[test]
+    fn gas_headroom_counts_refund() {
+        assert_eq!(required_gas_limit(121_000, 0, 1_000), 131_000);
+        // 执行峰值 141_000，refund 20_000 后 gas_used 为 121_000
+        assert_eq!(required_gas_limit(121_000, 20_000, 1_000), 153_000);
+        assert_eq!(required_gas_limit(u64::MAX / 2, 0, u64::MAX), u64::MAX);
+        assert_eq!(required_gas_limit(u64::MAX, u64::MAX, 1_000), u64::MAX);
+    }
+
+    fn call(to: Address, value: u64, gas_limit: u64) -> TxRequest {
+        TxRequest {
+            to,
+            value: U256::from(value),
+            data: Bytes::new(),
+            gas_limit,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn direct_batch_passes_and_is_sent() {
+        let (chain, provider) = chain().await;
+        let sender = RpcSender::new(chain.url()).unwrap();
+        let gate = SimulationGate::new(chain.url())
+            .expect_balance_delta(None, RECIPIENT, I256::ONE, I256::ONE)
+            .check("one log", |sim| {
+                eyre::ensure!(sim.logs().len() == 1, "got {} logs", sim.logs().len());
+                Ok(())
+            });
+        let requests = [call(EMITTER, 0, 50_000), call(RECIPIENT, 1, 21_000)];
+
+        let hashes = gate
+            .submit(
+                &provider,
+                &DirectBuilder::new(1),
+                &testing_delegate().0,
+                &sender,
+                &requests,
+                2 * GWEI,
+                GWEI,
+            )
+            .await
+            .unwrap();
+        assert_eq!(hashes.len(), 2);
+        assert_eq!(chain.sent_txs().len(), 2);
+
+        // 断言不满足：整批拦下，不广播
+        let gate = SimulationGate::new(chain.url()).expect_balance_delta(
+            None,
+            RECIPIENT,
+            I256::try_from(2).unwrap(),
+            I256::MAX,
+        );
+        let err = gate
+            .submit(
+                &provider,
+                &DirectBuilder::new(1),
+                &testing_delegate().0,
+                &sender,
+                &requests,
+                2 * GWEI,
+                GWEI,
+            )
+            .await
+            .unwrap_err();
+        let rejection = err.downcast_ref::<GateRejection>().unwrap();
+        assert_eq!(rejection.tx_index, None);
+        assert!(matches!(&rejection.failure, GateFailure::Assertions(f) if f.len() == 1));
+        assert_eq!(chain.sent_txs().len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn revert_returns_reason_and_trace() {
+        let (chain, provider) = chain().await;
+        let sender = RpcSender::new(chain.url()).unwrap();
+        let requests = [call(EMITTER, 0, 50_000), call(REVERTER, 0, 50_000)];
+
+        let err = SimulationGate::new(chain.url())
+            .submit(
+                &provider,
+                &DirectBuilder::new(1),
+                &testing_delegate().0,
+                &sender,
+                &requests,
+                2 * GWEI,
+                GWEI,
+            )
+            .await
+            .unwrap_err();
+        let rejection = err.downcast_ref::<GateRejection>().unwrap();
+        assert_eq!(rejection.tx_index, Some(1));
+        assert_eq!(rejection.failure, GateFailure::Reverted);
+        assert_eq!(rejection.revert_reason.as_deref(), Some("nope"));
+        assert_eq!(rejection.trace.len(), 1);
+        assert_eq!(rejection.trace[0].to, REVERTER);
+        assert!(err.to_string().contains("REVERT: nope"), "{err}");
+        assert!(chain.sent_txs().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cobosafe_gas_headroom() {
+        let (chain, provider) = chain().await;
+        let sender = RpcSender::new(chain.url()).unwrap();
+        // CoboSafe 位置放 emitter：不管 calldata，只 emit 一条 log
+        let builder = CoboSafeBuilder::new(EMITTER, 1);
+        let gate = SimulationGate::new(chain.url());
+        gate.submit(
+            &provider,
+            &builder,
+            &testing_delegate().0,
+            &sender,
+            &[call(RECIPIENT, 0, 100_000)],
+            2 * GWEI,
+            GWEI,
+        )
+        .await
+        .unwrap();
+
+        // gas_limit 恰好等于 gas_used：能执行，但没有余量
+        let signer = testing_delegate().0;
+        let build =
+            |gas_limit| builder.build_txs(&[call(RECIPIENT, 0, gas_limit)], 1, 2 * GWEI, GWEI);
+        let raw = signer
+            .sign(build(100_000).unwrap().remove(0))
+            .await
+            .unwrap();
+        let gas_used = gate.simulate(&[raw]).await.unwrap().gas_used();
+        let raw = signer
+            .sign(build(gas_used).unwrap().remove(0))
+            .await
+            .unwrap();
+        let err = gate.simulate(&[raw]).await.err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<GateRejection>().unwrap().failure,
+            GateFailure::GasLimit { gas_limit, .. } if gas_limit == gas_used
+        ));
+        assert!(gate
+            .with_gas_headroom_bps(0)
+            .simulate(&[signer
+                .sign(build(gas_used).unwrap().remove(0))
+                .await
+                .unwrap()])
+            .await
+            .is_ok());
+    }
+}
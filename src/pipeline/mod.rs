@@ -0,0 +1,14 @@
+//! 把 builder / signer / sender 串起来的提交流程。
+//!
+//! - [`Pipeline`]：fees → build → nonce → sign → (simulate) → send，gas 定价
+//!   ([`GasStrategy`]) 和 nonce 来源 ([`NonceSource`]) 可替换，支持 dry-run，
+//!   每个阶段的结果通过 hook 和 [`PipelineOutcome`] / [`PipelineError`] 返回
+//! - [`SimulationGate`]：签名后先在 pending 区块的 fork 上模拟、跑断言，通过才广播
+
+mod gate;
+mod strategy;
+mod submit;
+
+pub use gate::{GateFailure, GateRejection, GateSimulation, SimulationGate};
+pub use strategy::{BaseFeeMultiplier, Fees, FixedNonce, GasStrategy, NonceLease, NonceSource};
+pub use submit::{Pipeline, PipelineError, PipelineOutcome, Stage, StageReport};
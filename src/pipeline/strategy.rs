@@ -0,0 +1,145 @@
+//! [`Pipeline`](super::Pipeline) 的可替换部件：gas 定价和 nonce 来源。
+
+use std::future::Future;
+
+use alloy::{
+    network::AnyNetwork,
+    primitives::Address,
+    providers::{DynProvider, Provider},
+};
+use eyre::Result;
+
+use crate::{
+    app::estimate_gas_fee,
+    sender::{NonceGuard, NonceManager},
+};
+
+/// EIP-1559 费用。本身也是一个固定值的 [`GasStrategy`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fees {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+/// 每次提交前决定费用。
+///
+/// 与 `TxSender` 一样用 RPITIT，不支持 `dyn GasStrategy`。
+pub trait GasStrategy: Send + Sync {
+    fn fees(&self) -> impl Future<Output = Result<Fees>> + Send;
+}
+
+impl GasStrategy for Fees {
+    async fn fees(&self) -> Result<Fees> {
+        Ok(*self)
+    }
+}
+
+/// 下一块 basefee × `percent` / 100，默认与
+/// [`AppConfigBase::resolve_gas_fee`](crate::app::AppConfigBase::resolve_gas_fee) 一致：
+/// `max_fee = priority_fee = 1.5 × base`。
+pub struct BaseFeeMultiplier {
+    provider: DynProvider<AnyNetwork>,
+    percent: u128,
+    priority_fee: Option<u128>,
+}
+
+impl BaseFeeMultiplier {
+    pub fn new(provider: DynProvider<AnyNetwork>) -> Self {
+        Self {
+            provider,
+            percent: 150,
+            priority_fee: None,
+        }
+    }
+
+    pub fn with_percent(mut self, percent: u128) -> Self {
+        self.percent = percent;
+        self
+    }
+
+    /// 固定 priority fee；不设时与 max fee 相同。
+    pub fn with_priority_fee(mut self, wei: u128) -> Self {
+        self.priority_fee = Some(wei);
+        self
+    }
+}
+
+impl GasStrategy for BaseFeeMultiplier {
+    async fn fees(&self) -> Result<Fees> {
+        let base = estimate_gas_fee(&self.provider).await?;
+        let max_fee = base * self.percent / 100;
+        Ok(Fees {
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: self.priority_fee.unwrap_or(max_fee).min(max_fee),
+        })
+    }
+}
+
+/// 为一次提交分配 nonce。
+pub trait NonceSource: Send + Sync {
+    /// 为 `account` 分配 `count` 个 nonce（不一定连续）。
+    fn reserve(
+        &self,
+        account: Address,
+        count: usize,
+    ) -> impl Future<Output = Result<NonceLease>> + Send;
+}
+
+/// 分配到的 nonce。开始发送前 [`commit`](Self::commit)；未 commit 就 drop
+/// 时，来自 [`NonceManager`] 的 nonce 会归还。
+pub struct NonceLease {
+    nonces: Vec<u64>,
+    guards: Vec<NonceGuard>,
+}
+
+impl NonceLease {
+    /// 不需要归还的 nonce（链上查询 / 固定值）。
+    pub fn new(nonces: Vec<u64>) -> Self {
+        Self {
+            nonces,
+            guards: Vec::new(),
+        }
+    }
+
+    pub fn nonces(&self) -> &[u64] {
+        &self.nonces
+    }
+
+    pub fn commit(self) {
+        for guard in self.guards {
+            guard.commit();
+        }
+    }
+}
+
+/// 按 `pending` 交易数取连续 nonce，不做本地记账。单 task 提交时用。
+impl NonceSource for DynProvider<AnyNetwork> {
+    async fn reserve(&self, account: Address, count: usize) -> Result<NonceLease> {
+        let start = self.get_transaction_count(account).pending().await?;
+        Ok(NonceLease::new((start..start + count as u64).collect()))
+    }
+}
+
+/// 多 task 共享时用，失败自动归还。
+impl NonceSource for NonceManager {
+    async fn reserve(&self, account: Address, count: usize) -> Result<NonceLease> {
+        let mut guards = Vec::with_capacity(count);
+        for _ in 0..count {
+            guards.push(NonceManager::reserve(self, account).await?);
+        }
+        Ok(NonceLease {
+            nonces: guards.iter().map(NonceGuard::nonce).collect(),
+            guards,
+        })
+    }
+}
+
+/// 从给定值开始的连续 nonce（离线签名 / 调用方自己管理 nonce）。
+#[derive(Debug, Clone, Copy)]
+pub struct FixedNonce(pub u64);
+
+impl NonceSource for FixedNonce {
+    async fn reserve(&self, _account: Address, count: usize) -> Result<NonceLease> {
+        Ok(NonceLease::new((self.0..self.0 + count as u64).collect()))
+    }
+}
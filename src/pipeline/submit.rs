@@ -0,0 +1,447 @@
+//! 通用的 fees → build → nonce → sign → (simulate) → send 流程。
+
+use std::time::{Duration, Instant};
+
+use alloy::{
+    consensus::TxEip1559,
+    primitives::{Address, B256},
+};
+use eyre::Result;
+
+use super::{
+    gate::{GateSimulation, SimulationGate},
+    strategy::{Fees, GasStrategy, NonceSource},
+};
+use crate::{RawTx, TxBuilder, TxRequest, TxSender, TxSigner};
+
+/// 流程的各个阶段，按执行顺序。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Fees,
+    Build,
+    Nonce,
+    Sign,
+    Simulate,
+    Send,
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Fees => "fees",
+            Self::Build => "build",
+            Self::Nonce => "nonce",
+            Self::Sign => "sign",
+            Self::Simulate => "simulate",
+            Self::Send => "send",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 一个阶段的结果，完成（或失败）时交给 [`Pipeline::on_stage`] 的 hook。
+#[derive(Debug, Clone)]
+pub struct StageReport {
+    pub stage: Stage,
+    pub elapsed: Duration,
+    /// 成功时的简述（费用、nonce、hash 等）。
+    pub detail: String,
+    /// 失败原因；`None` 表示成功。
+    pub error: Option<String>,
+}
+
+type Hook = Box<dyn Fn(&StageReport) + Send + Sync>;
+
+/// 一次成功提交（或 dry-run）的完整记录。
+pub struct PipelineOutcome {
+    pub from: Address,
+    pub fees: Fees,
+    pub nonces: Vec<u64>,
+    pub txs: Vec<TxEip1559>,
+    pub raws: Vec<RawTx>,
+    /// 配了 [`Pipeline::with_simulation`] 时有值。
+    pub simulation: Option<GateSimulation>,
+    /// dry-run 时为空。
+    pub hashes: Vec<B256>,
+    pub dry_run: bool,
+    pub stages: Vec<StageReport>,
+}
+
+/// 某个阶段失败。`source` 是原始错误，可继续 `downcast_ref`
+/// （如 [`GateRejection`](super::GateRejection)、[`PolicyViolation`](crate::PolicyViolation)）。
+#[derive(Debug)]
+pub struct PipelineError {
+    pub stage: Stage,
+    /// 含失败阶段在内的所有已执行阶段。
+    pub stages: Vec<StageReport>,
+    pub source: eyre::Report,
+}
+
+impl std::fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "pipeline failed at {}: {:#}", self.stage, self.source)
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+/// 不绑定具体 builder / signer / sender 的提交流程。
+///
+/// ```ignore
+/// let pipeline = Pipeline::new(
+///     &DirectBuilder::new(chain_id),
+///     &signer,
+///     &RpcSender::new(&rpc_url)?,
+///     BaseFeeMultiplier::new(provider.clone()),
+///     provider.clone(),
+/// )
+/// .with_simulation(SimulationGate::new(&rpc_url))
+/// .on_stage(|r| metrics::record(r.stage, r.elapsed));
+/// let outcome = pipeline.submit(&requests).await?;
+/// ```
+///
+/// 分配到的 nonce 不必连续：build 之后逐笔覆盖 `nonce`，所以 builder 产出的
+/// 每笔交易必须彼此独立（`CoboSafeBuilder` / `DirectBuilder` 都满足）。
+/// 进入发送阶段前 commit nonce：发送中途失败时节点 / relay 可能已经收下了其中几笔，
+/// 这些 nonce 不能再分给别的交易（真的留下 gap 时用 [`NonceManager::resync`] 回收）。
+/// dry-run 和发送之前的阶段失败都会归还。
+///
+/// [`NonceManager::resync`]: crate::sender::NonceManager::resync
+pub struct Pipeline<'a, B, Sg, S, G, N> {
+    builder: &'a B,
+    signer: &'a Sg,
+    sender: &'a S,
+    gas: G,
+    nonces: N,
+    gate: Option<SimulationGate>,
+    dry_run: bool,
+    hooks: Vec<Hook>,
+}
+
+impl<'a, B, Sg, S, G, N> Pipeline<'a, B, Sg, S, G, N>
+where
+    B: TxBuilder,
+    Sg: TxSigner,
+    S: TxSender,
+    G: GasStrategy,
+    N: NonceSource,
+{
+    pub fn new(builder: &'a B, signer: &'a Sg, sender: &'a S, gas: G, nonces: N) -> Self {
+        Self {
+            builder,
+            signer,
+            sender,
+            gas,
+            nonces,
+            gate: None,
+            dry_run: false,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// 签名后先过 [`SimulationGate`]，不通过则不发送。
+    pub fn with_simulation(mut self, gate: SimulationGate) -> Self {
+        self.gate = Some(gate);
+        self
+    }
+
+    /// 只走到 sign / simulate，不发送。
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// 每个阶段结束时调用，用于日志 / metrics。可注册多个。
+    pub fn on_stage(mut self, hook: impl Fn(&StageReport) + Send + Sync + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// 跑完整流程。失败时返回的错误可 `downcast_ref::<PipelineError>()`。
+    pub async fn submit(&self, requests: &[TxRequest]) -> Result<PipelineOutcome> {
+        let mut stages = Vec::new();
+        let from = self.signer.address();
+
+        let fees = self
+            .stage(&mut stages, Stage::Fees, self.gas.fees(), |f| {
+                format!(
+                    "max_fee={} priority_fee={}",
+                    f.max_fee_per_gas, f.max_priority_fee_per_gas
+                )
+            })
+            .await?;
+
+        // 先按占位 nonce 构建一次，确定需要几个 nonce
+        let mut txs = self
+            .stage(
+                &mut stages,
+                Stage::Build,
+                async {
+                    self.builder.build_txs(
+                        requests,
+                        0,
+                        fees.max_fee_per_gas,
+                        fees.max_priority_fee_per_gas,
+                    )
+                },
+                |txs| format!("{} tx(s)", txs.len()),
+            )
+            .await?;
+
+        let lease = self
+            .stage(
+                &mut stages,
+                Stage::Nonce,
+                self.nonces.reserve(from, txs.len()),
+                |l| format!("{from} {:?}", l.nonces()),
+            )
+            .await?;
+        for (tx, nonce) in txs.iter_mut().zip(lease.nonces()) {
+            tx.nonce = *nonce;
+        }
+
+        let raws = self
+            .stage(
+                &mut stages,
+                Stage::Sign,
+                async {
+                    let mut raws = Vec::with_capacity(txs.len());
+                    for tx in &txs {
+                        raws.push(self.signer.sign(tx.clone()).await?);
+                    }
+                    Ok(raws)
+                },
+                |raws| format!("{} raw tx(s)", raws.len()),
+            )
+            .await?;
+
+        let simulation = match &self.gate {
+            Some(gate) => Some(
+                self.stage(&mut stages, Stage::Simulate, gate.simulate(&raws), |s| {
+                    format!("gas_used={}", s.gas_used())
+                })
+                .await?,
+            ),
+            None => None,
+        };
+
+        let hashes = if self.dry_run {
+            Vec::new()
+        } else {
+            lease.commit();
+            self.stage(&mut stages, Stage::Send, self.sender.send_txs(&raws), |h| {
+                format!("{h:?}")
+            })
+            .await?
+        };
+
+        Ok(PipelineOutcome {
+            from,
+            fees,
+            nonces: txs.iter().map(|tx| tx.nonce).collect(),
+            txs,
+            raws,
+            simulation,
+            hashes,
+            dry_run: self.dry_run,
+            stages,
+        })
+    }
+
+    /// 执行一个阶段并记录 [`StageReport`]，失败时包成 [`PipelineError`]。
+    async fn stage<T>(
+        &self,
+        stages: &mut Vec<StageReport>,
+        stage: Stage,
+        fut: impl std::future::Future<Output = Result<T>>,
+        detail: impl FnOnce(&T) -> String,
+    ) -> Result<T> {
+        let started = Instant::now();
+        let result = fut.await;
+        let mut report = StageReport {
+            stage,
+            elapsed: started.elapsed(),
+            detail: String::new(),
+            error: None,
+        };
+        match &result {
+            Ok(value) => {
+                report.detail = detail(value);
+                tracing::info!(
+                    "[pipeline] {stage}: {} ({:?})",
+                    report.detail,
+                    report.elapsed
+                );
+            }
+            Err(e) => {
+                report.error = Some(format!("{e:#}"));
+                tracing::warn!("[pipeline] {stage} failed: {e:#}");
+            }
+        }
+        for hook in &self.hooks {
+            hook(&report);
+        }
+        stages.push(report);
+        result.map_err(|source| {
+            PipelineError {
+                stage,
+                stages: stages.clone(),
+                source,
+            }
+            .into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{
+        pipeline::{BaseFeeMultiplier, GateRejection},
+        sender::NonceManager,
+        utils::testing::{testing_delegate, MockChain},
+        DirectBuilder, RpcSender,
+    };
+    use alloy::{
+        network::AnyNetwork,
+        primitives::{address, hex, Bytes, U256},
+        providers::{DynProvider, Provider, ProviderBuilder},
+    };
+
+    const RECIPIENT: Address = address!("e000000000000000000000000000000000000003");
+    /// 空 revert。
+    const REVERTER: Address = address!("e000000000000000000000000000000000000004");
+
+    async fn chain() -> (MockChain, DynProvider<AnyNetwork>) {
+        let chain = MockChain::start(1).await.unwrap();
+        chain.set_balance(testing_delegate().1, U256::from(10u128.pow(18)));
+        chain.set_code(REVERTER, Bytes::from(hex::decode("60006000fd").unwrap()));
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_http(chain.url().parse().unwrap())
+            .erased();
+        (chain, provider)
+    }
+
+    fn transfer(to: Address) -> TxRequest {
+        TxRequest {
+            to,
+            value: U256::from(1),
+            data: Bytes::new(),
+            gas_limit: 50_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn direct_pipeline_reports_every_stage() {
+        let (chain, provider) = chain().await;
+        let (signer, _) = testing_delegate();
+        let sender = RpcSender::new(chain.url()).unwrap();
+        let builder = DirectBuilder::new(1);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let hook_seen = seen.clone();
+        let pipeline = Pipeline::new(
+            &builder,
+            &signer,
+            &sender,
+            BaseFeeMultiplier::new(provider.clone()),
+            provider.clone(),
+        )
+        .on_stage(move |r| hook_seen.lock().unwrap().push(r.stage));
+
+        let requests = [transfer(RECIPIENT), transfer(RECIPIENT)];
+        let outcome = pipeline.submit(&requests).await.unwrap();
+        assert_eq!(outcome.nonces, vec![0, 1]);
+        assert_eq!(outcome.fees.max_fee_per_gas, 1_500_000_000);
+        assert_eq!(outcome.hashes.len(), 2);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                Stage::Fees,
+                Stage::Build,
+                Stage::Nonce,
+                Stage::Sign,
+                Stage::Send
+            ]
+        );
+
+        let outcome = pipeline.submit(&requests).await.unwrap();
+        assert_eq!(outcome.nonces, vec![2, 3]);
+        assert_eq!(chain.sent_txs().len(), 4);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dry_run_and_rejection_release_nonces() {
+        let (chain, provider) = chain().await;
+        let (signer, from) = testing_delegate();
+        let sender = RpcSender::new(chain.url()).unwrap();
+        let builder = DirectBuilder::new(1);
+        let nonces = NonceManager::new(provider.clone());
+        let fees = Fees {
+            max_fee_per_gas: 2_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+        };
+        let pipeline = |dry_run| {
+            Pipeline::new(&builder, &signer, &sender, fees, nonces.clone())
+                .with_simulation(SimulationGate::new(chain.url()))
+                .dry_run(dry_run)
+        };
+
+        let outcome = pipeline(true).submit(&[transfer(RECIPIENT)]).await.unwrap();
+        assert!(outcome.dry_run && outcome.hashes.is_empty());
+        assert_eq!(outcome.simulation.unwrap().results.len(), 1);
+        assert_eq!(outcome.nonces, vec![0]);
+
+        let err = pipeline(false)
+            .submit(&[transfer(REVERTER)])
+            .await
+            .err()
+            .unwrap();
+        let err = err.downcast_ref::<PipelineError>().unwrap();
+        assert_eq!(err.stage, Stage::Simulate);
+        assert!(err.stages.last().unwrap().error.is_some());
+        assert!(err.source.downcast_ref::<GateRejection>().is_some());
+
+        assert!(chain.sent_txs().is_empty());
+        assert_eq!(nonces.reserve(from).await.unwrap().nonce(), 0);
+    }
+
+    /// 只发出第一笔就断开，模拟多笔逐笔广播中途失败。
+    struct PartialSender(RpcSender);
+
+    impl TxSender for PartialSender {
+        async fn send_txs(&self, txs: &[RawTx]) -> Result<Vec<B256>> {
+            self.0.send_txs(&txs[..1]).await?;
+            eyre::bail!("connection reset after 1 of {} txs", txs.len())
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_send_keeps_nonces() {
+        let (chain, provider) = chain().await;
+        let (signer, from) = testing_delegate();
+        let sender = PartialSender(RpcSender::new(chain.url()).unwrap());
+        let builder = DirectBuilder::new(1);
+        let nonces = NonceManager::new(provider.clone());
+        let fees = Fees {
+            max_fee_per_gas: 2_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+        };
+        let pipeline = Pipeline::new(&builder, &signer, &sender, fees, nonces.clone());
+
+        let err = pipeline
+            .submit(&[transfer(RECIPIENT), transfer(RECIPIENT)])
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.downcast_ref::<PipelineError>().unwrap().stage,
+            Stage::Send
+        );
+        assert_eq!(chain.sent_txs().len(), 1);
+        // 第一笔已在节点里，nonce 0 / 1 都不能复用
+        assert_eq!(nonces.reserve(from).await.unwrap().nonce(), 2);
+    }
+}
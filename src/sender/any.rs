@@ -0,0 +1,172 @@
+use alloy::primitives::B256;
+use eyre::Result;
+
+use super::{
+    EscalatingSender, FanoutSender, FlashbotsSender, MevShareSender, PrivateSender, RawTx,
+    RecordSender, RpcSender, TxSender,
+};
+
+/// 运行时选定的发送器，[`crate::app::AppConfigBase::build_sender`] 的返回值。
+///
+/// `TxSender` 使用 RPITIT 不能做成 `dyn`，所以用 enum 分发。
+pub enum AnySender {
+    Rpc(RpcSender),
+    Flashbots(Box<FlashbotsSender>),
+    Private(Box<PrivateSender>),
+    MevShare(Box<MevShareSender>),
+    Fanout(FanoutSender),
+    Escalating(Box<EscalatingSender>),
+    /// 只写 JSONL，不广播。
+    Record(Box<RecordSender>),
+    /// 先走 `primary`；它返回错误时（relay 拒绝 / 超时）改用公共 RPC 广播。
+    ///
+    /// 只按发送错误回退，不看是否上链。
+    PublicOnError {
+        primary: Box<AnySender>,
+        public: RpcSender,
+    },
+}
+
+impl From<RpcSender> for AnySender {
+    fn from(s: RpcSender) -> Self {
+        Self::Rpc(s)
+    }
+}
+
+impl From<FlashbotsSender> for AnySender {
+    fn from(s: FlashbotsSender) -> Self {
+        Self::Flashbots(Box::new(s))
+    }
+}
+
+impl From<PrivateSender> for AnySender {
+    fn from(s: PrivateSender) -> Self {
+        Self::Private(Box::new(s))
+    }
+}
+
+impl From<MevShareSender> for AnySender {
+    fn from(s: MevShareSender) -> Self {
+        Self::MevShare(Box::new(s))
+    }
+}
+
+impl From<FanoutSender> for AnySender {
+    fn from(s: FanoutSender) -> Self {
+        Self::Fanout(s)
+    }
+}
+
+impl From<EscalatingSender> for AnySender {
+    fn from(s: EscalatingSender) -> Self {
+        Self::Escalating(Box::new(s))
+    }
+}
+
+impl From<RecordSender> for AnySender {
+    fn from(s: RecordSender) -> Self {
+        Self::Record(Box::new(s))
+    }
+}
+
+impl AnySender {
+    /// 包一层 [`AnySender::PublicOnError`]。
+    pub fn with_public_fallback(self, public: RpcSender) -> Self {
+        Self::PublicOnError {
+            primary: Box::new(self),
+            public,
+        }
+    }
+
+    /// 最终是否只写 [`RecordSender`]：透过 `PublicOnError` / `Escalating` 看主通道。
+    pub fn is_record(&self) -> bool {
+        match self {
+            Self::Record(_) => true,
+            Self::PublicOnError { primary, .. } => primary.is_record(),
+            Self::Escalating(s) => s.primary().is_record(),
+            _ => false,
+        }
+    }
+
+    /// 不带 fallback 的发送；`Box::pin` 打断 `PublicOnError` 的递归 future 类型。
+    fn send_boxed<'a>(
+        &'a self,
+        txs: &'a [RawTx],
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<B256>>> + Send + 'a>> {
+        Box::pin(async move {
+            match self {
+                Self::Rpc(s) => s.send_txs(txs).await,
+                Self::Flashbots(s) => s.send_txs(txs).await,
+                Self::Private(s) => s.send_txs(txs).await,
+                Self::MevShare(s) => s.send_txs(txs).await,
+                Self::Fanout(s) => s.send_txs(txs).await,
+                Self::Escalating(s) => s.send_txs(txs).await,
+                Self::Record(s) => s.send_txs(txs).await,
+                Self::PublicOnError { primary, public } => match primary.send_boxed(txs).await {
+                    Ok(hashes) => Ok(hashes),
+                    Err(e) => {
+                        tracing::warn!(
+                            "[sender] primary failed, falling back to public rpc: {e:#}"
+                        );
+                        public.send_txs(txs).await
+                    }
+                },
+            }
+        })
+    }
+}
+
+impl TxSender for AnySender {
+    async fn send_txs(&self, txs: &[RawTx]) -> Result<Vec<B256>> {
+        self.send_boxed(txs).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::{serve_http, serve_json_rpc, HttpResponse};
+    use alloy::signers::local::PrivateKeySigner;
+
+    #[tokio::test]
+    async fn public_fallback_after_relay_error() {
+        let rpc = serve_json_rpc(|method, _| match method {
+            "eth_blockNumber" => Ok(serde_json::json!("0x10")),
+            "eth_sendRawTransaction" => Ok(serde_json::json!(format!("0x{}", "cd".repeat(32)))),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let relay = serve_http(|_| HttpResponse::json(503, &serde_json::json!("down")))
+            .await
+            .unwrap();
+
+        let private =
+            PrivateSender::with_relay(PrivateKeySigner::random(), rpc.url(), relay.url()).unwrap();
+        let txs = [RawTx::from(vec![0x02, 0x01])];
+
+        let strict = AnySender::from(private);
+        assert!(strict.send_txs(&txs).await.is_err());
+
+        let sender = strict.with_public_fallback(RpcSender::new(rpc.url()).unwrap());
+        let hashes = sender.send_txs(&txs).await.unwrap();
+        assert_eq!(hashes, vec![B256::repeat_byte(0xcd)]);
+    }
+
+    #[test]
+    fn is_record_sees_through_wrappers() {
+        let path =
+            std::env::temp_dir().join(format!("flashseal-any-record-{}", std::process::id()));
+        let rpc = || RpcSender::new("http://127.0.0.1:1").unwrap();
+        let record = || AnySender::from(RecordSender::new(&path).unwrap());
+
+        assert!(record().is_record());
+        assert!(record().with_public_fallback(rpc()).is_record());
+        let escalating = EscalatingSender::new(record(), rpc(), "http://127.0.0.1:1").unwrap();
+        assert!(AnySender::from(escalating).is_record());
+        assert!(!AnySender::from(rpc())
+            .with_public_fallback(rpc())
+            .is_record());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
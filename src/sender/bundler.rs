@@ -0,0 +1,189 @@
+use alloy::{
+    network::{AnyNetwork, TransactionBuilder},
+    primitives::{aliases::U192, Address, Bytes, B256, U256},
+    providers::{DynProvider, Provider},
+    rpc::types::TransactionRequest,
+    sol_types::SolCall,
+};
+use eyre::{eyre, Result};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::builder::user_op::{getNonceCall, UserOpGasEstimate, UserOperation};
+
+/// ERC-4337 bundler JSON-RPC 客户端（EntryPoint v0.7）。
+///
+/// UserOperation 不是普通签名交易，所以它不实现 [`super::TxSender`]；
+/// 广播走 `eth_sendUserOperation`，返回的是 userOpHash 而不是 tx hash。
+pub struct BundlerSender {
+    client: reqwest::Client,
+    bundler_url: String,
+    entry_point: Address,
+}
+
+impl BundlerSender {
+    pub fn new(bundler_url: &str, entry_point: Address) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()?;
+        Ok(Self {
+            client,
+            bundler_url: bundler_url.to_string(),
+            entry_point,
+        })
+    }
+
+    pub fn entry_point(&self) -> Address {
+        self.entry_point
+    }
+
+    /// `eth_sendUserOperation`，返回 bundler 确认的 userOpHash。
+    pub async fn send_user_operation(&self, op: &UserOperation) -> Result<B256> {
+        let params = serde_json::json!([op.to_rpc(), self.entry_point]);
+        let hash: B256 = self.rpc("eth_sendUserOperation", params).await?;
+        tracing::info!("[bundler] userOpHash={hash} sender={} nonce={}", op.sender, op.nonce);
+        Ok(hash)
+    }
+
+    /// `eth_estimateUserOperationGas`。
+    ///
+    /// `op.signature` 需要是**格式正确的假签名**（长度与真实签名一致），否则账户的
+    /// `validateUserOp` 会在 ecrecover 前直接 revert。
+    pub async fn estimate_user_operation_gas(
+        &self,
+        op: &UserOperation,
+    ) -> Result<UserOpGasEstimate> {
+        let params = serde_json::json!([op.to_rpc(), self.entry_point]);
+        let est: GasEstimateResponse = self.rpc("eth_estimateUserOperationGas", params).await?;
+        Ok(UserOpGasEstimate {
+            pre_verification_gas: est.pre_verification_gas,
+            verification_gas_limit: est.verification_gas_limit.to(),
+            call_gas_limit: est.call_gas_limit.to(),
+            paymaster_verification_gas_limit: est.paymaster_verification_gas_limit.map(|g| g.to()),
+            paymaster_post_op_gas_limit: est.paymaster_post_op_gas_limit.map(|g| g.to()),
+        })
+    }
+
+    async fn rpc<T: DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<T> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let resp = self.client.post(&self.bundler_url).json(&body).send().await?;
+        let status = resp.status();
+        let text = resp.text().await?;
+        if !status.is_success() {
+            return Err(eyre!("bundler http {}: {}", status, text));
+        }
+
+        let parsed: RpcResponse<T> = serde_json::from_str(&text)
+            .map_err(|e| eyre!("decode bundler response failed: {e}; body={text}"))?;
+        if let Some(err) = parsed.error {
+            return Err(eyre!("bundler error {} ({method}): {}", err.code, err.message));
+        }
+        parsed
+            .result
+            .ok_or_else(|| eyre!("bundler response missing result: {text}"))
+    }
+}
+
+/// 通过 RPC 查 `EntryPoint.getNonce(sender, key)`。`key = 0` 即默认 nonce 序列。
+///
+/// fork 场景见 [`crate::simulator::user_op::get_nonce`]。
+pub async fn entry_point_nonce(
+    provider: &DynProvider<AnyNetwork>,
+    entry_point: Address,
+    sender: Address,
+    key: U192,
+) -> Result<U256> {
+    let req = TransactionRequest::default()
+        .with_to(entry_point)
+        .with_input(Bytes::from(getNonceCall { sender, key }.abi_encode()));
+    let result = provider.call(req.into()).await?;
+    Ok(getNonceCall::abi_decode_returns(&result)?)
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GasEstimateResponse {
+    pre_verification_gas: U256,
+    verification_gas_limit: U256,
+    call_gas_limit: U256,
+    #[serde(default)]
+    paymaster_verification_gas_limit: Option<U256>,
+    #[serde(default)]
+    paymaster_post_op_gas_limit: Option<U256>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::user_op::ENTRY_POINT_V07, utils::testing::serve_json_rpc};
+    use alloy::primitives::address;
+
+    #[tokio::test]
+    async fn send_and_estimate_against_stub() {
+        let stub = serve_json_rpc(|method, params| {
+            let op = &params[0];
+            assert_eq!(params[1], "0x0000000071727de22e5e9d8baf0edac6f37da032");
+            assert_eq!(op["sender"], "0x1111111111111111111111111111111111111111");
+            assert_eq!(op["callGasLimit"], "0x186a0");
+            assert!(op.get("paymaster").is_none());
+            match method {
+                "eth_sendUserOperation" => Ok(serde_json::json!(format!("0x{}", "ab".repeat(32)))),
+                "eth_estimateUserOperationGas" => Ok(serde_json::json!({
+                    "preVerificationGas": "0xc350",
+                    "verificationGasLimit": "0x30d40",
+                    "callGasLimit": "0x7530",
+                })),
+                m => Err((-32601, format!("method {m} not found"))),
+            }
+        })
+        .await
+        .unwrap();
+
+        let bundler = BundlerSender::new(stub.url(), ENTRY_POINT_V07).unwrap();
+        let op = UserOperation {
+            sender: address!("1111111111111111111111111111111111111111"),
+            call_gas_limit: 100_000,
+            ..Default::default()
+        };
+
+        let hash = bundler.send_user_operation(&op).await.unwrap();
+        assert_eq!(hash, B256::repeat_byte(0xab));
+
+        let est = bundler.estimate_user_operation_gas(&op).await.unwrap();
+        assert_eq!(est.pre_verification_gas, U256::from(50_000));
+        assert_eq!(est.verification_gas_limit, 200_000);
+        assert_eq!(est.call_gas_limit, 30_000);
+        assert_eq!(est.paymaster_verification_gas_limit, None);
+        let op = op.with_gas_estimate(&est);
+        assert_eq!(op.call_gas_limit, 30_000);
+    }
+
+    #[tokio::test]
+    async fn bundler_error_is_surfaced() {
+        let stub = serve_json_rpc(|_, _| Err((-32500, "AA21 didn't pay prefund".into())))
+            .await
+            .unwrap();
+        let bundler = BundlerSender::new(stub.url(), ENTRY_POINT_V07).unwrap();
+        let err = bundler
+            .send_user_operation(&UserOperation::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("AA21"), "{err}");
+    }
+}
@@ -0,0 +1,387 @@
+use std::time::Duration;
+
+use alloy::{
+    consensus::TxEnvelope,
+    eips::Decodable2718,
+    network::AnyNetwork,
+    primitives::B256,
+    providers::{DynProvider, Provider, ProviderBuilder},
+};
+use eyre::{eyre, Result, WrapErr};
+
+use super::{bump_fees, AnySender, RawTx, TxHandle, TxSender, TxTracker};
+use crate::{AnySigner, TxSigner};
+
+/// 默认给私有通道的区块数。
+const DEFAULT_AFTER_BLOCKS: u64 = 3;
+
+/// 先走私有通道（private tx / bundle / MEV-Share），`after_blocks` 个块内没上链再公开广播。
+///
+/// - 私有通道发送报错时直接升级，不等
+/// - 是否上链按 tx receipt 判断，与私有通道返回的 hash 类型无关；nonce 已被别的交易
+///   用掉时报错，不再公开广播
+/// - 配了 [`with_resign`](Self::with_resign) 时公开广播前按比例加价重签（须是 EIP-1559
+///   交易且 signer 与交易 sender 一致），否则原样广播
+///
+/// 返回的是每笔交易最终被追踪的 hash：私有通道上链的为原交易 hash，升级的为公开广播的
+/// hash。部分上链时只升级没上链的那些。升级后私有版本仍可能先上链，同 nonce 只会成功一笔。
+pub struct EscalatingSender {
+    primary: AnySender,
+    public: AnySender,
+    provider: DynProvider<AnyNetwork>,
+    after_blocks: u64,
+    poll_interval: Duration,
+    resign: Option<(AnySigner, u64)>,
+}
+
+impl EscalatingSender {
+    /// `rpc_url` 用来读区块高度和 receipt；`public` 通常是 `RpcSender` 或 `FanoutSender`。
+    pub fn new(
+        primary: impl Into<AnySender>,
+        public: impl Into<AnySender>,
+        rpc_url: &str,
+    ) -> Result<Self> {
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_http(rpc_url.parse()?)
+            .erased();
+        Ok(Self {
+            primary: primary.into(),
+            public: public.into(),
+            provider,
+            after_blocks: DEFAULT_AFTER_BLOCKS,
+            poll_interval: Duration::from_secs(2),
+            resign: None,
+        })
+    }
+
+    pub fn primary(&self) -> &AnySender {
+        &self.primary
+    }
+
+    /// 私有通道发出后等多少个块（至少 1，默认 3）。
+    pub fn with_after_blocks(mut self, blocks: u64) -> Self {
+        self.after_blocks = blocks.max(1);
+        self
+    }
+
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// 公开广播前用 `signer` 按 `bump_percent` 加价重签。
+    pub fn with_resign(mut self, signer: impl Into<AnySigner>, bump_percent: u64) -> Self {
+        self.resign = Some((signer.into(), bump_percent));
+        self
+    }
+
+    /// 等到所有交易都有 receipt 或超过 `until` 块，返回每笔交易是否已上链。
+    ///
+    /// 与 [`TxHandle::wait`] 一样比对账户 nonce：某笔交易没有 receipt 但其 nonce 已被
+    /// 用掉（被替换 / 抢用），公开广播只会 "nonce too low"，直接报错。
+    async fn wait_included(&self, handles: &[TxHandle], until: u64) -> Result<Vec<bool>> {
+        let mut mined = vec![false; handles.len()];
+        loop {
+            for (h, mined) in handles.iter().zip(mined.iter_mut()) {
+                if *mined {
+                    continue;
+                }
+                if self.is_mined(h).await? {
+                    *mined = true;
+                    continue;
+                }
+                let mined_nonce = self.provider.get_transaction_count(h.from).await?;
+                // 再查一次 receipt，避免 "查 receipt 之后交易恰好上链" 的竞态
+                if mined_nonce > h.nonce {
+                    if self.is_mined(h).await? {
+                        *mined = true;
+                        continue;
+                    }
+                    return Err(eyre!(
+                        "tx {} replaced: nonce {} of {} used by another tx, not escalating",
+                        h.hash,
+                        h.nonce,
+                        h.from
+                    ));
+                }
+            }
+            if mined.iter().all(|&m| m) || self.provider.get_block_number().await? >= until {
+                return Ok(mined);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn is_mined(&self, h: &TxHandle) -> Result<bool> {
+        Ok(self
+            .provider
+            .get_transaction_receipt(h.hash)
+            .await?
+            .is_some())
+    }
+
+    async fn resigned(&self, txs: &[RawTx], handles: &[TxHandle]) -> Result<Vec<RawTx>> {
+        let Some((signer, percent)) = &self.resign else {
+            return Ok(txs.to_vec());
+        };
+        let mut out = Vec::with_capacity(txs.len());
+        for (raw, h) in txs.iter().zip(handles) {
+            eyre::ensure!(
+                h.from == signer.address(),
+                "cannot re-sign tx {} from {}: signer is {}",
+                h.hash,
+                h.from,
+                signer.address()
+            );
+            let envelope = TxEnvelope::decode_2718(&mut raw.0.as_ref())
+                .wrap_err("raw tx is not a valid EIP-2718 envelope")?;
+            let TxEnvelope::Eip1559(signed) = envelope else {
+                return Err(eyre!("can only re-sign EIP-1559 txs, {} is not", h.hash));
+            };
+            out.push(signer.sign(bump_fees(signed.tx(), *percent)).await?);
+        }
+        Ok(out)
+    }
+}
+
+impl TxSender for EscalatingSender {
+    async fn send_txs(&self, txs: &[RawTx]) -> Result<Vec<B256>> {
+        let handles = TxTracker::new(self.provider.clone()).track_all(txs)?;
+        let mut hashes: Vec<B256> = handles.iter().map(|h| h.hash).collect();
+
+        let mined = match self.primary.send_txs(txs).await {
+            Ok(_) => {
+                let until = self.provider.get_block_number().await? + self.after_blocks;
+                let mined = self.wait_included(&handles, until).await?;
+                if mined.iter().all(|&m| m) {
+                    tracing::info!("[escalation] included via private channel");
+                    return Ok(hashes);
+                }
+                tracing::warn!(
+                    "[escalation] {}/{} txs not included by block 0x{until:x}, escalating to public broadcast",
+                    mined.iter().filter(|&&m| !m).count(),
+                    txs.len()
+                );
+                mined
+            }
+            Err(e) => {
+                tracing::warn!("[escalation] private channel failed, escalating now: {e:#}");
+                vec![false; txs.len()]
+            }
+        };
+
+        // 已上链的交易 nonce 已用掉，只重签 / 广播没上链的那些
+        let pending: Vec<usize> = (0..txs.len()).filter(|&i| !mined[i]).collect();
+        let pending_txs: Vec<RawTx> = pending.iter().map(|&i| txs[i].clone()).collect();
+        let pending_handles: Vec<TxHandle> = pending.iter().map(|&i| handles[i].clone()).collect();
+        let public_txs = self.resigned(&pending_txs, &pending_handles).await?;
+        let public_hashes = self.public.send_txs(&public_txs).await?;
+        eyre::ensure!(
+            public_hashes.len() == pending.len(),
+            "public sender returned {} hashes for {} txs",
+            public_hashes.len(),
+            pending.len()
+        );
+        for (i, hash) in pending.into_iter().zip(public_hashes) {
+            hashes[i] = hash;
+        }
+        Ok(hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    use super::*;
+    use crate::{
+        sender::{PrivateSender, RpcSender},
+        utils::testing::{serve_http, serve_json_rpc, HttpResponse},
+        LocalSigner,
+    };
+    use alloy::{
+        consensus::{Transaction, TxEip1559},
+        primitives::{address, keccak256, TxKind, U256},
+        signers::local::PrivateKeySigner,
+    };
+    use serde_json::json;
+
+    fn signer() -> LocalSigner {
+        LocalSigner::new(PrivateKeySigner::from_bytes(&B256::repeat_byte(0x07)).unwrap())
+    }
+
+    async fn signed_tx(nonce: u64) -> RawTx {
+        signer()
+            .sign(TxEip1559 {
+                chain_id: 1,
+                nonce,
+                gas_limit: 21_000,
+                max_fee_per_gas: 100,
+                max_priority_fee_per_gas: 10,
+                to: TxKind::Call(address!("1111111111111111111111111111111111111111")),
+                value: U256::from(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn escalates_to_public_with_bumped_fees() {
+        // 私有通道接受但始终不上链；公共节点记录广播的 raw tx
+        let head = Arc::new(AtomicU64::new(0x10));
+        let broadcast = Arc::new(Mutex::new(Vec::<String>::new()));
+        let b = broadcast.clone();
+        let rpc = serve_json_rpc(move |method, params| match method {
+            "eth_blockNumber" => Ok(json!(format!(
+                "0x{:x}",
+                head.fetch_add(1, Ordering::SeqCst)
+            ))),
+            "eth_getTransactionReceipt" => Ok(json!(null)),
+            "eth_getTransactionCount" => Ok(json!("0x3")),
+            "eth_sendRawTransaction" => {
+                let raw = params[0].as_str().unwrap().to_string();
+                let hash = keccak256(alloy::hex::decode(&raw).unwrap());
+                b.lock().unwrap().push(raw);
+                Ok(json!(hash))
+            }
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let relay_hits = Arc::new(AtomicUsize::new(0));
+        let r = relay_hits.clone();
+        let relay = serve_http(move |_| {
+            r.fetch_add(1, Ordering::SeqCst);
+            let result = json!(B256::repeat_byte(0xaa));
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+        })
+        .await
+        .unwrap();
+
+        let private =
+            PrivateSender::with_relay(PrivateKeySigner::random(), rpc.url(), relay.url()).unwrap();
+        let sender = EscalatingSender::new(private, RpcSender::new(rpc.url()).unwrap(), rpc.url())
+            .unwrap()
+            .with_after_blocks(2)
+            .with_poll_interval(Duration::from_millis(10))
+            .with_resign(signer(), 20);
+
+        let tx = signed_tx(3).await;
+        let hashes = sender.send_txs(std::slice::from_ref(&tx)).await.unwrap();
+        assert_eq!(relay_hits.load(Ordering::SeqCst), 1);
+
+        let sent = broadcast.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        let raw = alloy::hex::decode(&sent[0]).unwrap();
+        assert_eq!(hashes, vec![keccak256(&raw)]);
+        let bumped = TxEnvelope::decode_2718(&mut raw.as_slice()).unwrap();
+        assert_eq!(bumped.nonce(), 3);
+        assert_eq!(bumped.max_fee_per_gas(), 120);
+        assert_eq!(bumped.max_priority_fee_per_gas(), Some(12));
+    }
+
+    #[tokio::test]
+    async fn escalates_only_unmined_txs() {
+        // 两笔交易走私有通道，nonce 3 上链、nonce 4 没有
+        let (tx0, tx1) = (signed_tx(3).await, signed_tx(4).await);
+        let mined = keccak256(&tx0.0);
+        let head = Arc::new(AtomicU64::new(0x10));
+        let broadcast = Arc::new(Mutex::new(Vec::<String>::new()));
+        let b = broadcast.clone();
+        let rpc = serve_json_rpc(move |method, params| match method {
+            "eth_blockNumber" => Ok(json!(format!(
+                "0x{:x}",
+                head.fetch_add(1, Ordering::SeqCst)
+            ))),
+            "eth_getTransactionReceipt" => {
+                let hash: B256 = serde_json::from_value(params[0].clone()).unwrap();
+                Ok(if hash == mined {
+                    json!({
+                        "type": "0x2", "status": "0x1", "transactionHash": hash,
+                        "transactionIndex": "0x0", "blockHash": B256::repeat_byte(1),
+                        "blockNumber": "0x10", "from": signer().address(), "to": null,
+                        "cumulativeGasUsed": "0x5208", "gasUsed": "0x5208",
+                        "effectiveGasPrice": "0x7", "contractAddress": null, "logs": [],
+                        "logsBloom": format!("0x{}", "00".repeat(256)),
+                    })
+                } else {
+                    json!(null)
+                })
+            }
+            "eth_getTransactionCount" => Ok(json!("0x4")),
+            "eth_sendRawTransaction" => {
+                let raw = params[0].as_str().unwrap().to_string();
+                let hash = keccak256(alloy::hex::decode(&raw).unwrap());
+                b.lock().unwrap().push(raw);
+                Ok(json!(hash))
+            }
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let relay = serve_http(|_| {
+            let result = json!(B256::repeat_byte(0xaa));
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+        })
+        .await
+        .unwrap();
+
+        let private =
+            PrivateSender::with_relay(PrivateKeySigner::random(), rpc.url(), relay.url()).unwrap();
+        let sender = EscalatingSender::new(private, RpcSender::new(rpc.url()).unwrap(), rpc.url())
+            .unwrap()
+            .with_after_blocks(2)
+            .with_poll_interval(Duration::from_millis(10))
+            .with_resign(signer(), 20);
+
+        let hashes = sender.send_txs(&[tx0, tx1]).await.unwrap();
+
+        let sent = broadcast.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        let raw = alloy::hex::decode(&sent[0]).unwrap();
+        let bumped = TxEnvelope::decode_2718(&mut raw.as_slice()).unwrap();
+        assert_eq!(bumped.nonce(), 4);
+        assert_eq!(bumped.max_fee_per_gas(), 120);
+        assert_eq!(hashes, vec![mined, keccak256(&raw)]);
+    }
+
+    #[tokio::test]
+    async fn does_not_escalate_replaced_nonce() {
+        // 私有通道接受，nonce 3 被另一笔交易用掉
+        let broadcasts = Arc::new(AtomicUsize::new(0));
+        let b = broadcasts.clone();
+        let rpc = serve_json_rpc(move |method, _| match method {
+            "eth_blockNumber" => Ok(json!("0x10")),
+            "eth_getTransactionReceipt" => Ok(json!(null)),
+            "eth_getTransactionCount" => Ok(json!("0x4")),
+            "eth_sendRawTransaction" => {
+                b.fetch_add(1, Ordering::SeqCst);
+                Ok(json!(B256::ZERO))
+            }
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let relay = serve_http(|_| {
+            let result = json!(B256::repeat_byte(0xaa));
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+        })
+        .await
+        .unwrap();
+
+        let private =
+            PrivateSender::with_relay(PrivateKeySigner::random(), rpc.url(), relay.url()).unwrap();
+        let sender = EscalatingSender::new(private, RpcSender::new(rpc.url()).unwrap(), rpc.url())
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(10));
+
+        let err = sender.send_txs(&[signed_tx(3).await]).await.err().unwrap();
+        assert!(err.to_string().contains("replaced"), "{err}");
+        assert_eq!(broadcasts.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,101 @@
+use alloy::primitives::B256;
+use eyre::{eyre, Result};
+use futures::{stream::FuturesUnordered, StreamExt};
+
+use super::{RawTx, RpcSender, TxSender};
+
+/// 同时向多个公共 RPC 广播同一批交易，任意一个接受即成功。
+///
+/// 多个节点同时广播能更快扩散到 mempool，也能绕过单个节点的限流 / 故障。
+/// 返回最先接受的节点给的 tx hash，不等其余节点；其余节点的请求在后台继续完成
+/// （结果只打日志）。
+pub struct FanoutSender {
+    endpoints: Vec<(String, RpcSender)>,
+}
+
+impl FanoutSender {
+    pub fn new(urls: &[String]) -> Result<Self> {
+        eyre::ensure!(!urls.is_empty(), "fanout sender needs at least one rpc url");
+        let endpoints = urls
+            .iter()
+            .map(|url| Ok((url.clone(), RpcSender::new(url)?)))
+            .collect::<Result<_>>()?;
+        Ok(Self { endpoints })
+    }
+}
+
+impl TxSender for FanoutSender {
+    async fn send_txs(&self, txs: &[RawTx]) -> Result<Vec<B256>> {
+        // 每个节点一个 task：返回后未完成的 task 不会被取消
+        let mut pending: FuturesUnordered<_> = self
+            .endpoints
+            .iter()
+            .map(|(url, sender)| {
+                let (url, sender, txs) = (url.clone(), sender.clone(), txs.to_vec());
+                tokio::spawn(async move {
+                    let result = sender.send_txs(&txs).await;
+                    match &result {
+                        Ok(hashes) => tracing::info!("[fanout] {url} accepted {} txs", hashes.len()),
+                        Err(e) => tracing::warn!("[fanout] {url} rejected: {e:#}"),
+                    }
+                    (url, result)
+                })
+            })
+            .collect();
+
+        let mut errors = Vec::new();
+        while let Some(joined) = pending.next().await {
+            let (url, result) = joined?;
+            match result {
+                Ok(hashes) => return Ok(hashes),
+                Err(e) => errors.push(format!("{url}: {e}")),
+            }
+        }
+        Err(eyre!("all rpc endpoints rejected: {}", errors.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::{serve_http, serve_json_rpc, HttpResponse};
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn succeeds_if_any_endpoint_accepts() {
+        let good = serve_json_rpc(|method, _| match method {
+            "eth_sendRawTransaction" => Ok(serde_json::json!(B256::repeat_byte(0xcd))),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let bad = serve_json_rpc(|_, _| Err((-32000, "rate limited".into())))
+            .await
+            .unwrap();
+        let txs = [RawTx::from(vec![0x02, 0x01])];
+
+        let sender = FanoutSender::new(&[bad.url().into(), good.url().into()]).unwrap();
+        assert_eq!(
+            sender.send_txs(&txs).await.unwrap(),
+            vec![B256::repeat_byte(0xcd)]
+        );
+
+        // 挂起的节点不拖慢结果
+        let hanging = serve_http(|_| {
+            HttpResponse::json(200, &serde_json::json!({})).with_delay(Duration::from_secs(5))
+        })
+        .await
+        .unwrap();
+        let sender = FanoutSender::new(&[hanging.url().into(), good.url().into()]).unwrap();
+        let start = Instant::now();
+        assert_eq!(
+            sender.send_txs(&txs).await.unwrap(),
+            vec![B256::repeat_byte(0xcd)]
+        );
+        assert!(start.elapsed() < Duration::from_secs(2));
+
+        let sender = FanoutSender::new(&[bad.url().into()]).unwrap();
+        let err = sender.send_txs(&txs).await.unwrap_err();
+        assert!(err.to_string().contains("rate limited"), "{err}");
+    }
+}
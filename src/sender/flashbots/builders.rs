@@ -0,0 +1,361 @@
+use std::time::{Duration, Instant};
+
+use alloy::primitives::B256;
+use serde::Deserialize;
+
+/// 硬编码 builder 列表（name, rpc url）。来源：Flashbots dowg builder registry。
+/// 没带 scheme 的 URL 会在发送时自动补 https://。
+const BUILDERS: &[(&str, &str)] = &[
+    ("flashbots", "rpc.flashbots.net"),
+    ("f1b.io", "https://rpc.f1b.io"),
+    ("rsync", "rsync-builder.xyz"),
+    ("beaverbuild.org", "mevshare-rpc.beaverbuild.org"),
+    ("builder0x69", "builder0x69.io"),
+    ("Titan", "rpc.titanbuilder.xyz"),
+    ("EigenPhi", "builder.eigenphi.io"),
+    ("boba-builder", "boba-builder.com/searcher/bundle"),
+    ("Gambit Labs", "https://builder.gmbit.co/rpc"),
+    ("payload", "rpc.payload.de"),
+    ("Loki", "rpc.lokibuilder.xyz"),
+    ("BuildAI", "https://buildai.net"),
+    ("JetBuilder", "rpc.mevshare.jetbldr.xyz"),
+    ("tbuilder", "flashbots.rpc.tbuilder.xyz"),
+    ("penguinbuild", "rpc.penguinbuild.org"),
+    ("bobthebuilder", "rpc.bobthebuilder.xyz"),
+    ("BTCS", "flashbots.btcs.com"),
+    ("bloXroute", "rpc-builder.blxrbdn.com"),
+    ("Blockbeelder", "https://blockbeelder.com/rpc"),
+    ("Quasar", "rpc.quasar.win"),
+    ("Eureka", "rpc.eurekabuilder.xyz"),
+];
+
+/// 单个 builder 的默认 HTTP 超时。
+const DEFAULT_BUILDER_TIMEOUT_MS: u64 = 1_000;
+
+/// 一个 Flashbots-兼容 builder 的 RPC 端点。`url` 可省略 scheme（自动补 https://）。
+///
+/// ```json
+/// { "name": "bloXroute", "url": "rpc-builder.blxrbdn.com",
+///   "auth": { "type": "header", "name": "Authorization", "value_env": "BLXR_AUTH" },
+///   "timeout_ms": 2000, "retries": 1 }
+/// ```
+///
+/// `Debug` 不输出 auth 值和 `headers` 的值（启动时会打印整份 sender 配置）。
+#[derive(Clone, Deserialize)]
+pub struct BuilderEndpoint {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub auth: BuilderAuth,
+    /// 额外固定 header（如 builder 要求的 `X-Api-Key` 以外的标记）。
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// 超时 / 连接错误 / 5xx / 429 时的重试次数（不含首次）。
+    #[serde(default)]
+    pub retries: u32,
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_BUILDER_TIMEOUT_MS
+}
+
+/// builder 的认证方式。`Debug` 隐去 `value`。
+#[derive(Clone, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BuilderAuth {
+    /// `X-Flashbots-Signature`（默认）。
+    #[default]
+    Flashbots,
+    /// 不带任何认证 header。
+    None,
+    /// 自定义 header（如 bloXroute 的 `Authorization`）。值直接给或从环境变量读。
+    Header {
+        name: String,
+        #[serde(default)]
+        value: Option<String>,
+        #[serde(default)]
+        value_env: Option<String>,
+    },
+}
+
+const REDACTED: &str = "<redacted>";
+
+impl std::fmt::Debug for BuilderEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(k, _)| (k.as_str(), REDACTED))
+            .collect();
+        f.debug_struct("BuilderEndpoint")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("auth", &self.auth)
+            .field("headers", &headers)
+            .field("timeout_ms", &self.timeout_ms)
+            .field("retries", &self.retries)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for BuilderAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Flashbots => f.write_str("Flashbots"),
+            Self::None => f.write_str("None"),
+            Self::Header {
+                name,
+                value,
+                value_env,
+            } => f
+                .debug_struct("Header")
+                .field("name", name)
+                .field("value", &value.as_ref().map(|_| REDACTED))
+                .field("value_env", value_env)
+                .finish(),
+        }
+    }
+}
+
+impl BuilderEndpoint {
+    pub fn new(name: &str, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            auth: BuilderAuth::default(),
+            headers: Vec::new(),
+            timeout_ms: DEFAULT_BUILDER_TIMEOUT_MS,
+            retries: 0,
+        }
+    }
+
+    /// 内置的 builder 列表（见 `BUILDERS`），全部用 Flashbots 签名认证。
+    pub fn defaults() -> Vec<Self> {
+        BUILDERS
+            .iter()
+            .map(|(name, url)| Self::new(name, url))
+            .collect()
+    }
+
+    fn normalized_url(&self) -> String {
+        if self.url.starts_with("http://") || self.url.starts_with("https://") {
+            self.url.clone()
+        } else {
+            format!("https://{}", self.url)
+        }
+    }
+
+    fn auth_header(&self, flashbots_sig: &str) -> Result<Option<(String, String)>, String> {
+        match &self.auth {
+            BuilderAuth::Flashbots => Ok(Some((
+                "X-Flashbots-Signature".into(),
+                flashbots_sig.to_string(),
+            ))),
+            BuilderAuth::None => Ok(None),
+            BuilderAuth::Header {
+                name,
+                value,
+                value_env,
+            } => {
+                let value = match (value, value_env) {
+                    (Some(v), _) => v.clone(),
+                    (None, Some(env)) => {
+                        std::env::var(env).map_err(|_| format!("auth env {env} not set"))?
+                    }
+                    (None, None) => {
+                        return Err("auth header has neither value nor value_env".into())
+                    }
+                };
+                Ok(Some((name.clone(), value)))
+            }
+        }
+    }
+
+    /// 把已签名的 body POST 给该 builder，按配置重试；永不返回错误，结果都记在 [`BuilderResult`]。
+    pub(super) async fn submit(
+        &self,
+        client: &reqwest::Client,
+        body: &[u8],
+        flashbots_sig: &str,
+    ) -> BuilderResult {
+        let start = Instant::now();
+        let mut result = BuilderResult {
+            builder: self.name.clone(),
+            status: None,
+            latency: Duration::ZERO,
+            attempts: 0,
+            bundle_hash: None,
+            error: None,
+        };
+        let auth = match self.auth_header(flashbots_sig) {
+            Ok(a) => a,
+            Err(e) => {
+                result.error = Some(e);
+                return result;
+            }
+        };
+        let url = self.normalized_url();
+
+        loop {
+            result.attempts += 1;
+            let mut req = client
+                .post(&url)
+                .timeout(Duration::from_millis(self.timeout_ms))
+                .header("Content-Type", "application/json")
+                .body(body.to_vec());
+            if let Some((k, v)) = &auth {
+                req = req.header(k, v);
+            }
+            for (k, v) in &self.headers {
+                req = req.header(k, v);
+            }
+
+            let retryable = match req.send().await {
+                Ok(resp) => {
+                    let status = resp.status();
+                    result.status = Some(status.as_u16());
+                    let text = resp
+                        .text()
+                        .await
+                        .unwrap_or_else(|e| format!("<body read error: {e}>"));
+                    match parse_builder_response(&text) {
+                        Ok(hash) if status.is_success() => {
+                            result.bundle_hash = hash;
+                            result.error = None;
+                            false
+                        }
+                        Ok(_) => {
+                            result.error = Some(format!("http {status}: {text}"));
+                            status.is_server_error() || status.as_u16() == 429
+                        }
+                        Err(e) => {
+                            result.error = Some(e);
+                            status.is_server_error() || status.as_u16() == 429
+                        }
+                    }
+                }
+                Err(e) => {
+                    result.status = None;
+                    result.error = Some(format!("http error: {e}"));
+                    true
+                }
+            };
+            if !retryable || result.attempts > self.retries {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100 * result.attempts as u64)).await;
+        }
+        result.latency = start.elapsed();
+        result
+    }
+}
+
+/// 解析 builder 的 JSON-RPC 响应：成功返回 bundleHash（部分 builder 不回 hash，为 `None`）。
+fn parse_builder_response(text: &str) -> Result<Option<B256>, String> {
+    #[derive(Deserialize)]
+    struct Resp {
+        #[serde(default)]
+        result: Option<serde_json::Value>,
+        #[serde(default)]
+        error: Option<RpcError>,
+    }
+    #[derive(Deserialize)]
+    struct RpcError {
+        #[serde(default)]
+        code: i64,
+        #[serde(default)]
+        message: String,
+    }
+
+    let resp: Resp =
+        serde_json::from_str(text).map_err(|e| format!("invalid response ({e}): {text}"))?;
+    if let Some(err) = resp.error {
+        return Err(format!("rpc error {}: {}", err.code, err.message));
+    }
+    Ok(resp
+        .result
+        .as_ref()
+        .and_then(|r| r.get("bundleHash"))
+        .and_then(|h| serde_json::from_value(h.clone()).ok()))
+}
+
+/// 单个 builder 的提交结果。
+#[derive(Debug, Clone)]
+pub struct BuilderResult {
+    pub builder: String,
+    /// 最后一次尝试的 HTTP 状态码；连接失败 / 超时为 `None`。
+    pub status: Option<u16>,
+    /// 含重试在内的总耗时。
+    pub latency: Duration,
+    pub attempts: u32,
+    pub bundle_hash: Option<B256>,
+    pub error: Option<String>,
+}
+
+impl BuilderResult {
+    pub fn accepted(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// 一次 `send_bundle`（单个 target block）在所有 builder 上的结果。
+#[derive(Debug, Clone)]
+pub struct BundleSubmissionReport {
+    pub target_block: u64,
+    pub results: Vec<BuilderResult>,
+}
+
+impl BundleSubmissionReport {
+    pub fn accepted(&self) -> usize {
+        self.results.iter().filter(|r| r.accepted()).count()
+    }
+
+    pub fn rejected(&self) -> usize {
+        self.results.len() - self.accepted()
+    }
+
+    /// 超过半数 builder 拒绝 / 不可达，调用方可据此告警。
+    pub fn mostly_rejected(&self) -> bool {
+        self.rejected() * 2 > self.results.len()
+    }
+
+    /// 第一个接受的 builder 返回的 bundleHash。
+    pub fn bundle_hash(&self) -> Option<B256> {
+        self.results.iter().find_map(|r| r.bundle_hash)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &BuilderResult> {
+        self.results.iter().filter(|r| !r.accepted())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_redacts_secrets() {
+        let endpoint = BuilderEndpoint {
+            auth: BuilderAuth::Header {
+                name: "Authorization".into(),
+                value: Some("blxr-secret-token".into()),
+                value_env: Some("BLXR_AUTH".into()),
+            },
+            headers: vec![("X-Api-Key".into(), "api-secret".into())],
+            ..BuilderEndpoint::new("bloXroute", "rpc-builder.blxrbdn.com")
+        };
+        let printed = format!("{:?}", vec![endpoint]);
+        assert!(!printed.contains("secret"), "{printed}");
+        for visible in [
+            "bloXroute",
+            "rpc-builder.blxrbdn.com",
+            "Authorization",
+            "BLXR_AUTH",
+            "X-Api-Key",
+        ] {
+            assert!(printed.contains(visible), "{printed}");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use alloy::primitives::{Address, B256};
+use eyre::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// `eth_sendBundle` 除 `txs` / `blockNumber` 以外的可选参数。
+///
+/// ```json
+/// { "reverting_tx_hashes": ["0x.."], "replacement_uuid": "2b0f..", "refund_percent": 90,
+///   "refund_recipient": "0x.." }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BundleOptions {
+    /// bundle 只在 `timestamp >= min_timestamp` 的区块有效（unix 秒）。
+    #[serde(default)]
+    pub min_timestamp: Option<u64>,
+    /// bundle 只在 `timestamp <= max_timestamp` 的区块有效（unix 秒）。
+    #[serde(default)]
+    pub max_timestamp: Option<u64>,
+    /// 允许 revert 的交易；revert 时 bundle 仍可被打包。
+    #[serde(default)]
+    pub reverting_tx_hashes: Vec<B256>,
+    /// 允许被丢弃（revert 或 nonce 失效）的交易，丢弃后其余交易照常打包。
+    #[serde(default)]
+    pub dropping_tx_hashes: Vec<B256>,
+    /// 相同 uuid 的新 bundle 替换旧 bundle；也是 [`cancel_bundle`] 的依据。
+    ///
+    /// builder 对同一 uuid 只保留最后收到的 bundle，所以设了 uuid 时
+    /// `block_window` 必须为 1（否则发送报错），需要多 block 覆盖时逐块重发。
+    ///
+    /// [`cancel_bundle`]: super::FlashbotsSender::cancel_bundle
+    #[serde(default)]
+    pub replacement_uuid: Option<String>,
+    /// 第一笔交易的 builder 收益中退还给 `refund_recipient` 的百分比（0–99）。
+    #[serde(default)]
+    pub refund_percent: Option<u8>,
+    /// 退款地址，不填默认为第一笔交易的 sender。
+    #[serde(default)]
+    pub refund_recipient: Option<Address>,
+}
+
+impl BundleOptions {
+    /// `replacement_uuid` 只能配单个 target block，见字段文档。
+    pub(crate) fn check_block_window(&self, block_window: u64) -> Result<()> {
+        eyre::ensure!(
+            self.replacement_uuid.is_none() || block_window <= 1,
+            "bundle replacement_uuid requires block_window = 1 (got {block_window}): \
+             builders keep only the latest bundle per uuid"
+        );
+        Ok(())
+    }
+
+    /// 组装 `eth_sendBundle` 的参数对象，未设置的字段不出现在请求里。
+    pub(super) fn to_params(&self, txs: Vec<String>, target_block: u64) -> Result<Value> {
+        if let (Some(min), Some(max)) = (self.min_timestamp, self.max_timestamp) {
+            eyre::ensure!(
+                min <= max,
+                "bundle min_timestamp {min} > max_timestamp {max}"
+            );
+        }
+        if let Some(p) = self.refund_percent {
+            eyre::ensure!(p < 100, "bundle refund_percent must be 0-99, got {p}");
+        }
+        eyre::ensure!(
+            self.refund_recipient.is_none() || self.refund_percent.is_some(),
+            "bundle refund_recipient requires refund_percent"
+        );
+
+        let mut params = json!({
+            "txs": txs,
+            "blockNumber": format!("0x{target_block:x}"),
+        });
+        if let Some(t) = self.min_timestamp {
+            params["minTimestamp"] = json!(t);
+        }
+        if let Some(t) = self.max_timestamp {
+            params["maxTimestamp"] = json!(t);
+        }
+        if !self.reverting_tx_hashes.is_empty() {
+            params["revertingTxHashes"] = json!(self.reverting_tx_hashes);
+        }
+        if !self.dropping_tx_hashes.is_empty() {
+            params["droppingTxHashes"] = json!(self.dropping_tx_hashes);
+        }
+        if let Some(uuid) = &self.replacement_uuid {
+            params["replacementUuid"] = json!(uuid);
+        }
+        if let Some(p) = self.refund_percent {
+            params["refundPercent"] = json!(p);
+        }
+        if let Some(r) = self.refund_recipient {
+            params["refundRecipient"] = json!(r);
+        }
+        Ok(params)
+    }
+
+    /// preflight 时可以容忍 revert 的交易（`reverting_tx_hashes` + `dropping_tx_hashes`）。
+    pub(super) fn allowed_reverts(&self) -> impl Iterator<Item = &B256> {
+        self.reverting_tx_hashes
+            .iter()
+            .chain(&self.dropping_tx_hashes)
+    }
+}
@@ -0,0 +1,347 @@
+use std::time::Duration;
+
+use alloy::{network::ReceiptResponse, primitives::B256, providers::Provider};
+use eyre::{eyre, Result};
+use serde::Deserialize;
+
+use super::{signed_rpc, FlashbotsSender};
+use crate::sender::{RawTx, TxHandle, TxTracker};
+
+/// 等 target block 出块的最长时间（约 10 个 slot）。超过说明节点卡住了。
+const MAX_BLOCK_WAIT: Duration = Duration::from_secs(120);
+
+/// `flashbots_getBundleStatsV2` 的结果：relay 侧看到的 bundle 状态。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleStats {
+    #[serde(default)]
+    pub is_high_priority: bool,
+    #[serde(default)]
+    pub is_simulated: bool,
+    #[serde(default)]
+    pub simulated_at: Option<String>,
+    #[serde(default)]
+    pub received_at: Option<String>,
+    /// 把 bundle 纳入候选的 builder。
+    #[serde(default)]
+    pub considered_by_builders_at: Vec<BuilderSeen>,
+    /// 把 bundle 封进提交给 relay 的区块的 builder。
+    #[serde(default)]
+    pub sealed_by_builders_at: Vec<BuilderSeen>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuilderSeen {
+    pub pubkey: String,
+    pub timestamp: String,
+}
+
+/// bundle 的最终结果，见 [`FlashbotsSender::check_inclusion`]。
+#[derive(Debug, Clone)]
+pub enum BundleOutcome {
+    /// 所有交易都已上链；`block` 为其中最早的区块。
+    Included { block: u64 },
+    /// 只有部分交易上链（`droppingTxHashes` 被丢弃，或有交易另走了公共 mempool）。
+    PartiallyIncluded {
+        block: u64,
+        included: Vec<B256>,
+        missing: Vec<B256>,
+    },
+    /// `hash` 的 nonce 已被链上其它交易用掉，bundle 永远不会再被打包。
+    NonceInvalidated { hash: B256, nonce: u64 },
+    /// 整个窗口都没上链。`stats` 为 relay 侧状态（查不到时为 `None`）。
+    NotIncluded { stats: Option<BundleStats> },
+}
+
+impl FlashbotsSender {
+    /// [`check_inclusion`](Self::check_inclusion) 等 target block 时的轮询间隔（默认 1s）。
+    pub fn with_inclusion_poll_interval(mut self, interval: Duration) -> Self {
+        self.inclusion_poll = interval;
+        self
+    }
+
+    /// `flashbots_getBundleStatsV2`（需要与发送时同一个 auth signer）。
+    pub async fn bundle_stats(&self, bundle_hash: B256, target_block: u64) -> Result<BundleStats> {
+        signed_rpc(
+            &self.relay_client,
+            &self.relay_url,
+            &self.auth_signer,
+            "flashbots_getBundleStatsV2",
+            serde_json::json!([{
+                "bundleHash": bundle_hash,
+                "blockNumber": format!("0x{target_block:x}"),
+            }]),
+        )
+        .await
+    }
+
+    /// 等到 `last_target` 出块后按 receipt 判断 bundle 结果。
+    ///
+    /// 整个 bundle 都没上链时再查 nonce：被占用则为 `NonceInvalidated`，否则带上
+    /// `bundle_hash`（若有）对应的 relay 统计返回 `NotIncluded`。
+    pub async fn check_inclusion(
+        &self,
+        txs: &[RawTx],
+        bundle_hash: Option<B256>,
+        last_target: u64,
+    ) -> Result<BundleOutcome> {
+        let handles = TxTracker::new(self.provider.clone()).track_all(txs)?;
+        self.wait_for_block(last_target).await?;
+
+        let mut included = Vec::new();
+        let mut missing = Vec::new();
+        let mut first_block = None::<u64>;
+        for h in &handles {
+            match self.provider.get_transaction_receipt(h.hash).await? {
+                Some(r) => {
+                    let block = r.block_number().unwrap_or(last_target);
+                    first_block = Some(first_block.map_or(block, |b| b.min(block)));
+                    included.push(h.hash);
+                }
+                None => missing.push(h.hash),
+            }
+        }
+        if let Some(block) = first_block {
+            return Ok(if missing.is_empty() {
+                BundleOutcome::Included { block }
+            } else {
+                BundleOutcome::PartiallyIncluded {
+                    block,
+                    included,
+                    missing,
+                }
+            });
+        }
+
+        if let Some(h) = self.nonce_taken(&handles).await? {
+            return Ok(BundleOutcome::NonceInvalidated {
+                hash: h.hash,
+                nonce: h.nonce,
+            });
+        }
+        let stats = match bundle_hash {
+            Some(hash) => match self.bundle_stats(hash, last_target).await {
+                Ok(stats) => Some(stats),
+                Err(e) => {
+                    tracing::warn!("[flashbots] getBundleStatsV2 {hash} failed: {e:#}");
+                    None
+                }
+            },
+            None => None,
+        };
+        Ok(BundleOutcome::NotIncluded { stats })
+    }
+
+    /// 发送 → 等窗口结束 → 没上链就对新的窗口重发，最多 `max_rounds` 轮。
+    ///
+    /// 只有 `NotIncluded` 会重发；其余结果（包括 nonce 失效）直接返回。
+    /// `max_rounds` 为 0 时报错，不发送。
+    pub async fn send_until_included(
+        &self,
+        txs: &[RawTx],
+        max_rounds: usize,
+    ) -> Result<BundleOutcome> {
+        eyre::ensure!(max_rounds > 0, "max_rounds must be at least 1");
+        let mut round = 0;
+        loop {
+            round += 1;
+            let reports = self.send_window(txs).await?;
+            let last = reports.last().expect("block window is at least 1");
+            let outcome = self
+                .check_inclusion(txs, last.bundle_hash(), last.target_block)
+                .await?;
+            match &outcome {
+                BundleOutcome::NotIncluded { stats } if round < max_rounds => {
+                    tracing::warn!(
+                        "[flashbots] round {round}/{max_rounds}: not included by block 0x{:x}, resubmitting (stats: {stats:?})",
+                        last.target_block
+                    );
+                }
+                _ => {
+                    tracing::info!("[flashbots] round {round}: {outcome:?}");
+                    return Ok(outcome);
+                }
+            }
+        }
+    }
+
+    async fn wait_for_block(&self, block: u64) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + MAX_BLOCK_WAIT;
+        while self.provider.get_block_number().await? < block {
+            if tokio::time::Instant::now() >= deadline {
+                return Err(eyre!("timed out waiting for block 0x{block:x}"));
+            }
+            tokio::time::sleep(self.inclusion_poll).await;
+        }
+        Ok(())
+    }
+
+    /// 第一笔 nonce 已被链上其它交易用掉的 bundle 交易。
+    async fn nonce_taken<'h>(&self, handles: &'h [TxHandle]) -> Result<Option<&'h TxHandle>> {
+        for h in handles {
+            let next = self.provider.get_transaction_count(h.from).latest().await?;
+            if next > h.nonce {
+                return Ok(Some(h));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::{
+        sender::BuilderEndpoint,
+        utils::testing::{serve_http, serve_json_rpc, HttpResponse, StubServer},
+        LocalSigner, TxSigner,
+    };
+    use alloy::{
+        consensus::TxEip1559,
+        primitives::{address, TxKind, U256},
+        signers::local::PrivateKeySigner,
+    };
+    use serde_json::json;
+
+    async fn signed_tx(nonce: u64) -> RawTx {
+        let signer =
+            LocalSigner::new(PrivateKeySigner::from_bytes(&B256::repeat_byte(0x07)).unwrap());
+        signer
+            .sign(TxEip1559 {
+                chain_id: 1,
+                nonce,
+                gas_limit: 21_000,
+                max_fee_per_gas: 10,
+                max_priority_fee_per_gas: 1,
+                to: TxKind::Call(address!("1111111111111111111111111111111111111111")),
+                value: U256::from(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+    }
+
+    /// 每次 `eth_blockNumber` 前进一个块；head 到 `mined_from_block` 后返回 receipt，
+    /// `chain_nonce` 为 `eth_getTransactionCount` 的返回值。
+    async fn setup(
+        mined_from_block: u64,
+        chain_nonce: u64,
+    ) -> (
+        FlashbotsSender,
+        Vec<StubServer>,
+        Arc<AtomicUsize>,
+        Arc<AtomicUsize>,
+    ) {
+        let head = Arc::new(AtomicU64::new(0x10));
+        let rpc = serve_json_rpc(move |method, params| match method {
+            "eth_blockNumber" => Ok(json!(format!(
+                "0x{:x}",
+                head.fetch_add(1, Ordering::SeqCst)
+            ))),
+            "eth_getTransactionReceipt" => {
+                let block = head.load(Ordering::SeqCst) - 1;
+                if block < mined_from_block {
+                    return Ok(json!(null));
+                }
+                Ok(json!({
+                    "type": "0x2",
+                    "status": "0x1",
+                    "transactionHash": params[0],
+                    "transactionIndex": "0x0",
+                    "blockHash": B256::repeat_byte(0xbb),
+                    "blockNumber": format!("0x{block:x}"),
+                    "from": "0x0000000000000000000000000000000000000000",
+                    "to": "0x1111111111111111111111111111111111111111",
+                    "cumulativeGasUsed": "0x5208",
+                    "gasUsed": "0x5208",
+                    "effectiveGasPrice": "0x7",
+                    "contractAddress": null,
+                    "logs": [],
+                    "logsBloom": format!("0x{}", "00".repeat(256)),
+                }))
+            }
+            "eth_getTransactionCount" => Ok(json!(format!("0x{chain_nonce:x}"))),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let sends = Arc::new(AtomicUsize::new(0));
+        let s = sends.clone();
+        let builder = serve_http(move |_| {
+            s.fetch_add(1, Ordering::SeqCst);
+            let result = json!({ "bundleHash": format!("0x{}", "cc".repeat(32)) });
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+        })
+        .await
+        .unwrap();
+        let stats_calls = Arc::new(AtomicUsize::new(0));
+        let c = stats_calls.clone();
+        let relay = serve_http(move |req| {
+            let body = req.json().unwrap();
+            assert_eq!(body["method"], "flashbots_getBundleStatsV2");
+            assert_eq!(body["params"][0]["bundleHash"], json!(B256::repeat_byte(0xcc)));
+            c.fetch_add(1, Ordering::SeqCst);
+            let result = json!({
+                "isHighPriority": true,
+                "isSimulated": true,
+                "receivedAt": "2024-01-01T00:00:00Z",
+                "consideredByBuildersAt": [{ "pubkey": "0xaa", "timestamp": "2024-01-01T00:00:01Z" }],
+                "sealedByBuildersAt": [],
+            });
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+        })
+        .await
+        .unwrap();
+
+        let sender = FlashbotsSender::new(PrivateKeySigner::random(), rpc.url())
+            .unwrap()
+            .with_relay(relay.url())
+            .with_builders(vec![BuilderEndpoint::new("stub", builder.url())])
+            .with_block_window(1)
+            .with_inclusion_poll_interval(Duration::from_millis(10));
+        (sender, vec![rpc, builder, relay], sends, stats_calls)
+    }
+
+    #[tokio::test]
+    async fn resubmits_until_included() {
+        // 第一轮窗口 0x11 没上链，第二轮（0x13 起）上链
+        let (sender, _stubs, sends, stats_calls) = setup(0x13, 5).await;
+        let txs = [signed_tx(5).await, signed_tx(6).await];
+        let outcome = sender.send_until_included(&txs, 3).await.unwrap();
+        let BundleOutcome::Included { block } = outcome else {
+            panic!("expected inclusion, got {outcome:?}");
+        };
+        assert!(block >= 0x13);
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+        assert_eq!(stats_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn reports_not_included_and_nonce_invalidated() {
+        let txs = [signed_tx(5).await];
+
+        let (sender, _stubs, sends, _) = setup(u64::MAX, 5).await;
+        assert!(sender.send_until_included(&txs, 0).await.is_err());
+        let outcome = sender.send_until_included(&txs, 2).await.unwrap();
+        let BundleOutcome::NotIncluded { stats: Some(stats) } = outcome else {
+            panic!("expected not included with stats, got {outcome:?}");
+        };
+        assert!(stats.is_simulated);
+        assert_eq!(stats.considered_by_builders_at.len(), 1);
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+
+        let (sender, _stubs, sends, stats_calls) = setup(u64::MAX, 6).await;
+        let outcome = sender.send_until_included(&txs, 3).await.unwrap();
+        assert!(matches!(
+            outcome,
+            BundleOutcome::NonceInvalidated { nonce: 5, .. }
+        ));
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+        assert_eq!(stats_calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,524 @@
+mod builders;
+mod bundle;
+mod inclusion;
+mod simulate;
+
+pub use builders::{BuilderAuth, BuilderEndpoint, BuilderResult, BundleSubmissionReport};
+pub use bundle::BundleOptions;
+pub use inclusion::{BuilderSeen, BundleOutcome, BundleStats};
+pub use simulate::{BundlePreflight, BundleSimulation, BundleTxResult};
+
+use alloy::{
+    network::AnyNetwork,
+    primitives::B256,
+    providers::{DynProvider, Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+};
+use eyre::Result;
+use futures::future::join_all;
+
+use super::{
+    relay::{flashbots_signature, signed_rpc},
+    RawTx, TxSender,
+};
+
+/// `eth_callBundle` 默认发往的 relay。
+const DEFAULT_RELAY_URL: &str = "https://relay.flashbots.net";
+
+/// `send_txs` 默认覆盖的 target block 数（current + 1 ..= current + 3）。
+const DEFAULT_BLOCK_WINDOW: u64 = 3;
+
+/// 并发将 bundle 发送到所有 Flashbots-兼容 builder 的 RPC。不通过单一 relay 做
+/// `builders` 过滤；每笔 bundle 直接打到 builder 列表里的每个 builder
+/// （默认为内置列表，可用 [`with_builders`](Self::with_builders) 替换）。
+///
+/// 每个 builder 的认证方式 / 超时 / 重试见 [`BuilderEndpoint`]。
+pub struct FlashbotsSender {
+    auth_signer: PrivateKeySigner,
+    provider: DynProvider<AnyNetwork>,
+    client: reqwest::Client,
+    builders: Vec<BuilderEndpoint>,
+    block_window: u64,
+    relay_url: String,
+    relay_client: reqwest::Client,
+    preflight: Option<BundlePreflight>,
+    options: BundleOptions,
+    inclusion_poll: std::time::Duration,
+}
+
+impl FlashbotsSender {
+    pub fn new(auth_signer: PrivateKeySigner, rpc_url: &str) -> Result<Self> {
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_http(rpc_url.parse()?)
+            .erased();
+        // 超时按 builder 单独设置（BuilderEndpoint::timeout_ms）
+        let client = reqwest::Client::new();
+        let relay_client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(5))
+            .build()?;
+        Ok(Self {
+            auth_signer,
+            provider,
+            client,
+            builders: BuilderEndpoint::defaults(),
+            block_window: DEFAULT_BLOCK_WINDOW,
+            relay_url: DEFAULT_RELAY_URL.to_string(),
+            relay_client,
+            preflight: None,
+            options: BundleOptions::default(),
+            inclusion_poll: std::time::Duration::from_secs(1),
+        })
+    }
+
+    /// `eth_callBundle` 发往的 relay（默认 `https://relay.flashbots.net`）。
+    pub fn with_relay(mut self, relay_url: &str) -> Self {
+        self.relay_url = relay_url.to_string();
+        self
+    }
+
+    /// `send_txs` 发送前先 [`simulate_bundle`](Self::simulate_bundle)，不满足条件则不发。
+    pub fn with_preflight(mut self, preflight: BundlePreflight) -> Self {
+        self.preflight = Some(preflight);
+        self
+    }
+
+    /// `eth_callBundle`：在 latest 状态上模拟 bundle 在 `target_block` 的执行。
+    pub async fn simulate_bundle(
+        &self,
+        txs: &[RawTx],
+        target_block: u64,
+    ) -> Result<BundleSimulation> {
+        let params = serde_json::json!([{
+            "txs": raw_tx_hexes(txs),
+            "blockNumber": format!("0x{target_block:x}"),
+            "stateBlockNumber": "latest",
+        }]);
+        let sim: BundleSimulation = signed_rpc(
+            &self.relay_client,
+            &self.relay_url,
+            &self.auth_signer,
+            "eth_callBundle",
+            params,
+        )
+        .await?;
+        tracing::info!(
+            "[callBundle] block=0x{target_block:x} gas={} coinbase_diff={} gas_price={} reverts={}",
+            sim.total_gas_used,
+            sim.coinbase_diff,
+            sim.bundle_gas_price,
+            sim.results.iter().filter(|r| r.reverted()).count()
+        );
+        Ok(sim)
+    }
+
+    /// 替换 builder 列表。
+    pub fn with_builders(mut self, builders: Vec<BuilderEndpoint>) -> Self {
+        self.builders = builders;
+        self
+    }
+
+    /// `send_txs` 覆盖 current + 1 ..= current + `block_window`（至少 1）。
+    pub fn with_block_window(mut self, block_window: u64) -> Self {
+        self.block_window = block_window.max(1);
+        self
+    }
+
+    /// `send_txs` / [`send_bundle`](Self::send_bundle) 默认带上的 bundle 参数。
+    pub fn with_bundle_options(mut self, options: BundleOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 用默认 [`BundleOptions`] 发送，见 [`send_bundle_with`](Self::send_bundle_with)。
+    pub async fn send_bundle(
+        &self,
+        txs: &[RawTx],
+        target_block: u64,
+    ) -> Result<BundleSubmissionReport> {
+        self.send_bundle_with(txs, target_block, &self.options)
+            .await
+    }
+
+    /// 对指定目标区块的 bundle 并发打到所有 builder，返回每个 builder 的结果。
+    ///
+    /// 单个 builder 失败不会让整体返回 `Err`；超过半数拒绝时打 warn，调用方可再按
+    /// [`BundleSubmissionReport::mostly_rejected`] 告警。参数不合法时直接返回 `Err`。
+    pub async fn send_bundle_with(
+        &self,
+        txs: &[RawTx],
+        target_block: u64,
+        options: &BundleOptions,
+    ) -> Result<BundleSubmissionReport> {
+        // 注意：不带 `builders` 字段。
+        let params = options.to_params(raw_tx_hexes(txs), target_block)?;
+        let report = BundleSubmissionReport {
+            target_block,
+            results: self.broadcast("eth_sendBundle", params).await?,
+        };
+
+        for r in &report.results {
+            tracing::info!(
+                "[builder:{}] block=0x{target_block:x} status={:?} attempts={} latency={:?} hash={:?} error={:?}",
+                r.builder,
+                r.status,
+                r.attempts,
+                r.latency,
+                r.bundle_hash,
+                r.error
+            );
+        }
+        if report.mostly_rejected() {
+            tracing::warn!(
+                "[flashbots] block=0x{target_block:x}: {}/{} builders rejected the bundle",
+                report.rejected(),
+                report.results.len()
+            );
+        }
+        Ok(report)
+    }
+
+    /// 校验 bundle 参数与 `block_window` 的组合，构造完配置后可提前调用。
+    pub(crate) fn check_options(&self) -> Result<()> {
+        self.options.check_block_window(self.block_window)
+    }
+
+    /// [`send_txs`](TxSender::send_txs) 的完整版：返回每个 target block 的
+    /// [`BundleSubmissionReport`]（按 target block 升序）。
+    pub async fn send_window(&self, txs: &[RawTx]) -> Result<Vec<BundleSubmissionReport>> {
+        self.check_options()?;
+        let block = self.provider.get_block_number().await?;
+        if let Some(preflight) = &self.preflight {
+            let sim = self.simulate_bundle(txs, block + 1).await?;
+            let allowed: Vec<B256> = self.options.allowed_reverts().copied().collect();
+            preflight.check_allowing(&sim, &allowed)?;
+        }
+        let futures =
+            (1..=self.block_window).map(|offset| self.send_bundle(txs, block + offset));
+        let reports: Vec<BundleSubmissionReport> =
+            join_all(futures).await.into_iter().collect::<Result<_>>()?;
+        if reports.iter().all(|r| r.accepted() == 0) {
+            let errors: Vec<String> = reports[0]
+                .failures()
+                .map(|r| format!("{}: {}", r.builder, r.error.as_deref().unwrap_or_default()))
+                .collect();
+            eyre::bail!("no builder accepted the bundle: {}", errors.join("; "));
+        }
+        Ok(reports)
+    }
+
+    /// `eth_cancelBundle`：让所有 builder 丢弃 `replacementUuid` 为 `uuid` 的 bundle。
+    ///
+    /// builder 对取消请求通常只回 `null`，成功与否只能看 HTTP / RPC 错误；
+    /// 已经进入区块构建的 bundle 不保证能撤回。
+    pub async fn cancel_bundle(&self, uuid: &str) -> Result<Vec<BuilderResult>> {
+        let params = serde_json::json!({ "replacementUuid": uuid });
+        let results = self.broadcast("eth_cancelBundle", params).await?;
+        for r in &results {
+            tracing::info!(
+                "[builder:{}] cancel {uuid} status={:?} error={:?}",
+                r.builder,
+                r.status,
+                r.error
+            );
+        }
+        Ok(results)
+    }
+
+    /// 把单个参数对象的 JSON-RPC 请求签名一次后并发发给所有 builder。
+    async fn broadcast(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<Vec<BuilderResult>> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": [params]
+        });
+        let body_bytes = serde_json::to_vec(&body)?;
+
+        // Flashbots 风格签名（EIP-191 personal_sign）；只发给 auth = flashbots 的 builder。
+        let sig_header = flashbots_signature(&self.auth_signer, &body_bytes)?;
+
+        let futures = self
+            .builders
+            .iter()
+            .map(|b| b.submit(&self.client, &body_bytes, &sig_header));
+        Ok(join_all(futures).await)
+    }
+}
+
+fn raw_tx_hexes(txs: &[RawTx]) -> Vec<String> {
+    txs.iter()
+        .map(|tx| format!("0x{}", alloy::hex::encode(&tx.0)))
+        .collect()
+}
+
+impl TxSender for FlashbotsSender {
+    /// 并发发送 bundle 到 current_block + 1 ~ current_block + block_window（默认 3，
+    /// 见 [`with_block_window`](FlashbotsSender::with_block_window)），每个 target block
+    /// 内部再 fan-out 到所有 builder，参数取 [`with_bundle_options`](FlashbotsSender::with_bundle_options)。返回每个 target block 的
+    /// bundleHash（该 block 无 builder 回 hash 时为 `B256::ZERO`）。
+    ///
+    /// 所有 target block 都没有任何 builder 接受时返回 `Err`。
+    ///
+    /// 配置了 [`with_preflight`](FlashbotsSender::with_preflight) 时先对 current + 1
+    /// 做 `eth_callBundle`，检查不通过直接返回 `Err`，不会发给任何 builder。
+    async fn send_txs(&self, txs: &[RawTx]) -> Result<Vec<B256>> {
+        Ok(self
+            .send_window(txs)
+            .await?
+            .iter()
+            .map(|r| r.bundle_hash().unwrap_or_default())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::utils::testing::{serve_http, serve_json_rpc, HttpResponse, StubServer};
+    use alloy::primitives::U256;
+    use serde_json::json;
+
+    fn call_bundle_result(revert: bool) -> serde_json::Value {
+        let mut tx = json!({
+            "coinbaseDiff": "10000000000000000",
+            "ethSentToCoinbase": "0",
+            "fromAddress": "0x1111111111111111111111111111111111111111",
+            "gasFees": "10000000000000000",
+            "gasPrice": "476190476193",
+            "gasUsed": 21000,
+            "toAddress": "0x2222222222222222222222222222222222222222",
+            "txHash": format!("0x{}", "aa".repeat(32)),
+            "value": "0x",
+        });
+        if revert {
+            tx["error"] = json!("execution reverted");
+            tx["revert"] = json!("STF");
+        }
+        json!({
+            "bundleGasPrice": "476190476193",
+            "bundleHash": format!("0x{}", "bb".repeat(32)),
+            "coinbaseDiff": "10000000000000000",
+            "ethSentToCoinbase": "0",
+            "gasFees": "10000000000000000",
+            "results": [tx],
+            "stateBlockNumber": 16,
+            "totalGasUsed": 21000,
+        })
+    }
+
+    /// relay 桩：校验签名后返回 `eth_callBundle` 结果；builder 桩：计数。
+    async fn setup(revert: bool) -> (FlashbotsSender, [StubServer; 3], Arc<AtomicUsize>) {
+        let auth = PrivateKeySigner::random();
+        let auth_addr = auth.address();
+        let rpc = serve_json_rpc(|method, _| match method {
+            "eth_blockNumber" => Ok(json!("0x10")),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let relay = serve_http(move |req| {
+            assert_eq!(req.flashbots_signer(), Some(auth_addr));
+            let body = req.json().unwrap();
+            assert_eq!(body["method"], "eth_callBundle");
+            assert_eq!(body["params"][0]["blockNumber"], "0x11");
+            HttpResponse::json(
+                200,
+                &json!({ "jsonrpc": "2.0", "id": 1, "result": call_bundle_result(revert) }),
+            )
+        })
+        .await
+        .unwrap();
+        let hits = Arc::new(AtomicUsize::new(0));
+        let h = hits.clone();
+        let builder = serve_http(move |_| {
+            h.fetch_add(1, Ordering::SeqCst);
+            let result = json!({ "bundleHash": format!("0x{}", "cc".repeat(32)) });
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+        })
+        .await
+        .unwrap();
+
+        let sender = FlashbotsSender::new(auth, rpc.url())
+            .unwrap()
+            .with_relay(relay.url())
+            .with_builders(vec![BuilderEndpoint::new("stub", builder.url())])
+            .with_block_window(1);
+        (sender, [rpc, relay, builder], hits)
+    }
+
+    #[tokio::test]
+    async fn simulate_bundle_parses_relay_result() {
+        let (sender, _stubs, _) = setup(true).await;
+        let sim = sender
+            .simulate_bundle(&[RawTx::from(vec![0x02])], 0x11)
+            .await
+            .unwrap();
+        assert_eq!(sim.coinbase_diff, U256::from(10_000_000_000_000_000u64));
+        assert_eq!(sim.bundle_gas_price, U256::from(476_190_476_193u64));
+        assert_eq!(sim.total_gas_used, 21_000);
+        assert_eq!(sim.first_revert().unwrap().revert.as_deref(), Some("STF"));
+    }
+
+    #[tokio::test]
+    async fn preflight_blocks_reverting_or_underpaying_bundle() {
+        let txs = [RawTx::from(vec![0x02])];
+
+        let (sender, _stubs, hits) = setup(true).await;
+        let err = sender
+            .with_preflight(BundlePreflight::default())
+            .send_txs(&txs)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("reverts"), "{err}");
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+
+        let (sender, _stubs, hits) = setup(false).await;
+        let sender = sender.with_preflight(BundlePreflight {
+            min_coinbase_diff: Some(U256::from(10u64).pow(U256::from(18))),
+            ..Default::default()
+        });
+        assert!(sender.send_txs(&txs).await.is_err());
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+
+        let (sender, _stubs, hits) = setup(false).await;
+        let hashes = sender
+            .with_preflight(BundlePreflight::default())
+            .send_txs(&txs)
+            .await
+            .unwrap();
+        assert_eq!(hashes, vec![B256::repeat_byte(0xcc)]);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn send_bundle_reports_each_builder() {
+        let auth = PrivateKeySigner::random();
+        let auth_addr = auth.address();
+        let rpc = serve_json_rpc(|_, _| Err((-32601, "unused".into())))
+            .await
+            .unwrap();
+        let ok = serve_http(move |req| {
+            assert_eq!(req.flashbots_signer(), Some(auth_addr));
+            let result = json!({ "bundleHash": format!("0x{}", "cc".repeat(32)) });
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+        })
+        .await
+        .unwrap();
+        let flaky_hits = Arc::new(AtomicUsize::new(0));
+        let h = flaky_hits.clone();
+        let flaky = serve_http(move |_| {
+            h.fetch_add(1, Ordering::SeqCst);
+            HttpResponse::json(500, &json!({ "error": "overloaded" }))
+        })
+        .await
+        .unwrap();
+        let keyed = serve_http(|req| {
+            assert_eq!(req.header("x-flashbots-signature"), None);
+            assert_eq!(req.header("authorization"), Some("secret"));
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": null }))
+        })
+        .await
+        .unwrap();
+
+        let mut flaky_builder = BuilderEndpoint::new("flaky", flaky.url());
+        flaky_builder.retries = 2;
+        let mut keyed_builder = BuilderEndpoint::new("keyed", keyed.url());
+        keyed_builder.auth = BuilderAuth::Header {
+            name: "Authorization".into(),
+            value: Some("secret".into()),
+            value_env: None,
+        };
+        let sender = FlashbotsSender::new(auth, rpc.url())
+            .unwrap()
+            .with_builders(vec![
+                BuilderEndpoint::new("ok", ok.url()),
+                flaky_builder,
+                keyed_builder,
+            ]);
+
+        let report = sender
+            .send_bundle(&[RawTx::from(vec![0x02])], 0x11)
+            .await
+            .unwrap();
+        assert_eq!(report.target_block, 0x11);
+        assert_eq!((report.accepted(), report.rejected()), (2, 1));
+        assert!(!report.mostly_rejected());
+        assert_eq!(report.bundle_hash(), Some(B256::repeat_byte(0xcc)));
+
+        let failed: Vec<_> = report.failures().collect();
+        assert_eq!(failed[0].builder, "flaky");
+        assert_eq!(failed[0].status, Some(500));
+        assert_eq!(failed[0].attempts, 3);
+        assert_eq!(flaky_hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn sends_bundle_options_and_cancels_by_uuid() {
+        let rpc = serve_json_rpc(|_, _| Err((-32601, "unused".into())))
+            .await
+            .unwrap();
+        let bodies = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let b = bodies.clone();
+        let builder = serve_http(move |req| {
+            b.lock().unwrap().push(req.json().unwrap());
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": null }))
+        })
+        .await
+        .unwrap();
+        let uuid = "2b0f3a34-4a36-4e0e-9d1b-9e3c3f0b3a11";
+        let reverting = B256::repeat_byte(0xaa);
+        let options = BundleOptions {
+            min_timestamp: Some(1_700_000_000),
+            reverting_tx_hashes: vec![reverting],
+            replacement_uuid: Some(uuid.into()),
+            refund_percent: Some(90),
+            ..Default::default()
+        };
+        let sender = FlashbotsSender::new(PrivateKeySigner::random(), rpc.url())
+            .unwrap()
+            .with_builders(vec![BuilderEndpoint::new("stub", builder.url())])
+            .with_bundle_options(options);
+
+        let report = sender
+            .send_bundle(&[RawTx::from(vec![0x02])], 0x11)
+            .await
+            .unwrap();
+        assert_eq!(report.accepted(), 1);
+        assert_eq!(sender.cancel_bundle(uuid).await.unwrap().len(), 1);
+
+        let bodies = bodies.lock().unwrap().clone();
+        assert_eq!(bodies[0]["method"], "eth_sendBundle");
+        let params = &bodies[0]["params"][0];
+        assert_eq!(params["blockNumber"], "0x11");
+        assert_eq!(params["minTimestamp"], 1_700_000_000);
+        assert!(params.get("maxTimestamp").is_none());
+        assert_eq!(params["revertingTxHashes"], json!([reverting]));
+        assert_eq!(params["replacementUuid"], uuid);
+        assert_eq!(params["refundPercent"], 90);
+        assert_eq!(bodies[1]["method"], "eth_cancelBundle");
+        assert_eq!(bodies[1]["params"], json!([{ "replacementUuid": uuid }]));
+
+        let bad = BundleOptions {
+            refund_percent: Some(100),
+            ..Default::default()
+        };
+        assert!(sender
+            .send_bundle_with(&[RawTx::from(vec![0x02])], 0x11, &bad)
+            .await
+            .is_err());
+
+        // 默认 block_window = 3，同一 uuid 会互相覆盖
+        let err = sender.send_window(&[RawTx::from(vec![0x02])]).await.err().unwrap();
+        assert!(err.to_string().contains("block_window = 1"), "{err}");
+    }
+}
@@ -0,0 +1,108 @@
+use alloy::primitives::{Address, Bytes, B256, U256};
+use eyre::Result;
+use serde::Deserialize;
+
+/// `eth_callBundle` 模拟结果。金额单位均为 wei。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSimulation {
+    #[serde(default)]
+    pub bundle_hash: Option<B256>,
+    pub bundle_gas_price: U256,
+    /// 整个 bundle 让 coinbase 增加的余额（gas 费 + 直接转账）。
+    pub coinbase_diff: U256,
+    pub eth_sent_to_coinbase: U256,
+    pub gas_fees: U256,
+    pub state_block_number: u64,
+    pub total_gas_used: u64,
+    pub results: Vec<BundleTxResult>,
+}
+
+/// `eth_callBundle` 里单笔交易的执行结果。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleTxResult {
+    pub tx_hash: B256,
+    pub from_address: Address,
+    #[serde(default)]
+    pub to_address: Option<Address>,
+    pub gas_used: u64,
+    pub gas_price: U256,
+    pub coinbase_diff: U256,
+    #[serde(default)]
+    pub value: Option<Bytes>,
+    /// revert 时 relay 给的错误（如 `execution reverted`）。
+    #[serde(default)]
+    pub error: Option<String>,
+    /// revert reason（已解码的字符串，relay 不一定给）。
+    #[serde(default)]
+    pub revert: Option<String>,
+}
+
+impl BundleTxResult {
+    pub fn reverted(&self) -> bool {
+        self.error.is_some() || self.revert.is_some()
+    }
+}
+
+impl BundleSimulation {
+    /// 第一笔 revert 的交易。
+    pub fn first_revert(&self) -> Option<&BundleTxResult> {
+        self.results.iter().find(|r| r.reverted())
+    }
+}
+
+/// 发送前的 `eth_callBundle` 检查条件，任一不满足则 `send_txs` 拒绝发送。
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BundlePreflight {
+    /// 允许 bundle 里有交易 revert（默认不允许）。
+    #[serde(default)]
+    pub allow_reverts: bool,
+    /// 最低 coinbase diff（wei）。
+    #[serde(default)]
+    pub min_coinbase_diff: Option<U256>,
+    /// 最低 bundle gas price（wei / gas）。
+    #[serde(default)]
+    pub min_bundle_gas_price: Option<U256>,
+}
+
+impl BundlePreflight {
+    pub fn check(&self, sim: &BundleSimulation) -> Result<()> {
+        self.check_allowing(sim, &[])
+    }
+
+    /// 同 [`check`](Self::check)，但 `allowed_reverts` 里的交易 revert 不算失败
+    /// （对应 bundle 的 `revertingTxHashes` / `droppingTxHashes`）。
+    pub fn check_allowing(&self, sim: &BundleSimulation, allowed_reverts: &[B256]) -> Result<()> {
+        if !self.allow_reverts
+            && let Some(r) = sim
+                .results
+                .iter()
+                .find(|r| r.reverted() && !allowed_reverts.contains(&r.tx_hash))
+        {
+            eyre::bail!(
+                "bundle tx {} reverts: {}",
+                r.tx_hash,
+                r.revert
+                    .as_deref()
+                    .or(r.error.as_deref())
+                    .unwrap_or_default()
+            );
+        }
+        if let Some(min) = self.min_coinbase_diff {
+            eyre::ensure!(
+                sim.coinbase_diff >= min,
+                "bundle coinbase diff {} < minimum {min}",
+                sim.coinbase_diff
+            );
+        }
+        if let Some(min) = self.min_bundle_gas_price {
+            eyre::ensure!(
+                sim.bundle_gas_price >= min,
+                "bundle gas price {} < minimum {min}",
+                sim.bundle_gas_price
+            );
+        }
+        Ok(())
+    }
+}
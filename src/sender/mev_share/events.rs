@@ -0,0 +1,207 @@
+use std::collections::VecDeque;
+
+use alloy::primitives::{Address, Bytes, FixedBytes, B256, U256};
+use eyre::{eyre, Result};
+use serde::Deserialize;
+
+/// Flashbots 主网 MEV-Share 事件流。
+pub const MEV_SHARE_STREAM_URL: &str = "https://mev-share.flashbots.net";
+
+/// 事件流里的一条 pending 交易 / bundle hint。字段是否出现取决于发送方给的
+/// privacy hints，没公开的字段为空。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevShareEvent {
+    /// 交易 hash（单笔）或 bundle hash，backrun 时放进 [`BundleItem::Hash`](super::BundleItem::Hash)。
+    pub hash: B256,
+    #[serde(default)]
+    pub logs: Vec<EventLog>,
+    #[serde(default)]
+    pub txs: Vec<EventTx>,
+    #[serde(default)]
+    pub mev_gas_price: Option<U256>,
+    #[serde(default)]
+    pub gas_used: Option<U256>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventLog {
+    pub address: Address,
+    #[serde(default)]
+    pub topics: Vec<B256>,
+    #[serde(default)]
+    pub data: Option<Bytes>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventTx {
+    #[serde(default)]
+    pub to: Option<Address>,
+    #[serde(default)]
+    pub function_selector: Option<FixedBytes<4>>,
+    #[serde(default)]
+    pub call_data: Option<Bytes>,
+}
+
+impl MevShareEvent {
+    /// 公开了的 function selector（calldata 公开时取前 4 字节）。
+    pub fn selectors(&self) -> impl Iterator<Item = FixedBytes<4>> + '_ {
+        self.txs.iter().filter_map(|tx| {
+            tx.function_selector.or_else(|| {
+                tx.call_data
+                    .as_ref()
+                    .filter(|d| d.len() >= 4)
+                    .map(|d| FixedBytes::from_slice(&d[..4]))
+            })
+        })
+    }
+
+    /// 是否有 `address` 发出的、topic0 为 `topic0` 的 log（如 Uniswap `Swap`）。
+    pub fn has_log(&self, address: Address, topic0: B256) -> bool {
+        self.logs
+            .iter()
+            .any(|l| l.address == address && l.topics.first() == Some(&topic0))
+    }
+
+    /// 是否有交易调用 `to`（仅在公开了 contract_address 时可判断）。
+    pub fn calls(&self, to: Address) -> bool {
+        self.txs.iter().any(|tx| tx.to == Some(to))
+    }
+}
+
+/// MEV-Share SSE 事件流客户端。
+///
+/// 连接断开时 [`next_event`](Self::next_event) 返回 `Ok(None)`，由调用方决定是否重连；
+/// 解析失败的单条事件只打 warn 并跳过。
+pub struct MevShareStream {
+    resp: reqwest::Response,
+    parser: SseParser,
+    queued: VecDeque<MevShareEvent>,
+}
+
+impl MevShareStream {
+    /// 连接事件流，主网用 [`MEV_SHARE_STREAM_URL`]。
+    pub async fn connect(url: &str) -> Result<Self> {
+        let resp = reqwest::Client::new()
+            .get(url)
+            .header("Accept", "text/event-stream")
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(eyre!("mev-share stream http {status}"));
+        }
+        tracing::info!("[mev-share] connected to event stream {url}");
+        Ok(Self {
+            resp,
+            parser: SseParser::default(),
+            queued: VecDeque::new(),
+        })
+    }
+
+    pub async fn next_event(&mut self) -> Result<Option<MevShareEvent>> {
+        loop {
+            if let Some(event) = self.queued.pop_front() {
+                return Ok(Some(event));
+            }
+            let Some(chunk) = self.resp.chunk().await? else {
+                return Ok(None);
+            };
+            for data in self.parser.push(&chunk) {
+                match serde_json::from_str::<MevShareEvent>(&data) {
+                    Ok(event) => self.queued.push_back(event),
+                    Err(e) => tracing::warn!("[mev-share] skip undecodable event ({e}): {data}"),
+                }
+            }
+        }
+    }
+}
+
+/// 增量 SSE 解析：只关心 `data:` 字段，注释行（keep-alive `:ping`）和其它字段忽略。
+#[derive(Default)]
+struct SseParser {
+    /// 未成块的原始字节。多字节 UTF-8 字符可能被拆在两个 chunk 里，所以整块到齐后再解码。
+    buf: Vec<u8>,
+}
+
+impl SseParser {
+    /// 喂入一段字节，返回其中已完整的事件的 data（多行 data 以 `\n` 拼接）。
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        // `\r\n` 按 `\n` 处理；SSE 的字段值里不会出现 `\r`。
+        self.buf.extend(chunk.iter().filter(|&&b| b != b'\r'));
+        let mut out = Vec::new();
+        while let Some(end) = self.buf.windows(2).position(|w| w == b"\n\n") {
+            let block: Vec<u8> = self.buf.drain(..end + 2).collect();
+            let block = String::from_utf8_lossy(&block);
+            let data: Vec<&str> = block
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|d| d.strip_prefix(' ').unwrap_or(d))
+                .collect();
+            if !data.is_empty() {
+                out.push(data.join("\n"));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::{serve_http, HttpResponse};
+    use alloy::primitives::{address, b256, fixed_bytes};
+
+    const EVENT: &str = r#"{"hash":"0x1111111111111111111111111111111111111111111111111111111111111111","logs":[{"address":"0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640","topics":["0xc42079f94a6350d7e6235f29174924f928cc2ac818eb64fed8004e115fbcca67"],"data":"0x"}],"txs":[{"to":"0x3fc91a3afd70395cd496c647d5a6cc9d4b2b7fad","functionSelector":"0x3593564c"}],"mevGasPrice":"0x2540be400","gasUsed":"0x30d40"}"#;
+
+    #[test]
+    fn parses_chunked_sse() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b":ping\n\ndata: {\"a\":").is_empty());
+        assert_eq!(
+            parser.push(b"1}\r\n\r\ndata: x\ndata: y\n\n"),
+            vec!["{\"a\":1}", "x\ny"]
+        );
+    }
+
+    #[test]
+    fn keeps_utf8_split_across_chunks() {
+        let event = "data: {\"name\":\"以太坊\"}\n\n".as_bytes();
+        // 在 `以` 的三个字节中间切开
+        let split = event.iter().position(|&b| b >= 0x80).unwrap() + 1;
+        let mut parser = SseParser::default();
+        assert!(parser.push(&event[..split]).is_empty());
+        assert_eq!(parser.push(&event[split..]), vec!["{\"name\":\"以太坊\"}"]);
+    }
+
+    #[tokio::test]
+    async fn streams_decoded_hints() {
+        let stub = serve_http(|req| {
+            assert_eq!(req.header("accept"), Some("text/event-stream"));
+            HttpResponse {
+                status: 200,
+                body: format!(":ping\n\ndata: not json\n\ndata: {EVENT}\n\n"),
+                delay: None,
+                headers: Vec::new(),
+            }
+        })
+        .await
+        .unwrap();
+
+        let mut stream = MevShareStream::connect(stub.url()).await.unwrap();
+        let event = stream.next_event().await.unwrap().unwrap();
+        assert_eq!(event.hash, B256::repeat_byte(0x11));
+        assert_eq!(event.mev_gas_price, Some(U256::from(10_000_000_000u64)));
+        assert!(event.has_log(
+            address!("88e6a0c2ddd26feeb64f039a2c41296fcb3f5640"),
+            b256!("c42079f94a6350d7e6235f29174924f928cc2ac818eb64fed8004e115fbcca67"),
+        ));
+        assert!(event.calls(address!("3fc91a3afd70395cd496c647d5a6cc9d4b2b7fad")));
+        assert_eq!(
+            event.selectors().collect::<Vec<_>>(),
+            vec![fixed_bytes!("3593564c")]
+        );
+        assert!(stream.next_event().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,354 @@
+mod events;
+
+pub use events::{EventLog, EventTx, MevShareEvent, MevShareStream, MEV_SHARE_STREAM_URL};
+
+use alloy::{
+    network::AnyNetwork,
+    primitives::{Address, Bytes, B256},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{relay::signed_rpc, RawTx, TxSender};
+
+/// `mev_sendBundle` 默认发往的 relay；relay 按 `privacy.builders` 转发给 builder。
+const DEFAULT_RELAY_URL: &str = "https://relay.flashbots.net";
+
+/// `send_txs` 默认的 inclusion 区间：current + 1 ..= current + 5。
+const DEFAULT_BLOCK_WINDOW: u64 = 5;
+
+/// 一个 MEV-Share bundle（`mev_sendBundle` 的参数，协议版本 v0.1）。
+///
+/// 参考：https://docs.flashbots.net/flashbots-mev-share/searchers/sending-bundles
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevShareBundle {
+    pub version: &'static str,
+    pub inclusion: Inclusion,
+    pub body: Vec<BundleItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validity: Option<Validity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<Privacy>,
+}
+
+/// bundle 可被打包的区块区间。
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Inclusion {
+    #[serde(serialize_with = "hex_u64")]
+    pub block: u64,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hex_u64_opt"
+    )]
+    pub max_block: Option<u64>,
+}
+
+/// bundle 里的一项：别人的 pending 交易（只知道 hash）或我们自己的签名交易。
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum BundleItem {
+    Hash {
+        hash: B256,
+    },
+    #[serde(rename_all = "camelCase")]
+    Tx {
+        tx: Bytes,
+        can_revert: bool,
+    },
+}
+
+/// 退款规则。`refund` 按 body 下标指定从哪笔交易的收益里退多少，
+/// `refund_config` 指定退给谁。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Validity {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub refund: Vec<Refund>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub refund_config: Vec<RefundConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Refund {
+    pub body_idx: usize,
+    pub percent: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundConfig {
+    pub address: Address,
+    pub percent: u8,
+}
+
+/// 向其他 searcher 公开哪些信息，以及允许哪些 builder 打包。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Privacy {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<PrivacyHint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub builders: Vec<String>,
+}
+
+/// MEV-Share 隐私 hint。不给任何 hint 时 bundle 对其他 searcher 完全不可见。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyHint {
+    Calldata,
+    ContractAddress,
+    Logs,
+    FunctionSelector,
+    Hash,
+    TxHash,
+    DefaultLogs,
+    Full,
+}
+
+fn hex_u64<S: serde::Serializer>(v: &u64, s: S) -> std::result::Result<S::Ok, S::Error> {
+    s.serialize_str(&format!("0x{v:x}"))
+}
+
+fn hex_u64_opt<S: serde::Serializer>(
+    v: &Option<u64>,
+    s: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match v {
+        Some(v) => hex_u64(v, s),
+        None => s.serialize_none(),
+    }
+}
+
+impl MevShareBundle {
+    /// 只含我们自己交易的 bundle（都不允许 revert）。
+    pub fn new(txs: &[RawTx], block: u64, max_block: Option<u64>) -> Self {
+        Self {
+            version: "v0.1",
+            inclusion: Inclusion { block, max_block },
+            body: txs
+                .iter()
+                .map(|tx| BundleItem::Tx {
+                    tx: tx.0.clone(),
+                    can_revert: false,
+                })
+                .collect(),
+            validity: None,
+            privacy: None,
+        }
+    }
+
+    /// backrun：`pending` 是事件流里看到的交易 hash，我们的交易排在它后面。
+    pub fn backrun(pending: B256, txs: &[RawTx], block: u64, max_block: Option<u64>) -> Self {
+        let mut bundle = Self::new(txs, block, max_block);
+        bundle.body.insert(0, BundleItem::Hash { hash: pending });
+        bundle
+    }
+
+    pub fn with_validity(mut self, validity: Validity) -> Self {
+        self.validity = Some(validity);
+        self
+    }
+
+    pub fn with_privacy(mut self, privacy: Privacy) -> Self {
+        self.privacy = Some(privacy);
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SendBundleResponse {
+    bundle_hash: B256,
+}
+
+/// 通过 MEV-Share（`mev_sendBundle`）发送 bundle。
+///
+/// 与 [`FlashbotsSender`](super::FlashbotsSender) 的区别：只发给一个 relay，由 relay
+/// 按 `privacy.builders` 转发；bundle 可以引用别人的 pending 交易做 backrun，
+/// 并按 `validity` 拿回部分收益。
+pub struct MevShareSender {
+    auth_signer: PrivateKeySigner,
+    provider: DynProvider<AnyNetwork>,
+    client: reqwest::Client,
+    relay_url: String,
+    block_window: u64,
+    privacy: Option<Privacy>,
+    validity: Option<Validity>,
+}
+
+impl MevShareSender {
+    pub fn new(auth_signer: PrivateKeySigner, rpc_url: &str) -> Result<Self> {
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_http(rpc_url.parse()?)
+            .erased();
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(5))
+            .build()?;
+        Ok(Self {
+            auth_signer,
+            provider,
+            client,
+            relay_url: DEFAULT_RELAY_URL.to_string(),
+            block_window: DEFAULT_BLOCK_WINDOW,
+            privacy: None,
+            validity: None,
+        })
+    }
+
+    pub fn with_relay(mut self, relay_url: &str) -> Self {
+        self.relay_url = relay_url.to_string();
+        self
+    }
+
+    /// `send_txs` / [`backrun`](Self::backrun) 的 inclusion 区间为
+    /// current + 1 ..= current + `block_window`（至少 1）。
+    pub fn with_block_window(mut self, block_window: u64) -> Self {
+        self.block_window = block_window.max(1);
+        self
+    }
+
+    /// `send_txs` / [`backrun`](Self::backrun) 默认带上的隐私设置。
+    pub fn with_privacy(mut self, privacy: Privacy) -> Self {
+        self.privacy = Some(privacy);
+        self
+    }
+
+    /// `send_txs` / [`backrun`](Self::backrun) 默认带上的退款设置。
+    pub fn with_validity(mut self, validity: Validity) -> Self {
+        self.validity = Some(validity);
+        self
+    }
+
+    /// 发送一个完整构造好的 bundle，返回 relay 给的 bundleHash。
+    pub async fn send_bundle(&self, bundle: &MevShareBundle) -> Result<B256> {
+        let resp: SendBundleResponse = signed_rpc(
+            &self.client,
+            &self.relay_url,
+            &self.auth_signer,
+            "mev_sendBundle",
+            serde_json::json!([bundle]),
+        )
+        .await?;
+        tracing::info!(
+            "[mev-share] block=0x{:x} max={:?} items={} bundle_hash={}",
+            bundle.inclusion.block,
+            bundle.inclusion.max_block,
+            bundle.body.len(),
+            resp.bundle_hash
+        );
+        Ok(resp.bundle_hash)
+    }
+
+    /// 把 `txs` 作为 `pending`（事件流里的 [`MevShareEvent::hash`]）的 backrun 发出。
+    pub async fn backrun(&self, pending: B256, txs: &[RawTx]) -> Result<B256> {
+        let (block, max_block) = self.inclusion_range().await?;
+        let bundle = self.with_defaults(MevShareBundle::backrun(pending, txs, block, max_block));
+        self.send_bundle(&bundle).await
+    }
+
+    async fn inclusion_range(&self) -> Result<(u64, Option<u64>)> {
+        let current = self.provider.get_block_number().await?;
+        let max_block = (self.block_window > 1).then(|| current + self.block_window);
+        Ok((current + 1, max_block))
+    }
+
+    fn with_defaults(&self, mut bundle: MevShareBundle) -> MevShareBundle {
+        bundle.privacy = self.privacy.clone();
+        bundle.validity = self.validity.clone();
+        bundle
+    }
+}
+
+impl TxSender for MevShareSender {
+    /// 所有交易组成一个 bundle，一次覆盖整个 inclusion 区间；返回单个 bundleHash。
+    async fn send_txs(&self, txs: &[RawTx]) -> Result<Vec<B256>> {
+        let (block, max_block) = self.inclusion_range().await?;
+        let bundle = self.with_defaults(MevShareBundle::new(txs, block, max_block));
+        Ok(vec![self.send_bundle(&bundle).await?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::{serve_http, serve_json_rpc, HttpResponse};
+    use serde_json::json;
+
+    #[test]
+    fn serializes_backrun_bundle() {
+        let pending = B256::repeat_byte(0x11);
+        let bundle =
+            MevShareBundle::backrun(pending, &[RawTx::from(vec![0x02, 0x01])], 0x10, Some(0x12))
+                .with_validity(Validity {
+                    refund: vec![Refund {
+                        body_idx: 0,
+                        percent: 90,
+                    }],
+                    ..Default::default()
+                })
+                .with_privacy(Privacy {
+                    hints: vec![PrivacyHint::Calldata, PrivacyHint::Logs],
+                    builders: vec!["flashbots".into()],
+                });
+        assert_eq!(
+            serde_json::to_value(&bundle).unwrap(),
+            json!({
+                "version": "v0.1",
+                "inclusion": { "block": "0x10", "maxBlock": "0x12" },
+                "body": [
+                    { "hash": pending },
+                    { "tx": "0x0201", "canRevert": false },
+                ],
+                "validity": { "refund": [{ "bodyIdx": 0, "percent": 90 }] },
+                "privacy": { "hints": ["calldata", "logs"], "builders": ["flashbots"] },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn backrun_signs_and_sends_to_relay() {
+        let auth = PrivateKeySigner::random();
+        let auth_addr = auth.address();
+        let pending = B256::repeat_byte(0x11);
+        let rpc = serve_json_rpc(|method, _| match method {
+            "eth_blockNumber" => Ok(json!("0x10")),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let relay = serve_http(move |req| {
+            assert_eq!(req.flashbots_signer(), Some(auth_addr));
+            let body = req.json().unwrap();
+            assert_eq!(body["method"], "mev_sendBundle");
+            let bundle = &body["params"][0];
+            assert_eq!(
+                bundle["inclusion"],
+                json!({ "block": "0x11", "maxBlock": "0x12" })
+            );
+            assert_eq!(bundle["body"][0]["hash"], json!(pending));
+            assert_eq!(bundle["privacy"]["hints"], json!(["hash"]));
+            let result = json!({ "bundleHash": format!("0x{}", "cc".repeat(32)) });
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+        })
+        .await
+        .unwrap();
+
+        let sender = MevShareSender::new(auth, rpc.url())
+            .unwrap()
+            .with_relay(relay.url())
+            .with_block_window(2)
+            .with_privacy(Privacy {
+                hints: vec![PrivacyHint::Hash],
+                ..Default::default()
+            });
+        let hash = sender
+            .backrun(pending, &[RawTx::from(vec![0x02])])
+            .await
+            .unwrap();
+        assert_eq!(hash, B256::repeat_byte(0xcc));
+    }
+}
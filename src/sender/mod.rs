@@ -1,10 +1,38 @@
+mod any;
+mod bundler;
+mod escalation;
+mod fanout;
 mod flashbots;
+mod mev_share;
+mod nonce;
 mod private;
+pub mod record;
+mod relay;
+mod replacement;
 mod rpc;
+mod tracker;
 
-pub use flashbots::FlashbotsSender;
-pub use private::PrivateSender;
+pub use any::AnySender;
+pub use bundler::{entry_point_nonce, BundlerSender};
+pub use escalation::EscalatingSender;
+pub use fanout::FanoutSender;
+pub use flashbots::{
+    BuilderAuth, BuilderEndpoint, BuilderResult, BuilderSeen, BundleOptions, BundleOutcome,
+    BundlePreflight, BundleSimulation, BundleStats, BundleSubmissionReport, BundleTxResult,
+    FlashbotsSender,
+};
+pub use mev_share::{
+    BundleItem, EventLog, EventTx, Inclusion, MevShareBundle, MevShareEvent, MevShareSender,
+    MevShareStream, Privacy, PrivacyHint, Refund, RefundConfig, Validity, MEV_SHARE_STREAM_URL,
+};
+pub use nonce::{NonceGuard, NonceManager};
+pub use private::{PrivateSender, PrivateTxPreferences, PrivateTxState, PrivateTxStatus};
+pub use record::{RecordSender, RecordedTx};
+pub use replacement::{
+    bump_fees, cancel_tx, ReplacementPolicy, TxReplacer, MIN_REPLACEMENT_BUMP_PERCENT,
+};
 pub use rpc::RpcSender;
+pub use tracker::{MinedTx, TxHandle, TxOutcome, TxTracker};
 
 use std::future::Future;
 
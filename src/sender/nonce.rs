@@ -0,0 +1,235 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use alloy::{
+    network::AnyNetwork,
+    primitives::Address,
+    providers::{DynProvider, Provider},
+};
+use eyre::Result;
+
+/// 多 task 共享的本地 nonce 分配器。
+///
+/// - 首次使用某账户时按 `pending` 交易数初始化（已在 mempool 里的交易也算上）
+/// - [`reserve`](Self::reserve) 返回 [`NonceGuard`]；交易发出后 `commit`，
+///   没发出去（签名失败等）直接 drop 即归还，下一次 reserve 优先复用最小的已归还 nonce
+/// - [`resync`](Self::resync) 与链上对齐：链上已用掉的丢弃，本地分配过但节点
+///   pending 里没有的（被丢弃的交易留下的 gap）重新放回可用池
+///
+/// clone 后共享同一份状态。
+#[derive(Clone)]
+pub struct NonceManager {
+    provider: DynProvider<AnyNetwork>,
+    accounts: Arc<Mutex<HashMap<Address, AccountNonces>>>,
+}
+
+#[derive(Debug, Default)]
+struct AccountNonces {
+    /// 下一个从未分配过的 nonce。
+    next: u64,
+    /// 已归还、待复用的 nonce。
+    released: BTreeSet<u64>,
+    /// 已分配但还没 commit / 归还的 nonce。
+    outstanding: BTreeSet<u64>,
+}
+
+impl AccountNonces {
+    fn take(&mut self) -> u64 {
+        let nonce = self.released.pop_first().unwrap_or_else(|| {
+            self.next += 1;
+            self.next - 1
+        });
+        self.outstanding.insert(nonce);
+        nonce
+    }
+}
+
+impl NonceManager {
+    pub fn new(provider: DynProvider<AnyNetwork>) -> Self {
+        Self {
+            provider,
+            accounts: Arc::default(),
+        }
+    }
+
+    /// 为 `account` 分配一个 nonce。
+    pub async fn reserve(&self, account: Address) -> Result<NonceGuard> {
+        if !self.lock().contains_key(&account) {
+            let pending = self.pending_count(account).await?;
+            // 并发初始化时以先到者为准
+            self.lock().entry(account).or_insert_with(|| AccountNonces {
+                next: pending,
+                ..Default::default()
+            });
+        }
+        let nonce = self
+            .lock()
+            .get_mut(&account)
+            .expect("account initialized above")
+            .take();
+        tracing::debug!("[nonce] reserved {account} #{nonce}");
+        Ok(NonceGuard {
+            manager: self.clone(),
+            account,
+            nonce,
+            committed: false,
+        })
+    }
+
+    /// 与链上 `pending` 交易数对齐，返回对齐后的下一个新 nonce。
+    ///
+    /// 只在确认 "本地分配过的交易已经不在节点里" 时调用（如发送报 nonce too low /
+    /// 长时间未上链）：刚广播的交易可能还没传到节点，私有交易 / bundle 在公共节点
+    /// pending 里本来就看不到，这两种情况下 resync 会把仍有效的 nonce 当成 gap 复用。
+    pub async fn resync(&self, account: Address) -> Result<u64> {
+        let pending = self.pending_count(account).await?;
+        let mut accounts = self.lock();
+        let state = accounts.entry(account).or_default();
+        state.released.retain(|n| *n >= pending);
+        if pending >= state.next {
+            state.next = pending;
+        } else {
+            for n in pending..state.next {
+                if !state.outstanding.contains(&n) {
+                    state.released.insert(n);
+                }
+            }
+        }
+        tracing::info!(
+            "[nonce] resync {account}: chain pending={pending} next={} gaps={:?}",
+            state.next,
+            state.released
+        );
+        Ok(state.next)
+    }
+
+    /// 丢弃本地状态，下次 reserve 重新从链上初始化。
+    pub fn reset(&self, account: Address) {
+        self.lock().remove(&account);
+    }
+
+    async fn pending_count(&self, account: Address) -> Result<u64> {
+        Ok(self
+            .provider
+            .get_transaction_count(account)
+            .pending()
+            .await?)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Address, AccountNonces>> {
+        self.accounts.lock().expect("nonce manager mutex poisoned")
+    }
+
+    fn finish(&self, account: Address, nonce: u64, committed: bool) {
+        let mut accounts = self.lock();
+        let Some(state) = accounts.get_mut(&account) else {
+            return;
+        };
+        if state.outstanding.remove(&nonce) && !committed {
+            tracing::debug!("[nonce] released {account} #{nonce}");
+            state.released.insert(nonce);
+        }
+    }
+}
+
+/// 一个已分配的 nonce。`commit` 表示交易已发出；未 commit 就 drop 则归还。
+pub struct NonceGuard {
+    manager: NonceManager,
+    account: Address,
+    nonce: u64,
+    committed: bool,
+}
+
+impl NonceGuard {
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    pub fn account(&self) -> Address {
+        self.account
+    }
+
+    /// 交易已被接受，nonce 不再归还。
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for NonceGuard {
+    fn drop(&mut self) {
+        self.manager
+            .finish(self.account, self.nonce, self.committed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+    use crate::utils::testing::serve_json_rpc;
+    use alloy::{primitives::address, providers::ProviderBuilder};
+
+    const OPERATOR: Address = address!("1111111111111111111111111111111111111111");
+
+    #[tokio::test]
+    async fn reserves_releases_and_resyncs() {
+        let chain = Arc::new(AtomicU64::new(5));
+        let c = chain.clone();
+        let stub = serve_json_rpc(move |method, params| match method {
+            "eth_getTransactionCount" => {
+                assert_eq!(params[1], "pending");
+                Ok(serde_json::json!(format!(
+                    "0x{:x}",
+                    c.load(Ordering::SeqCst)
+                )))
+            }
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_http(stub.url().parse().unwrap())
+            .erased();
+        let nonces = NonceManager::new(provider);
+
+        // 并发分配不重复
+        let shared = nonces.clone();
+        let (a, b, c) = tokio::join!(
+            nonces.reserve(OPERATOR),
+            shared.reserve(OPERATOR),
+            nonces.reserve(OPERATOR)
+        );
+        let (a, b, c) = (a.unwrap(), b.unwrap(), c.unwrap());
+        let mut got = vec![a.nonce(), b.nonce(), c.nonce()];
+        got.sort();
+        assert_eq!(got, vec![5, 6, 7]);
+
+        // 失败（drop）归还，下一次优先复用
+        let failed = got[1];
+        for g in [a, b, c] {
+            if g.nonce() == failed {
+                drop(g);
+            } else {
+                g.commit();
+            }
+        }
+        let retry = nonces.reserve(OPERATOR).await.unwrap();
+        assert_eq!(retry.nonce(), failed);
+        retry.commit();
+        assert_eq!(nonces.reserve(OPERATOR).await.unwrap().nonce(), 8);
+
+        // 8 被归还；节点只看到 5、6 → 7 是 gap，8 留在可用池
+        chain.store(7, Ordering::SeqCst);
+        assert_eq!(nonces.resync(OPERATOR).await.unwrap(), 9);
+        assert_eq!(nonces.reserve(OPERATOR).await.unwrap().nonce(), 7);
+
+        // 外部发了交易：链上 pending 超过本地
+        chain.store(12, Ordering::SeqCst);
+        assert_eq!(nonces.resync(OPERATOR).await.unwrap(), 12);
+        assert_eq!(nonces.reserve(OPERATOR).await.unwrap().nonce(), 12);
+    }
+}
@@ -1,13 +1,15 @@
+use std::time::{Duration, Instant};
+
 use alloy::{
     network::AnyNetwork,
-    primitives::{keccak256, B256},
+    primitives::B256,
     providers::{DynProvider, Provider, ProviderBuilder},
-    signers::{local::PrivateKeySigner, SignerSync},
+    signers::local::PrivateKeySigner,
 };
 use eyre::{eyre, Result};
 use serde::Deserialize;
 
-use super::{RawTx, TxSender};
+use super::{relay::signed_rpc, Privacy, RawTx, RefundConfig, TxSender};
 
 /// Flashbots relay 的 eth_sendPrivateTransaction 端点。
 /// Relay 收到后会把交易中继到所有合作 builder，调用方不需要自己 fan-out。
@@ -17,6 +19,68 @@ const DEFAULT_RELAY_URL: &str = "https://relay.flashbots.net";
 /// `eth_sendPrivateTransaction` 默认的 maxBlockNumber 偏移（Flashbots 规范）。
 const DEFAULT_MAX_BLOCK_OFFSET: u64 = 25;
 
+/// Protect 交易状态 API（`GET <url><tx hash>`）。
+const DEFAULT_STATUS_URL: &str = "https://protect.flashbots.net/tx/";
+
+/// Flashbots Protect 的 `preferences`。
+///
+/// ```json
+/// { "fast": true, "privacy": { "hints": ["hash"], "builders": ["flashbots", "beaverbuild.org"] },
+///   "refund": [{ "address": "0x..", "percent": 90 }] }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PrivateTxPreferences {
+    /// fast 模式：发给所有已注册 builder，并放弃 MEV 退款换更快上链。
+    #[serde(default)]
+    pub fast: bool,
+    #[serde(default)]
+    pub privacy: Option<Privacy>,
+    /// MEV 退款分配（`validity.refund`）。
+    #[serde(default)]
+    pub refund: Vec<RefundConfig>,
+}
+
+impl PrivateTxPreferences {
+    fn to_json(&self) -> serde_json::Value {
+        let mut prefs = serde_json::json!({ "fast": self.fast });
+        if let Some(privacy) = &self.privacy {
+            prefs["privacy"] = serde_json::json!(privacy);
+        }
+        if !self.refund.is_empty() {
+            prefs["validity"] = serde_json::json!({ "refund": self.refund });
+        }
+        prefs
+    }
+}
+
+/// Protect 状态 API 里的交易状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PrivateTxState {
+    Pending,
+    Included,
+    Failed,
+    Cancelled,
+    #[serde(other)]
+    Unknown,
+}
+
+/// `GET /tx/<hash>` 的返回。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivateTxStatus {
+    pub status: PrivateTxState,
+    pub hash: B256,
+    #[serde(default)]
+    pub max_block_number: Option<u64>,
+    #[serde(default)]
+    pub fast_mode: Option<bool>,
+    #[serde(default)]
+    pub seen_in_mempool: Option<bool>,
+    #[serde(default)]
+    pub sim_error: Option<String>,
+}
+
 /// 通过 Flashbots relay 中继的私有交易发送器。
 ///
 /// 与 `FlashbotsSender` 的区别：relay 负责把私有交易分发给所有合作 builder，
@@ -29,6 +93,10 @@ pub struct PrivateSender {
     provider: DynProvider<AnyNetwork>,
     client: reqwest::Client,
     relay_url: String,
+    max_block_offset: u64,
+    preferences: Option<PrivateTxPreferences>,
+    status_url: String,
+    status_poll: Duration,
 }
 
 impl PrivateSender {
@@ -55,80 +123,113 @@ impl PrivateSender {
             provider,
             client,
             relay_url: relay_url.to_string(),
+            max_block_offset: DEFAULT_MAX_BLOCK_OFFSET,
+            preferences: None,
+            status_url: DEFAULT_STATUS_URL.to_string(),
+            status_poll: Duration::from_secs(3),
         })
     }
 
+    /// 覆盖 `send_txs` 的 maxBlockNumber 窗口（current + `blocks`，默认 25）。
+    pub fn with_block_window(mut self, blocks: u64) -> Self {
+        self.max_block_offset = blocks.max(1);
+        self
+    }
+
+    /// 每笔交易带上的 Protect `preferences`（fast / builders / hints / refund）。
+    pub fn with_preferences(mut self, preferences: PrivateTxPreferences) -> Self {
+        self.preferences = Some(preferences);
+        self
+    }
+
+    /// 覆盖状态 API 前缀（默认 `https://protect.flashbots.net/tx/`）和轮询间隔。
+    pub fn with_status_api(mut self, url: &str, poll: Duration) -> Self {
+        self.status_url = url.to_string();
+        self.status_poll = poll;
+        self
+    }
+
     /// 把单笔私有交易发给 relay；返回 relay 确认的 tx hash。
     /// 任何 HTTP / JSON-RPC 错误都会返回 `Err`，不会吞掉。
     pub async fn send_private_tx(&self, tx: &RawTx, max_block_number: u64) -> Result<B256> {
         let raw_tx = format!("0x{}", alloy::hex::encode(&tx.0));
-        let params = serde_json::json!({
+        let mut params = serde_json::json!({
             "tx": raw_tx,
             "maxBlockNumber": format!("0x{max_block_number:x}"),
         });
-        let body = serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "eth_sendPrivateTransaction",
-            "params": [params],
-        });
-        let body_bytes = serde_json::to_vec(&body)?;
-
+        if let Some(prefs) = &self.preferences {
+            params["preferences"] = prefs.to_json();
+        }
         // Flashbots 风格签名（EIP-191 personal_sign 对 body 的 keccak256 hex）。
-        let body_hash = keccak256(&body_bytes);
-        let hash_hex = format!("{body_hash:#x}");
-        let sig = self
-            .auth_signer
-            .sign_message_sync(hash_hex.as_bytes())
-            .map_err(|e| eyre!("{e}"))?;
-        let sig_header = format!(
-            "{}:0x{}",
-            self.auth_signer.address(),
-            alloy::hex::encode(sig.as_bytes()),
-        );
-
-        let resp = self
-            .client
-            .post(&self.relay_url)
-            .header("Content-Type", "application/json")
-            .header("X-Flashbots-Signature", sig_header)
-            .body(body_bytes)
-            .send()
-            .await?;
+        let hash: B256 = signed_rpc(
+            &self.client,
+            &self.relay_url,
+            &self.auth_signer,
+            "eth_sendPrivateTransaction",
+            serde_json::json!([params]),
+        )
+        .await?;
+        tracing::info!("[flashbots-private] maxBlock=0x{max_block_number:x} hash={hash}");
+        Ok(hash)
+    }
 
+    /// `eth_cancelPrivateTransaction`；返回 relay 是否确认取消（已上链 / 已转发的可能取消不了）。
+    pub async fn cancel_private_tx(&self, tx_hash: B256) -> Result<bool> {
+        let cancelled: bool = signed_rpc(
+            &self.client,
+            &self.relay_url,
+            &self.auth_signer,
+            "eth_cancelPrivateTransaction",
+            serde_json::json!([{ "txHash": tx_hash }]),
+        )
+        .await?;
+        tracing::info!("[flashbots-private] cancel {tx_hash}: {cancelled}");
+        Ok(cancelled)
+    }
+
+    /// 查 Protect 状态 API（公开接口，不需要签名）。
+    pub async fn tx_status(&self, tx_hash: B256) -> Result<PrivateTxStatus> {
+        let url = format!("{}{tx_hash:#x}", self.status_url);
+        let resp = self.client.get(&url).send().await?;
         let status = resp.status();
         let text = resp.text().await?;
-        tracing::info!(
-            "[flashbots-private] maxBlock=0x{:x} status={} body={}",
-            max_block_number,
-            status.as_u16(),
-            text
-        );
-
         if !status.is_success() {
-            return Err(eyre!("relay http {}: {}", status, text));
+            return Err(eyre!("protect status http {status}: {text}"));
         }
+        serde_json::from_str(&text)
+            .map_err(|e| eyre!("decode protect status failed: {e}; body={text}"))
+    }
 
-        let parsed: PrivateTxResponse = serde_json::from_str(&text)
-            .map_err(|e| eyre!("decode relay response failed: {e}; body={text}"))?;
-        if let Some(err) = parsed.error {
-            return Err(eyre!("relay error {}: {}", err.code, err.message));
+    /// 轮询状态直到不再是 `PENDING`，或超过 `timeout` 返回 `Err`。
+    pub async fn wait_for_status(
+        &self,
+        tx_hash: B256,
+        timeout: Duration,
+    ) -> Result<PrivateTxStatus> {
+        let start = Instant::now();
+        loop {
+            let status = self.tx_status(tx_hash).await?;
+            if status.status != PrivateTxState::Pending {
+                tracing::info!("[flashbots-private] {tx_hash}: {:?}", status.status);
+                return Ok(status);
+            }
+            if start.elapsed() >= timeout {
+                return Err(eyre!("private tx {tx_hash} still pending after {timeout:?}"));
+            }
+            tokio::time::sleep(self.status_poll).await;
         }
-        parsed
-            .result
-            .ok_or_else(|| eyre!("relay response missing result: {text}"))
     }
 }
 
 impl TxSender for PrivateSender {
     /// 顺序把每笔交易发给 relay，保证 nonce 递增的交易按序到达；
-    /// maxBlockNumber = current + 25（Flashbots 默认窗口）。
+    /// maxBlockNumber = current + 窗口（默认 25，Flashbots 默认窗口）。
     ///
     /// 语义与 `RpcSender` 一致：若第 k 笔失败则早退返 `Err`，前 k-1 笔已被 relay 接受
     /// 但它们的 hash 会被丢弃，调用方需根据 nonce/账户另行追踪。
     async fn send_txs(&self, txs: &[RawTx]) -> Result<Vec<B256>> {
         let block = self.provider.get_block_number().await?;
-        let max_block = block + DEFAULT_MAX_BLOCK_OFFSET;
+        let max_block = block + self.max_block_offset;
         let mut hashes = Vec::with_capacity(txs.len());
         for tx in txs {
             hashes.push(self.send_private_tx(tx, max_block).await?);
@@ -137,14 +238,109 @@ impl TxSender for PrivateSender {
     }
 }
 
-#[derive(Deserialize)]
-struct PrivateTxResponse {
-    result: Option<B256>,
-    error: Option<PrivateTxError>,
-}
 
-#[derive(Deserialize)]
-struct PrivateTxError {
-    code: i64,
-    message: String,
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::{
+        sender::PrivacyHint,
+        utils::testing::{serve_http, serve_json_rpc, HttpResponse},
+    };
+    use alloy::primitives::address;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn sends_preferences_cancels_and_polls_status() {
+        let auth = PrivateKeySigner::random();
+        let auth_addr = auth.address();
+        let hash = B256::repeat_byte(0xab);
+        let rpc = serve_json_rpc(|method, _| match method {
+            "eth_blockNumber" => Ok(json!("0x10")),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let relay = serve_http(move |req| {
+            assert_eq!(req.flashbots_signer(), Some(auth_addr));
+            let body = req.json().unwrap();
+            let params = &body["params"][0];
+            let result = match body["method"].as_str().unwrap() {
+                "eth_sendPrivateTransaction" => {
+                    assert_eq!(params["maxBlockNumber"], "0x15");
+                    assert_eq!(
+                        params["preferences"],
+                        json!({
+                            "fast": true,
+                            "privacy": { "hints": ["hash"], "builders": ["flashbots"] },
+                            "validity": { "refund": [{
+                                "address": "0x2222222222222222222222222222222222222222",
+                                "percent": 90,
+                            }] },
+                        })
+                    );
+                    json!(hash)
+                }
+                "eth_cancelPrivateTransaction" => {
+                    assert_eq!(params["txHash"], json!(hash));
+                    json!(true)
+                }
+                m => panic!("unexpected {m}"),
+            };
+            HttpResponse::json(200, &json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+        })
+        .await
+        .unwrap();
+        let polls = Arc::new(AtomicUsize::new(0));
+        let p = polls.clone();
+        let status_api = serve_http(move |req| {
+            assert_eq!(req.path, format!("/tx/{hash:#x}"));
+            let status = if p.fetch_add(1, Ordering::SeqCst) < 2 {
+                "PENDING"
+            } else {
+                "INCLUDED"
+            };
+            HttpResponse::json(
+                200,
+                &json!({ "status": status, "hash": hash, "maxBlockNumber": 21, "seenInMempool": false }),
+            )
+        })
+        .await
+        .unwrap();
+
+        let sender = PrivateSender::with_relay(auth, rpc.url(), relay.url())
+            .unwrap()
+            .with_block_window(5)
+            .with_preferences(PrivateTxPreferences {
+                fast: true,
+                privacy: Some(Privacy {
+                    hints: vec![PrivacyHint::Hash],
+                    builders: vec!["flashbots".into()],
+                }),
+                refund: vec![RefundConfig {
+                    address: address!("2222222222222222222222222222222222222222"),
+                    percent: 90,
+                }],
+            })
+            .with_status_api(
+                &format!("{}/tx/", status_api.url()),
+                Duration::from_millis(10),
+            );
+
+        let hashes = sender.send_txs(&[RawTx::from(vec![0x02])]).await.unwrap();
+        assert_eq!(hashes, vec![hash]);
+        assert!(sender.cancel_private_tx(hash).await.unwrap());
+
+        let status = sender
+            .wait_for_status(hash, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(status.status, PrivateTxState::Included);
+        assert_eq!(status.max_block_number, Some(21));
+        assert_eq!(polls.load(Ordering::SeqCst), 3);
+    }
 }
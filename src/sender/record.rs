@@ -0,0 +1,307 @@
+//! 只记录不广播：把签好的交易连同解码后的字段逐行写进 JSONL，供人工审核、
+//! 审计留档，之后再用 [`replay`] 经任意真实 sender 发出去（"现在签、审完再发"）。
+//!
+//! ```ignore
+//! let recorder = RecordSender::new("signed.jsonl")?.with_decoder(decoder);
+//! Pipeline::new(&builder, &signer, &recorder, fees, provider.clone()).submit(&requests).await?;
+//! // ... 审核 signed.jsonl ...
+//! record::replay("signed.jsonl", &config.build_sender()?).await?;
+//! ```
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use alloy::{
+    consensus::{transaction::SignerRecoverable, Transaction, TxEnvelope},
+    eips::Decodable2718,
+    primitives::{keccak256, Address, Bytes, B256, U256},
+};
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use super::{RawTx, TxSender};
+use crate::AbiDecoder;
+
+/// JSONL 里的一行。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedTx {
+    pub hash: B256,
+    /// 同一次 `send_txs` 的交易共用一个 batch（取首笔 hash），replay 时按 batch
+    /// 原样分组发送（bundle 不会被拆开）。
+    pub batch: B256,
+    pub from: Address,
+    pub chain_id: Option<u64>,
+    pub nonce: u64,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub gas_limit: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: Option<u128>,
+    pub input: Bytes,
+    /// 配了 decoder 且能识别 selector 时有值。
+    pub decoded: Option<RecordedCall>,
+    /// RFC 3339。
+    pub recorded_at: String,
+    pub raw: Bytes,
+}
+
+/// [`DecodedCall`](crate::DecodedCall) 的可序列化版本。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    pub name: String,
+    pub signature: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl RecordedTx {
+    /// 解码 raw tx，`batch` 为所在批次首笔交易的 hash。
+    pub fn decode(raw: &RawTx, batch: B256, decoder: Option<&AbiDecoder>) -> Result<Self> {
+        let envelope = TxEnvelope::decode_2718(&mut raw.0.as_ref())
+            .map_err(|e| eyre::eyre!("decode 2718 envelope: {e}"))?;
+        let from = envelope
+            .recover_signer()
+            .map_err(|e| eyre::eyre!("recover signer: {e:?}"))?;
+        let to = envelope.to();
+        let decoded = decoder
+            .zip(to)
+            .and_then(|(d, to)| d.decode_calldata(&to, envelope.input()))
+            .map(|c| RecordedCall {
+                name: c.name,
+                signature: c.signature,
+                params: c.params,
+            });
+        Ok(Self {
+            hash: keccak256(&raw.0),
+            batch,
+            from,
+            chain_id: envelope.chain_id(),
+            nonce: envelope.nonce(),
+            to,
+            value: envelope.value(),
+            gas_limit: envelope.gas_limit(),
+            max_fee_per_gas: envelope.max_fee_per_gas(),
+            max_priority_fee_per_gas: envelope.max_priority_fee_per_gas(),
+            input: envelope.input().clone(),
+            decoded,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            raw: raw.0.clone(),
+        })
+    }
+}
+
+/// 把交易写进 JSONL 而不广播的 [`TxSender`]。返回的 hash 是交易 hash，但交易并没有发出。
+pub struct RecordSender {
+    path: PathBuf,
+    file: Mutex<File>,
+    decoder: Option<AbiDecoder>,
+}
+
+impl RecordSender {
+    /// 以追加方式打开（不存在则创建）`path`。
+    pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .wrap_err_with(|| format!("open record file {}", path.display()))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            decoder: None,
+        })
+    }
+
+    /// 用于解码 calldata。
+    pub fn with_decoder(mut self, decoder: AbiDecoder) -> Self {
+        self.decoder = Some(decoder);
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl TxSender for RecordSender {
+    /// 整批解码成功才写入，一批一次 flush。
+    async fn send_txs(&self, txs: &[RawTx]) -> Result<Vec<B256>> {
+        let Some(first) = txs.first() else {
+            return Ok(Vec::new());
+        };
+        let batch = keccak256(&first.0);
+        let mut lines = String::new();
+        let mut hashes = Vec::with_capacity(txs.len());
+        for raw in txs {
+            let record = RecordedTx::decode(raw, batch, self.decoder.as_ref())?;
+            lines.push_str(&serde_json::to_string(&record)?);
+            lines.push('\n');
+            hashes.push(record.hash);
+        }
+
+        let mut file = self.file.lock().expect("record file poisoned");
+        file.write_all(lines.as_bytes())?;
+        file.flush()?;
+        tracing::info!(
+            "[record] {} tx(s) written to {} (not broadcast)",
+            txs.len(),
+            self.path.display()
+        );
+        Ok(hashes)
+    }
+}
+
+/// 读取 [`RecordSender`] 写的文件，并校验每行的 `raw` 与 `hash` 一致。
+pub fn read_records(path: impl AsRef<Path>) -> Result<Vec<RecordedTx>> {
+    let path = path.as_ref();
+    let file = File::open(path).wrap_err_with(|| format!("open {}", path.display()))?;
+    let mut records = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: RecordedTx = serde_json::from_str(&line)
+            .wrap_err_with(|| format!("{}:{}: invalid record", path.display(), i + 1))?;
+        eyre::ensure!(
+            keccak256(&record.raw) == record.hash,
+            "{}:{}: raw does not match hash {}",
+            path.display(),
+            i + 1,
+            record.hash
+        );
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// 读 `path` 的记录（同 [`read_records`]）后经 [`replay_records`] 广播。
+pub async fn replay<S: TxSender>(path: impl AsRef<Path>, sender: &S) -> Result<Vec<B256>> {
+    replay_records(&read_records(path)?, sender).await
+}
+
+/// 把记录按 batch 分组，依次经 `sender` 广播，返回所有 hash。已经读过并审核的记录
+/// 直接传进来，不再从文件重读。
+///
+/// 任一批失败即停止（已发出的批次不回滚），错误里带出失败批次。
+pub async fn replay_records<S: TxSender>(records: &[RecordedTx], sender: &S) -> Result<Vec<B256>> {
+    let mut hashes = Vec::with_capacity(records.len());
+    for batch in records.chunk_by(|a, b| a.batch == b.batch) {
+        let raws: Vec<RawTx> = batch.iter().map(|r| RawTx(r.raw.clone())).collect();
+        let sent = sender
+            .send_txs(&raws)
+            .await
+            .wrap_err_with(|| format!("replay batch {}", batch[0].batch))?;
+        tracing::info!("[record] replayed batch {}: {sent:?}", batch[0].batch);
+        hashes.extend(sent);
+    }
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utils::{erc20::transferCall, testing::testing_delegate},
+        DirectBuilder, TxBuilder, TxRequest, TxSigner,
+    };
+    use alloy::{json_abi::JsonAbi, primitives::address, sol_types::SolCall};
+
+    const USDC: Address = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+
+    /// 只记录每次调用的批大小。
+    #[derive(Default)]
+    struct Batches(Mutex<Vec<Vec<B256>>>);
+
+    impl TxSender for Batches {
+        async fn send_txs(&self, txs: &[RawTx]) -> Result<Vec<B256>> {
+            let hashes: Vec<B256> = txs.iter().map(|t| keccak256(&t.0)).collect();
+            self.0.lock().unwrap().push(hashes.clone());
+            Ok(hashes)
+        }
+    }
+
+    async fn signed(count: usize, nonce: u64) -> Vec<RawTx> {
+        let data = transferCall {
+            to: address!("1111111111111111111111111111111111111111"),
+            amount: U256::from(42),
+        }
+        .abi_encode();
+        let request = TxRequest {
+            to: USDC,
+            value: U256::ZERO,
+            data: data.into(),
+            gas_limit: 60_000,
+        };
+        let txs = DirectBuilder::new(1)
+            .build_txs(&vec![request; count], nonce, 30, 2)
+            .unwrap();
+        let (signer, _) = testing_delegate();
+        let mut raws = Vec::new();
+        for tx in txs {
+            raws.push(signer.sign(tx).await.unwrap());
+        }
+        raws
+    }
+
+    #[tokio::test]
+    async fn records_decoded_fields_and_replays_by_batch() {
+        let path =
+            std::env::temp_dir().join(format!("flashseal-record-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut decoder = AbiDecoder::new();
+        decoder.register_abi(
+            USDC,
+            JsonAbi::parse(["function transfer(address to, uint256 amount) returns (bool)"])
+                .unwrap(),
+        );
+        let recorder = RecordSender::new(&path).unwrap().with_decoder(decoder);
+
+        let bundle = signed(2, 7).await;
+        let single = signed(1, 9).await;
+        let hashes = recorder.send_txs(&bundle).await.unwrap();
+        recorder.send_txs(&single).await.unwrap();
+
+        let records = read_records(&path).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].hash, hashes[0]);
+        assert_eq!(records[1].batch, records[0].hash);
+        assert_eq!(records[1].nonce, 8);
+        assert_eq!(records[2].batch, records[2].hash);
+        assert_eq!(records[0].from, testing_delegate().1);
+        assert_eq!(records[0].to, Some(USDC));
+        assert_eq!(records[0].max_fee_per_gas, 30);
+        let call = records[0].decoded.as_ref().unwrap();
+        assert_eq!(call.name, "transfer");
+        assert_eq!(
+            call.params[1],
+            ("amount".to_string(), "Uint(42, 256)".to_string())
+        );
+
+        let sender = Batches::default();
+        let replayed = replay(&path, &sender).await.unwrap();
+        assert_eq!(replayed, records.iter().map(|r| r.hash).collect::<Vec<_>>());
+        let batches: Vec<usize> = sender.0.lock().unwrap().iter().map(Vec::len).collect();
+        assert_eq!(batches, vec![2, 1]);
+
+        // 只发传进来的记录，不重读文件
+        let sender = Batches::default();
+        let replayed = replay_records(&records[2..], &sender).await.unwrap();
+        assert_eq!(replayed, vec![records[2].hash]);
+        assert_eq!(sender.0.lock().unwrap().len(), 1);
+
+        // 篡改 raw 后拒绝回放
+        let tampered = std::fs::read_to_string(&path).unwrap().replacen(
+            &alloy::hex::encode(&bundle[0].0[..8]),
+            "0000000000000000",
+            1,
+        );
+        std::fs::write(&path, tampered).unwrap();
+        assert!(read_records(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+//! Flashbots 风格 relay 的公共部分：`X-Flashbots-Signature` 签名和带签名的 JSON-RPC 调用。
+
+use alloy::{
+    primitives::keccak256,
+    signers::{local::PrivateKeySigner, SignerSync},
+};
+use eyre::{eyre, Result};
+use serde::{de::DeserializeOwned, Deserialize};
+
+/// `X-Flashbots-Signature` header 值：`address:0x<EIP-191 签名(keccak256(body) 的 hex 串)>`。
+pub(crate) fn flashbots_signature(signer: &PrivateKeySigner, body: &[u8]) -> Result<String> {
+    let hash_hex = format!("{:#x}", keccak256(body));
+    let sig = signer
+        .sign_message_sync(hash_hex.as_bytes())
+        .map_err(|e| eyre!("{e}"))?;
+    Ok(format!(
+        "{}:0x{}",
+        signer.address(),
+        alloy::hex::encode(sig.as_bytes())
+    ))
+}
+
+/// 发一个带 `X-Flashbots-Signature` 的 JSON-RPC 请求并解析 `result`。
+pub(crate) async fn signed_rpc<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+    signer: &PrivateKeySigner,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<T> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+    let body_bytes = serde_json::to_vec(&body)?;
+    let sig_header = flashbots_signature(signer, &body_bytes)?;
+
+    let resp = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("X-Flashbots-Signature", sig_header)
+        .body(body_bytes)
+        .send()
+        .await?;
+    let status = resp.status();
+    let text = resp.text().await?;
+    tracing::debug!("[relay] {method} status={} body={text}", status.as_u16());
+    if !status.is_success() {
+        return Err(eyre!("relay http {} ({method}): {}", status, text));
+    }
+
+    let parsed: RpcResponse<T> = serde_json::from_str(&text)
+        .map_err(|e| eyre!("decode relay response failed ({method}): {e}; body={text}"))?;
+    if let Some(err) = parsed.error {
+        return Err(eyre!("relay error {} ({method}): {}", err.code, err.message));
+    }
+    parsed
+        .result
+        .ok_or_else(|| eyre!("relay response missing result ({method}): {text}"))
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
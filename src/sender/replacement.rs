@@ -0,0 +1,375 @@
+use std::time::Duration;
+
+use alloy::{
+    consensus::TxEip1559,
+    primitives::{Address, Bytes, TxKind, U256},
+};
+use eyre::Result;
+
+use super::{TxHandle, TxOutcome, TxSender, TxTracker};
+use crate::TxSigner;
+
+/// geth / reth txpool 的最低替换加价比例（max_fee 和 priority 都要 ≥ +10%）。
+pub const MIN_REPLACEMENT_BUMP_PERCENT: u64 = 10;
+
+/// 卡单替换策略。
+#[derive(Debug, Clone)]
+pub struct ReplacementPolicy {
+    /// 每次加价比例，≥ [`MIN_REPLACEMENT_BUMP_PERCENT`]。默认 12%（留点余量避免取整后不足 10%）。
+    pub bump_percent: u64,
+    /// 每个版本等待上链的时长，超时即加价重发。默认 30s。
+    pub interval: Duration,
+    /// max_fee_per_gas 上限（wei）。加价后超过上限就不再重发，只继续等已发出的版本。
+    pub max_fee_cap: u128,
+    /// 最多加价次数（不含首发）。默认 5。
+    pub max_bumps: u32,
+}
+
+impl ReplacementPolicy {
+    pub fn new(max_fee_cap: u128) -> Self {
+        Self {
+            bump_percent: 12,
+            interval: Duration::from_secs(30),
+            max_fee_cap,
+            max_bumps: 5,
+        }
+    }
+}
+
+/// 按 `percent` 同时抬高 max_fee 和 priority（向上取整，至少 +1 wei）。
+pub fn bump_fees(tx: &TxEip1559, percent: u64) -> TxEip1559 {
+    let bump = |v: u128| v + (v * percent as u128).div_ceil(100).max(1);
+    let mut next = tx.clone();
+    next.max_fee_per_gas = bump(tx.max_fee_per_gas);
+    next.max_priority_fee_per_gas = bump(tx.max_priority_fee_per_gas);
+    next
+}
+
+/// 构造同 nonce 的取消交易：给自己转 0，fee 在 `tx` 基础上按 `percent` 加价。
+pub fn cancel_tx(tx: &TxEip1559, from: Address, percent: u64) -> TxEip1559 {
+    let bumped = bump_fees(tx, percent);
+    TxEip1559 {
+        chain_id: tx.chain_id,
+        nonce: tx.nonce,
+        gas_limit: 21_000,
+        to: TxKind::Call(from),
+        value: U256::ZERO,
+        input: Bytes::new(),
+        max_fee_per_gas: bumped.max_fee_per_gas,
+        max_priority_fee_per_gas: bumped.max_priority_fee_per_gas,
+        ..Default::default()
+    }
+}
+
+/// 卡单管理：同 nonce 重签加价（speed-up）或自转 0 取消（cancel）。
+///
+/// 签名走 [`TxSigner`]，所以 `LocalSigner` / `RemoteSigner` 都可以用；
+/// 用 `RemoteSigner` 时 rule.js 需要允许 operator 给自己转 0（cancel）。
+pub struct TxReplacer<'a, S: TxSigner, T: TxSender> {
+    signer: &'a S,
+    sender: &'a T,
+    tracker: TxTracker,
+    policy: ReplacementPolicy,
+}
+
+impl<'a, S: TxSigner, T: TxSender> TxReplacer<'a, S, T> {
+    pub fn new(
+        signer: &'a S,
+        sender: &'a T,
+        tracker: TxTracker,
+        policy: ReplacementPolicy,
+    ) -> Result<Self> {
+        eyre::ensure!(
+            policy.bump_percent >= MIN_REPLACEMENT_BUMP_PERCENT,
+            "bump_percent {} < {MIN_REPLACEMENT_BUMP_PERCENT}% replacement rule",
+            policy.bump_percent
+        );
+        Ok(Self {
+            signer,
+            sender,
+            tracker,
+            policy,
+        })
+    }
+
+    /// 签名并发送 `tx`，每 `interval` 未上链就加价重发，直到任一版本上链 / nonce 被占 /
+    /// 达到 `max_fee_cap` 或 `max_bumps` 后等满 tracker 的 timeout。
+    ///
+    /// `tx` 若已经广播过，把它的 handle 放进 `already_sent`，避免漏看旧版本上链。
+    pub async fn speed_up(&self, tx: TxEip1559, already_sent: &[TxHandle]) -> Result<TxOutcome> {
+        eyre::ensure!(
+            tx.max_fee_per_gas <= self.policy.max_fee_cap,
+            "max_fee_per_gas {} already above cap {}",
+            tx.max_fee_per_gas,
+            self.policy.max_fee_cap
+        );
+        let mut sent = already_sent.to_vec();
+        let mut current = tx;
+        for attempt in 0..=self.policy.max_bumps {
+            if attempt > 0 {
+                let next = bump_fees(&current, self.policy.bump_percent);
+                if next.max_fee_per_gas > self.policy.max_fee_cap {
+                    tracing::warn!(
+                        "[replace] nonce {} reached fee cap {} wei, stop bumping",
+                        current.nonce,
+                        self.policy.max_fee_cap
+                    );
+                    break;
+                }
+                current = next;
+            }
+            sent.push(self.sign_and_send(&current).await?);
+            if let Some(outcome) = self.tracker.wait_any(&sent, self.policy.interval).await? {
+                return Ok(outcome);
+            }
+        }
+        // 不再加价：继续等已发出的任一版本，直到 tracker 超时
+        let handle = sent
+            .last()
+            .cloned()
+            .ok_or_else(|| eyre::eyre!("nothing sent"))?;
+        match handle.wait().await? {
+            TxOutcome::Replaced { .. } | TxOutcome::Dropped => self
+                .tracker
+                .wait_any(&sent, Duration::ZERO)
+                .await?
+                .ok_or_else(|| eyre::eyre!("nonce {} unresolved", handle.nonce)),
+            outcome => Ok(outcome),
+        }
+    }
+
+    /// 取消 `tx`（最后一次广播的版本）：同 nonce 给自己转 0，并按策略继续加价直到上链。
+    ///
+    /// 返回 `Mined` 说明取消交易上链；若原交易抢先上链，返回的是原交易的 `Mined`
+    /// （通过 `hash` 区分）。原交易会重新签一次（不广播）来拿 hash 一起跟踪，
+    /// 签名不确定的签名器请把原交易的 handle 放进 `already_sent`。
+    pub async fn cancel(&self, tx: &TxEip1559, already_sent: &[TxHandle]) -> Result<TxOutcome> {
+        let original = self.tracker.track(&self.signer.sign(tx.clone()).await?)?;
+        let mut tracked = already_sent.to_vec();
+        if !tracked.iter().any(|h| h.hash == original.hash) {
+            tracked.push(original);
+        }
+        let cancel = cancel_tx(tx, self.signer.address(), self.policy.bump_percent);
+        eyre::ensure!(
+            cancel.max_fee_per_gas <= self.policy.max_fee_cap,
+            "cancel max_fee_per_gas {} exceeds cap {}",
+            cancel.max_fee_per_gas,
+            self.policy.max_fee_cap
+        );
+        tracing::info!(
+            "[replace] cancelling nonce {} with max_fee={} priority={}",
+            cancel.nonce,
+            cancel.max_fee_per_gas,
+            cancel.max_priority_fee_per_gas
+        );
+        self.speed_up(cancel, &tracked).await
+    }
+
+    /// 发送失败（如 `replacement transaction underpriced` / `already known`）只记日志：
+    /// 之前的版本可能仍在 mempool 里，继续等。
+    async fn sign_and_send(&self, tx: &TxEip1559) -> Result<TxHandle> {
+        let raw = self.signer.sign(tx.clone()).await?;
+        let handle = self.tracker.track(&raw)?;
+        match self.sender.send_txs(&[raw]).await {
+            Ok(_) => tracing::info!(
+                "[replace] sent {} nonce={} max_fee={} priority={}",
+                handle.hash,
+                tx.nonce,
+                tx.max_fee_per_gas,
+                tx.max_priority_fee_per_gas
+            ),
+            Err(e) => tracing::warn!("[replace] send {} failed: {e:#}", handle.hash),
+        }
+        Ok(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{utils::testing::serve_json_rpc, LocalSigner, RpcSender};
+    use alloy::{
+        consensus::{Transaction, TxEnvelope},
+        eips::Decodable2718,
+        network::AnyNetwork,
+        primitives::{address, keccak256, B256},
+        providers::{Provider, ProviderBuilder},
+        signers::local::PrivateKeySigner,
+    };
+    use serde_json::{json, Value};
+
+    fn base_tx() -> TxEip1559 {
+        TxEip1559 {
+            chain_id: 1,
+            nonce: 3,
+            gas_limit: 50_000,
+            to: TxKind::Call(address!("1111111111111111111111111111111111111111")),
+            value: U256::from(1),
+            max_fee_per_gas: 100,
+            max_priority_fee_per_gas: 10,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn bump_respects_replacement_rule() {
+        let next = bump_fees(&base_tx(), 10);
+        assert_eq!(
+            (next.max_fee_per_gas, next.max_priority_fee_per_gas),
+            (110, 11)
+        );
+        let tiny = TxEip1559 {
+            max_fee_per_gas: 3,
+            max_priority_fee_per_gas: 0,
+            ..base_tx()
+        };
+        let next = bump_fees(&tiny, 10);
+        assert_eq!(
+            (next.max_fee_per_gas, next.max_priority_fee_per_gas),
+            (4, 1)
+        );
+
+        let from = address!("2222222222222222222222222222222222222222");
+        let cancel = cancel_tx(&base_tx(), from, 12);
+        assert_eq!(cancel.to, TxKind::Call(from));
+        assert_eq!(
+            (cancel.nonce, cancel.value, cancel.gas_limit),
+            (3, U256::ZERO, 21_000)
+        );
+        assert_eq!(cancel.max_fee_per_gas, 112);
+    }
+
+    /// 桩节点：记录收到的 raw tx；第 `mine_at` 笔（从 1 数）发来后让它上链。
+    async fn stub_node(
+        mine_at: usize,
+    ) -> (
+        crate::utils::testing::StubServer,
+        Arc<Mutex<Vec<TxEnvelope>>>,
+    ) {
+        stub_node_with(mine_at, None).await
+    }
+
+    /// 同 [`stub_node`]，`premined` 是别处广播、已经上链的交易。
+    async fn stub_node_with(
+        mine_at: usize,
+        premined: Option<B256>,
+    ) -> (
+        crate::utils::testing::StubServer,
+        Arc<Mutex<Vec<TxEnvelope>>>,
+    ) {
+        let sent: Arc<Mutex<Vec<TxEnvelope>>> = Arc::default();
+        let s = sent.clone();
+        let stub = serve_json_rpc(move |method, params| {
+            let sent = s.lock().unwrap();
+            let mined = premined.or_else(|| sent.get(mine_at - 1).map(|tx| *tx.tx_hash()));
+            match method {
+                "eth_sendRawTransaction" => {
+                    drop(sent);
+                    let raw = alloy::hex::decode(params[0].as_str().unwrap()).unwrap();
+                    let tx = TxEnvelope::decode_2718(&mut raw.as_slice()).unwrap();
+                    s.lock().unwrap().push(tx);
+                    Ok(json!(keccak256(&raw)))
+                }
+                "eth_getTransactionReceipt" => {
+                    let hash: B256 = serde_json::from_value(params[0].clone()).unwrap();
+                    Ok(if Some(hash) == mined {
+                        json!({
+                            "type": "0x2", "status": "0x1", "transactionHash": hash,
+                            "transactionIndex": "0x0", "blockHash": B256::repeat_byte(1),
+                            "blockNumber": "0x10", "from": Address::ZERO, "to": Value::Null,
+                            "cumulativeGasUsed": "0x5208", "gasUsed": "0x5208",
+                            "effectiveGasPrice": "0x7", "contractAddress": null, "logs": [],
+                            "logsBloom": format!("0x{}", "00".repeat(256)),
+                        })
+                    } else {
+                        Value::Null
+                    })
+                }
+                "eth_blockNumber" => Ok(json!("0x10")),
+                "eth_getTransactionCount" => Ok(json!(if mined.is_some() { "0x4" } else { "0x3" })),
+                m => Err((-32601, format!("method {m} not found"))),
+            }
+        })
+        .await
+        .unwrap();
+        (stub, sent)
+    }
+
+    fn tracker(url: &str) -> TxTracker {
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_http(url.parse().unwrap())
+            .erased();
+        TxTracker::new(provider)
+            .with_poll_interval(Duration::from_millis(5))
+            .with_timeout(Duration::from_secs(5))
+    }
+
+    fn policy() -> ReplacementPolicy {
+        ReplacementPolicy {
+            interval: Duration::from_millis(30),
+            ..ReplacementPolicy::new(130)
+        }
+    }
+
+    #[tokio::test]
+    async fn speeds_up_until_mined_under_cap() {
+        let (stub, sent) = stub_node(3).await;
+        let signer = LocalSigner::new(PrivateKeySigner::random());
+        let sender = RpcSender::new(stub.url()).unwrap();
+        let replacer = TxReplacer::new(&signer, &sender, tracker(stub.url()), policy()).unwrap();
+
+        let TxOutcome::Mined(mined) = replacer.speed_up(base_tx(), &[]).await.unwrap() else {
+            panic!("expected mined");
+        };
+        let sent = sent.lock().unwrap();
+        let fees: Vec<u128> = sent.iter().map(|tx| tx.max_fee_per_gas()).collect();
+        assert_eq!(fees, vec![100, 112, 126]);
+        assert_eq!(mined.hash, *sent[2].tx_hash());
+        assert!(sent.iter().all(|tx| tx.nonce() == 3));
+    }
+
+    #[tokio::test]
+    async fn cancel_sends_self_transfer_and_stops_at_cap() {
+        // 永不上链：112 → 126 之后再加价会超过 cap=130
+        let (stub, sent) = stub_node(usize::MAX).await;
+        let signer = LocalSigner::new(PrivateKeySigner::random());
+        let sender = RpcSender::new(stub.url()).unwrap();
+        let t = tracker(stub.url()).with_timeout(Duration::from_millis(50));
+        let replacer = TxReplacer::new(&signer, &sender, t, policy()).unwrap();
+
+        assert!(replacer.cancel(&base_tx(), &[]).await.is_err());
+        let sent = sent.lock().unwrap();
+        let fees: Vec<u128> = sent.iter().map(|tx| tx.max_fee_per_gas()).collect();
+        assert_eq!(fees, vec![112, 126]);
+        assert!(sent
+            .iter()
+            .all(|tx| tx.to() == Some(signer.address()) && tx.value().is_zero()));
+
+        let p = ReplacementPolicy {
+            bump_percent: 5,
+            ..policy()
+        };
+        assert!(TxReplacer::new(&signer, &sender, tracker(stub.url()), p).is_err());
+    }
+
+    #[tokio::test]
+    async fn cancel_reports_original_when_it_mines() {
+        let signer = LocalSigner::new(PrivateKeySigner::random());
+        let original = signer.sign(base_tx()).await.unwrap();
+        let original_hash = keccak256(&original.0);
+        let (stub, sent) = stub_node_with(usize::MAX, Some(original_hash)).await;
+        let sender = RpcSender::new(stub.url()).unwrap();
+        let replacer = TxReplacer::new(&signer, &sender, tracker(stub.url()), policy()).unwrap();
+
+        // 没传 already_sent，也能看到原交易上链
+        let TxOutcome::Mined(mined) = replacer.cancel(&base_tx(), &[]).await.unwrap() else {
+            panic!("expected mined");
+        };
+        assert_eq!(mined.hash, original_hash);
+        // 原交易没有被再次广播
+        assert!(sent.lock().unwrap().iter().all(|tx| tx.value().is_zero()));
+    }
+}
@@ -8,6 +8,7 @@ use eyre::Result;
 use super::{RawTx, TxSender};
 
 /// 通过 RPC 逐笔广播签名交易
+#[derive(Clone)]
 pub struct RpcSender {
     provider: DynProvider<AnyNetwork>,
 }
@@ -0,0 +1,427 @@
+use std::time::{Duration, Instant};
+
+use alloy::{
+    consensus::{transaction::SignerRecoverable, Transaction, TxEnvelope},
+    eips::Decodable2718,
+    network::{AnyNetwork, AnyTransactionReceipt, ReceiptResponse},
+    primitives::{Address, Log, B256},
+    providers::{DynProvider, Provider},
+};
+use eyre::{eyre, Result, WrapErr};
+
+use super::{RawTx, TxSender};
+use crate::simulator::{AbiDecoder, DecodedEvent};
+
+/// 已上链并达到确认数的交易回执（从 RPC receipt 抽出常用字段）。
+#[derive(Debug, Clone)]
+pub struct MinedTx {
+    pub hash: B256,
+    pub block_number: u64,
+    pub block_hash: B256,
+    /// 达到要求时的确认数（含所在 block 本身）。
+    pub confirmations: u64,
+    pub success: bool,
+    pub gas_used: u64,
+    pub effective_gas_price: u128,
+    pub logs: Vec<Log>,
+}
+
+impl MinedTx {
+    fn from_receipt(r: &AnyTransactionReceipt, confirmations: u64) -> Self {
+        Self {
+            hash: r.transaction_hash,
+            block_number: r.block_number.unwrap_or_default(),
+            block_hash: r.block_hash.unwrap_or_default(),
+            confirmations,
+            success: r.status(),
+            gas_used: r.gas_used,
+            effective_gas_price: r.effective_gas_price,
+            logs: r
+                .inner
+                .inner
+                .logs()
+                .iter()
+                .map(|l| l.inner.clone())
+                .collect(),
+        }
+    }
+
+    /// 用 [`AbiDecoder`] 解码 logs；未注册 ABI 的 log 为 `None`，与 `logs` 一一对应。
+    pub fn decode_logs(&self, decoder: &AbiDecoder) -> Vec<Option<DecodedEvent>> {
+        self.logs.iter().map(|l| decoder.decode_log(l)).collect()
+    }
+
+    /// 实际花费（wei）= gas_used × effective_gas_price。
+    pub fn fee_wei(&self) -> u128 {
+        self.gas_used as u128 * self.effective_gas_price
+    }
+}
+
+/// [`TxHandle::wait`] 的结果。
+#[derive(Debug, Clone)]
+pub enum TxOutcome {
+    /// 已上链并达到确认数（`success` 可能为 false，即 revert）。
+    Mined(MinedTx),
+    /// 同 nonce 的另一笔交易已上链（被 speed-up / cancel 替换，或被别人抢用了 nonce）。
+    Replaced { nonce: u64 },
+    /// 节点既没有 receipt 也不在 mempool 里，超过 drop 检测时长。
+    Dropped,
+}
+
+/// 交易上链追踪器：轮询 receipt，处理确认数 / 替换 / 丢弃 / reorg。
+///
+/// reorg 处理：只信任节点按 canonical 链返回的 receipt —— receipt 消失（被 reorg
+/// 掉）或 block hash 变化（被重新打包到别的 block）都会重置确认计数继续等。
+///
+/// ```ignore
+/// let tracker = TxTracker::new(provider.clone()).with_confirmations(3);
+/// let handles = tracker.send_and_track(&sender, &raws).await?;
+/// for h in &handles {
+///     match h.wait().await? {
+///         TxOutcome::Mined(m) => tracing::info!("{} ok={} gas={}", m.hash, m.success, m.gas_used),
+///         other => tracing::warn!("{}: {other:?}", h.hash),
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TxTracker {
+    provider: DynProvider<AnyNetwork>,
+    confirmations: u64,
+    poll_interval: Duration,
+    timeout: Duration,
+    drop_after: Option<Duration>,
+}
+
+impl TxTracker {
+    /// 默认：1 个确认、每 2s 轮询、3 分钟超时、不做 drop 检测。
+    pub fn new(provider: DynProvider<AnyNetwork>) -> Self {
+        Self {
+            provider,
+            confirmations: 1,
+            poll_interval: Duration::from_secs(2),
+            timeout: Duration::from_secs(180),
+            drop_after: None,
+        }
+    }
+
+    /// 要求的确认数（含所在 block，至少 1）。
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations.max(1);
+        self
+    }
+
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// 超过 `timeout` 仍未有结论则 `wait` 返回 `Err`。
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 节点查不到交易（`eth_getTransactionByHash` 为 null）持续 `after` 即判定 Dropped。
+    ///
+    /// 只适合公共 mempool 广播；私有交易 / bundle 上链前公共节点本来就看不到。
+    pub fn with_drop_detection(mut self, after: Duration) -> Self {
+        self.drop_after = Some(after);
+        self
+    }
+
+    /// 从签名交易解出 hash / from / nonce 生成追踪 handle（不发请求）。
+    pub fn track(&self, tx: &RawTx) -> Result<TxHandle> {
+        let envelope = TxEnvelope::decode_2718(&mut tx.0.as_ref())
+            .wrap_err("raw tx is not a valid EIP-2718 envelope")?;
+        let from = envelope
+            .recover_signer()
+            .map_err(|e| eyre!("recover tx signer failed: {e}"))?;
+        Ok(TxHandle {
+            tracker: self.clone(),
+            hash: *envelope.tx_hash(),
+            from,
+            nonce: envelope.nonce(),
+        })
+    }
+
+    pub fn track_all(&self, txs: &[RawTx]) -> Result<Vec<TxHandle>> {
+        txs.iter().map(|tx| self.track(tx)).collect()
+    }
+
+    /// 发送并返回每笔交易的 handle。
+    ///
+    /// 追踪用的是从 raw tx 算出的 tx hash，而不是 `send_txs` 的返回值
+    /// （`FlashbotsSender` 返回的是 bundleHash）。
+    pub async fn send_and_track<S: TxSender>(
+        &self,
+        sender: &S,
+        txs: &[RawTx],
+    ) -> Result<Vec<TxHandle>> {
+        let handles = self.track_all(txs)?;
+        sender.send_txs(txs).await?;
+        Ok(handles)
+    }
+
+    /// 同一 nonce 的多个版本（speed-up 后的替换交易）里，等任意一个上链。
+    ///
+    /// `within` 内有版本上链则继续等到确认数并返回其结果；nonce 被这些版本之外的
+    /// 交易用掉返回 `Replaced`；都没有则返回 `None`，由调用方决定是否继续加价。
+    pub async fn wait_any(
+        &self,
+        handles: &[TxHandle],
+        within: Duration,
+    ) -> Result<Option<TxOutcome>> {
+        let Some(first) = handles.first() else {
+            return Ok(None);
+        };
+        let start = Instant::now();
+        loop {
+            if let Some(h) = self.find_mined(handles).await? {
+                return h.wait().await.map(Some);
+            }
+            let mined_nonce = self.provider.get_transaction_count(first.from).await?;
+            if mined_nonce > first.nonce {
+                // 再查一次，避免 "查 receipt 之后我们的某个版本恰好上链" 的竞态
+                return match self.find_mined(handles).await? {
+                    Some(h) => h.wait().await.map(Some),
+                    None => Ok(Some(TxOutcome::Replaced { nonce: first.nonce })),
+                };
+            }
+            if start.elapsed() >= within {
+                return Ok(None);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn find_mined<'h>(&self, handles: &'h [TxHandle]) -> Result<Option<&'h TxHandle>> {
+        for h in handles {
+            if self.provider.get_transaction_receipt(h.hash).await?.is_some() {
+                return Ok(Some(h));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// 单笔已提交交易的追踪 handle，可 clone 后分发给多个 task 等待。
+#[derive(Clone)]
+pub struct TxHandle {
+    tracker: TxTracker,
+    pub hash: B256,
+    pub from: Address,
+    pub nonce: u64,
+}
+
+impl TxHandle {
+    /// 等待交易得到结论，见 [`TxOutcome`]。
+    pub async fn wait(&self) -> Result<TxOutcome> {
+        let t = &self.tracker;
+        let start = Instant::now();
+        let mut seen: Option<(u64, B256)> = None;
+        let mut missing_since: Option<Instant> = None;
+
+        loop {
+            match t.provider.get_transaction_receipt(self.hash).await? {
+                Some(receipt) => {
+                    missing_since = None;
+                    let block = receipt
+                        .block_number
+                        .zip(receipt.block_hash)
+                        .ok_or_else(|| eyre!("receipt of {} missing block info", self.hash))?;
+                    if let Some(prev) = seen
+                        && prev != block
+                    {
+                        tracing::warn!(
+                            "[tracker] {} reorged from block {} to {}",
+                            self.hash,
+                            prev.0,
+                            block.0
+                        );
+                    }
+                    seen = Some(block);
+
+                    let head = t.provider.get_block_number().await?;
+                    let confirmations = (head + 1).saturating_sub(block.0);
+                    if confirmations >= t.confirmations {
+                        return Ok(TxOutcome::Mined(MinedTx::from_receipt(
+                            &receipt,
+                            confirmations,
+                        )));
+                    }
+                }
+                None => {
+                    if let Some((number, _)) = seen.take() {
+                        tracing::warn!(
+                            "[tracker] {} un-mined by reorg (was in block {number})",
+                            self.hash
+                        );
+                    }
+                    let mined_nonce = t.provider.get_transaction_count(self.from).await?;
+                    // 再查一次 receipt，避免 "查 receipt 之后我们自己的交易恰好上链" 的竞态
+                    if mined_nonce > self.nonce
+                        && t.provider
+                            .get_transaction_receipt(self.hash)
+                            .await?
+                            .is_none()
+                    {
+                        return Ok(TxOutcome::Replaced { nonce: self.nonce });
+                    }
+                    if mined_nonce <= self.nonce
+                        && let Some(grace) = t.drop_after
+                    {
+                        let known = t
+                            .provider
+                            .get_transaction_by_hash(self.hash)
+                            .await?
+                            .is_some();
+                        if known {
+                            missing_since = None;
+                        } else if missing_since.get_or_insert_with(Instant::now).elapsed() >= grace
+                        {
+                            return Ok(TxOutcome::Dropped);
+                        }
+                    }
+                }
+            }
+
+            if start.elapsed() >= t.timeout {
+                return Err(eyre!(
+                    "tx {} not settled within {:?} (from {} nonce {})",
+                    self.hash,
+                    t.timeout,
+                    self.from,
+                    self.nonce
+                ));
+            }
+            tokio::time::sleep(t.poll_interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::{utils::testing::serve_json_rpc, LocalSigner, TxSigner};
+    use alloy::{
+        consensus::TxEip1559,
+        primitives::{address, TxKind, U256},
+        providers::ProviderBuilder,
+        signers::local::PrivateKeySigner,
+    };
+    use serde_json::{json, Value};
+
+    async fn signed_tx(nonce: u64) -> RawTx {
+        let signer =
+            LocalSigner::new(PrivateKeySigner::from_bytes(&B256::repeat_byte(0x07)).unwrap());
+        signer
+            .sign(TxEip1559 {
+                chain_id: 1,
+                nonce,
+                gas_limit: 21_000,
+                max_fee_per_gas: 10,
+                max_priority_fee_per_gas: 1,
+                to: TxKind::Call(address!("1111111111111111111111111111111111111111")),
+                value: U256::from(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+    }
+
+    fn receipt(hash: &Value, block: u64, block_hash: B256) -> Value {
+        json!({
+            "type": "0x2",
+            "status": "0x1",
+            "transactionHash": hash,
+            "transactionIndex": "0x0",
+            "blockHash": block_hash,
+            "blockNumber": format!("0x{block:x}"),
+            "from": "0x0000000000000000000000000000000000000000",
+            "to": "0x1111111111111111111111111111111111111111",
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x7",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+        })
+    }
+
+    fn tracker(url: &str) -> TxTracker {
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_http(url.parse().unwrap())
+            .erased();
+        TxTracker::new(provider)
+            .with_poll_interval(Duration::from_millis(10))
+            .with_timeout(Duration::from_secs(5))
+    }
+
+    #[tokio::test]
+    async fn waits_for_confirmations_across_reorg() {
+        let polls = Arc::new(AtomicUsize::new(0));
+        let p = polls.clone();
+        let stub = serve_json_rpc(move |method, params| match method {
+            // 第 1 次：block 0x10（1 个确认）；第 2 次：被 reorg 掉；之后：block 0x11
+            "eth_getTransactionReceipt" => Ok(match p.fetch_add(1, Ordering::SeqCst) {
+                0 => receipt(&params[0], 0x10, B256::repeat_byte(0xa)),
+                1 => Value::Null,
+                _ => receipt(&params[0], 0x11, B256::repeat_byte(0xb)),
+            }),
+            "eth_blockNumber" => Ok(json!(if p.load(Ordering::SeqCst) <= 1 {
+                "0x10"
+            } else {
+                "0x13"
+            })),
+            "eth_getTransactionCount" => Ok(json!("0x5")),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+
+        let handle = tracker(stub.url())
+            .with_confirmations(3)
+            .track(&signed_tx(5).await)
+            .unwrap();
+        assert_eq!(handle.nonce, 5);
+        let TxOutcome::Mined(mined) = handle.wait().await.unwrap() else {
+            panic!("expected mined");
+        };
+        assert_eq!(mined.hash, handle.hash);
+        assert_eq!(mined.block_hash, B256::repeat_byte(0xb));
+        assert_eq!(mined.confirmations, 3);
+        assert!(mined.success);
+        assert_eq!(mined.fee_wei(), 21_000 * 7);
+    }
+
+    #[tokio::test]
+    async fn detects_replacement_and_drop() {
+        let stub = serve_json_rpc(|method, _| match method {
+            "eth_getTransactionReceipt" | "eth_getTransactionByHash" => Ok(Value::Null),
+            // nonce 0 已上链，nonce 1 还没
+            "eth_getTransactionCount" => Ok(json!("0x1")),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+
+        let t = tracker(stub.url());
+        let handle = t.track(&signed_tx(0).await).unwrap();
+        assert!(matches!(
+            handle.wait().await.unwrap(),
+            TxOutcome::Replaced { nonce: 0 }
+        ));
+
+        let handle = t
+            .with_drop_detection(Duration::from_millis(30))
+            .track(&signed_tx(1).await)
+            .unwrap();
+        assert!(matches!(handle.wait().await.unwrap(), TxOutcome::Dropped));
+    }
+}
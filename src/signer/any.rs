@@ -0,0 +1,112 @@
+use std::{future::Future, pin::Pin};
+
+use alloy::{
+    consensus::TxEip1559,
+    dyn_abi::TypedData,
+    primitives::{Address, Signature, B256},
+};
+use eyre::Result;
+
+use super::{LocalSigner, PolicySigner, RemoteSigner, TxSigner};
+use crate::RawTx;
+
+/// 运行时选定的签名器，[`crate::app::AppConfigBase::build_signer`] 的返回值。
+///
+/// `TxSigner` 使用 RPITIT 不能做成 `dyn`，所以用 enum 分发；同一个 bin 改 config
+/// 即可在生产 cs-signer 和本地 key 之间切换。
+pub enum AnySigner {
+    Remote(RemoteSigner),
+    Local(LocalSigner),
+    /// 签名前先过 [`TxPolicy`](super::TxPolicy)。
+    Policy(Box<PolicySigner<AnySigner>>),
+}
+
+impl From<RemoteSigner> for AnySigner {
+    fn from(s: RemoteSigner) -> Self {
+        Self::Remote(s)
+    }
+}
+
+impl From<LocalSigner> for AnySigner {
+    fn from(s: LocalSigner) -> Self {
+        Self::Local(s)
+    }
+}
+
+impl From<PolicySigner<AnySigner>> for AnySigner {
+    fn from(s: PolicySigner<AnySigner>) -> Self {
+        Self::Policy(Box::new(s))
+    }
+}
+
+/// `Policy` 里包的仍是 `AnySigner`，递归的 future 类型用 `Box::pin` 打断
+/// （同 `AnySender::send_boxed`）。
+type BoxedSign<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+impl AnySigner {
+    fn sign_boxed(&self, tx: TxEip1559) -> BoxedSign<'_, RawTx> {
+        Box::pin(async move {
+            match self {
+                Self::Remote(s) => s.sign(tx).await,
+                Self::Local(s) => s.sign(tx).await,
+                Self::Policy(s) => s.sign(tx).await,
+            }
+        })
+    }
+
+    fn sign_hash_boxed(&self, hash: B256) -> BoxedSign<'_, Signature> {
+        Box::pin(async move {
+            match self {
+                Self::Remote(s) => s.sign_hash(hash).await,
+                Self::Local(s) => s.sign_hash(hash).await,
+                Self::Policy(s) => s.sign_hash(hash).await,
+            }
+        })
+    }
+
+    fn sign_message_boxed<'a>(&'a self, message: &'a [u8]) -> BoxedSign<'a, Signature> {
+        Box::pin(async move {
+            match self {
+                Self::Remote(s) => s.sign_message(message).await,
+                Self::Local(s) => s.sign_message(message).await,
+                Self::Policy(s) => s.sign_message(message).await,
+            }
+        })
+    }
+
+    fn sign_typed_data_boxed<'a>(&'a self, data: &'a TypedData) -> BoxedSign<'a, Signature> {
+        Box::pin(async move {
+            match self {
+                Self::Remote(s) => s.sign_typed_data(data).await,
+                Self::Local(s) => s.sign_typed_data(data).await,
+                Self::Policy(s) => s.sign_typed_data(data).await,
+            }
+        })
+    }
+}
+
+impl TxSigner for AnySigner {
+    fn address(&self) -> Address {
+        match self {
+            Self::Remote(s) => s.address(),
+            Self::Local(s) => s.address(),
+            Self::Policy(s) => s.address(),
+        }
+    }
+
+    async fn sign(&self, tx: TxEip1559) -> Result<RawTx> {
+        self.sign_boxed(tx).await
+    }
+
+    async fn sign_hash(&self, hash: B256) -> Result<Signature> {
+        self.sign_hash_boxed(hash).await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        self.sign_message_boxed(message).await
+    }
+
+    async fn sign_typed_data(&self, data: &TypedData) -> Result<Signature> {
+        self.sign_typed_data_boxed(data).await
+    }
+}
@@ -1,14 +1,22 @@
+use std::path::Path;
+
 use alloy::{
     consensus::{SignableTransaction, TxEip1559, TxEnvelope},
-    primitives::Address,
-    signers::{local::PrivateKeySigner, SignerSync},
+    primitives::{Address, Signature, B256},
+    signers::{
+        local::{coins_bip39::English, MnemonicBuilder, PrivateKeySigner},
+        SignerSync,
+    },
 };
-use eyre::Result;
+use eyre::{Result, WrapErr};
 
 use super::TxSigner;
 use crate::RawTx;
 
 /// 本地私钥签名器：使用 PrivateKeySigner 对未签名交易进行签名
+///
+/// 私钥来源：直接传入、keystore 文件（[`from_keystore`](Self::from_keystore)）
+/// 或助记词（[`from_mnemonic`](Self::from_mnemonic)）。
 pub struct LocalSigner {
     signer: PrivateKeySigner,
 }
@@ -17,6 +25,28 @@ impl LocalSigner {
     pub fn new(signer: PrivateKeySigner) -> Self {
         Self { signer }
     }
+
+    /// 从 Web3 Secret Storage keystore 文件解密（geth / `cast wallet import`）。
+    /// 密码错误时 MAC 校验失败报错。
+    pub fn from_keystore<P: AsRef<Path>>(path: P, password: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let signer = PrivateKeySigner::decrypt_keystore(path, password)
+            .wrap_err_with(|| format!("failed to decrypt keystore: {}", path.display()))?;
+        Ok(Self::new(signer))
+    }
+
+    /// 从 BIP-39 英文助记词按 `m/44'/60'/0'/0/{index}` 派生。
+    /// 词表和 checksum 都会校验，拼错的助记词直接报错。
+    pub fn from_mnemonic(phrase: &str, passphrase: &str, index: u32) -> Result<Self> {
+        let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+        let signer = MnemonicBuilder::<English>::default()
+            .phrase(phrase.to_lowercase())
+            .password(passphrase)
+            .index(index)?
+            .build()
+            .wrap_err("invalid mnemonic")?;
+        Ok(Self::new(signer))
+    }
 }
 
 impl TxSigner for LocalSigner {
@@ -29,4 +59,65 @@ impl TxSigner for LocalSigner {
         let envelope = TxEnvelope::Eip1559(tx.into_signed(sig));
         Ok(RawTx::from(envelope))
     }
+
+    async fn sign_hash(&self, hash: B256) -> Result<Signature> {
+        Ok(self.signer.sign_hash_sync(&hash)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    const ABANDON: &str = "abandon abandon abandon abandon abandon abandon \
+                           abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn decrypts_keystore_spec_vector() {
+        // Web3 Secret Storage 规范里的 pbkdf2 测试向量，password = "testpassword"。
+        let json = r#"{
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+                "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144, "dklen": 32, "prf": "hmac-sha256",
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version": 3
+        }"#;
+        let path =
+            std::env::temp_dir().join(format!("flashseal-keystore-{}.json", std::process::id()));
+        std::fs::write(&path, json).unwrap();
+        let signer = LocalSigner::from_keystore(&path, "testpassword");
+        let wrong = LocalSigner::from_keystore(&path, "wrong");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            alloy::hex::encode(signer.unwrap().signer.to_bytes()),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+        assert!(wrong.is_err());
+    }
+
+    #[test]
+    fn derives_mnemonic_and_validates_checksum() {
+        let signer = LocalSigner::from_mnemonic(ABANDON, "", 0).unwrap();
+        assert_eq!(signer.address(), address!("9858EfFD232B4033E47d90003D41EC34EcaEda94"));
+        assert_ne!(
+            LocalSigner::from_mnemonic(ABANDON, "", 3).unwrap().address(),
+            signer.address()
+        );
+
+        // 词都在词表里，但 checksum 不对
+        let bad_checksum = "abandon ".repeat(12);
+        assert!(LocalSigner::from_mnemonic(&bad_checksum, "", 0).is_err());
+        // 拼写错误（不在词表里）
+        assert!(LocalSigner::from_mnemonic(&ABANDON.replace("about", "abuot"), "", 0).is_err());
+    }
 }
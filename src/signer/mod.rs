@@ -1,12 +1,20 @@
+mod any;
 mod local;
+mod policy;
 mod remote;
 
+pub use any::AnySigner;
 pub use local::LocalSigner;
-pub use remote::RemoteSigner;
+pub use policy::{PolicySigner, PolicyViolation, SpendLimit, TokenSpend, TxPolicy};
+pub use remote::{RemoteSigner, RemoteSignerOptions};
 
 use std::future::Future;
 
-use alloy::{consensus::TxEip1559, primitives::Address};
+use alloy::{
+    consensus::TxEip1559,
+    dyn_abi::TypedData,
+    primitives::{eip191_hash_message, Address, Signature, B256},
+};
 use eyre::Result;
 
 use crate::RawTx;
@@ -17,4 +25,32 @@ use crate::RawTx;
 pub trait TxSigner: Send + Sync {
     fn address(&self) -> Address;
     fn sign(&self, tx: TxEip1559) -> impl Future<Output = Result<RawTx>> + Send;
+
+    /// 对 32 字节 prehash 直接签名（不加任何前缀）。
+    ///
+    /// 用于 ERC-4337 userOpHash / Safe `SafeOp` 等由调用方自行算好 digest 的场景。
+    /// 默认实现返回错误 —— 远程签名服务通常不开放裸 hash 签名。
+    fn sign_hash(&self, hash: B256) -> impl Future<Output = Result<Signature>> + Send {
+        let _ = hash;
+        async { Err(eyre::eyre!("this signer does not support raw hash signing")) }
+    }
+
+    /// EIP-191 personal_sign（`"\x19Ethereum Signed Message:\n" + len + message`）。
+    ///
+    /// 默认实现：本地算 digest 后走 [`sign_hash`](Self::sign_hash)。
+    fn sign_message(&self, message: &[u8]) -> impl Future<Output = Result<Signature>> + Send {
+        let hash = eip191_hash_message(message);
+        self.sign_hash(hash)
+    }
+
+    /// EIP-712 typed data 签名（Permit / Permit2 / Safe tx / CoW order 等）。
+    ///
+    /// 默认实现：本地算 `eip712_signing_hash` 后走 [`sign_hash`](Self::sign_hash)。
+    fn sign_typed_data(
+        &self,
+        data: &TypedData,
+    ) -> impl Future<Output = Result<Signature>> + Send {
+        let hash = data.eip712_signing_hash();
+        async move { self.sign_hash(hash?).await }
+    }
 }
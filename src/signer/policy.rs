@@ -0,0 +1,747 @@
+//! 签名前的本地交易策略，与 cs-signer 的 rule.js 对应：在签名服务看到交易之前
+//! 就拦下不该签的交易。
+//!
+//! 策略可以写在 JSON 里（[`crate::app::load_json`]）：
+//!
+//! ```json
+//! {
+//!   "allowed_targets": ["0x<COBOSAFE>", "0x<PROTOCOL>", "0x<USDC>"],
+//!   "selectors": { "0x<PROTOCOL>": ["0xb6b55f25"], "0x<USDC>": ["0x095ea7b3"] },
+//!   "cobosafe": ["0x<COBOSAFE>"],
+//!   "max_value_wei": "0",
+//!   "max_fee_per_gas": 200000000000,
+//!   "spend_limits": [{ "token": "0x<USDC>", "max_amount": "1000000000", "period_secs": 86400 }],
+//!   "typed_data": ["Permit"],
+//!   "kill_switch": "/var/run/bot/STOP"
+//! }
+//! ```
+//!
+//! typed data 只签 `typed_data` 里列出的 `primaryType`，domain 的 `verifyingContract`
+//! 同样受 `allowed_targets` 约束；EIP-2612 `Permit` 的金额计入该 token 的限额。
+//! personal_sign 消息看不出内容，须 `allow_messages` 显式打开；裸 hash 一律拒绝。
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    consensus::TxEip1559,
+    dyn_abi::TypedData,
+    primitives::{Address, Bytes, Selector, Signature, TxKind, B256, U256},
+    sol_types::SolCall,
+};
+use eyre::Result;
+use serde::Deserialize;
+
+use super::TxSigner;
+use crate::{
+    builder::decode_inner_calls,
+    utils::erc20::{approveCall, transferCall},
+    RawTx,
+};
+
+/// 声明式交易策略。所有字段都可省略，省略即不限制。
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TxPolicy {
+    /// 允许调用的地址（外层 `to` 和 CoboSafe inner call 的 `to`）。为空不限制。
+    #[serde(default)]
+    pub allowed_targets: Vec<Address>,
+    /// 每个目标允许的 selector。目标不在表里时不限制 selector。
+    #[serde(default)]
+    pub selectors: HashMap<Address, Vec<Selector>>,
+    /// CoboSafe 地址：外层打到这些地址时，按 inner call 逐个检查。
+    #[serde(default)]
+    pub cobosafe: Vec<Address>,
+    /// 单个 call（外层或 inner）携带的 ETH 上限。
+    #[serde(default)]
+    pub max_value_wei: Option<U256>,
+    #[serde(default)]
+    pub max_fee_per_gas: Option<u128>,
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// token 支出限额，按 `transfer` / `approve` 的金额计。
+    #[serde(default)]
+    pub spend_limits: Vec<SpendLimit>,
+    /// 允许签名的 typed data `primaryType`（如 `Permit`、`SafeOp`）。为空则拒绝所有 typed data。
+    #[serde(default)]
+    pub typed_data: Vec<String>,
+    /// 是否允许 personal_sign 消息。
+    #[serde(default)]
+    pub allow_messages: bool,
+    /// 该文件存在时拒绝一切签名。
+    #[serde(default)]
+    pub kill_switch: Option<PathBuf>,
+}
+
+/// `period_secs` 滚动窗口内，`token` 的 `transfer` + `approve` 金额之和不超过 `max_amount`。
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpendLimit {
+    pub token: Address,
+    pub max_amount: U256,
+    pub period_secs: u64,
+}
+
+/// 交易里解出的一笔 token 支出。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenSpend {
+    pub token: Address,
+    pub amount: U256,
+}
+
+/// 策略拒签的原因。[`PolicySigner`] 以 `eyre::Report` 返回，可 `downcast_ref` 取回。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyViolation {
+    KillSwitch {
+        path: PathBuf,
+    },
+    ContractCreation,
+    TargetNotAllowed {
+        to: Address,
+    },
+    SelectorNotAllowed {
+        to: Address,
+        selector: Option<Selector>,
+    },
+    /// 外层打到 CoboSafe 但 calldata 不是 `execTransaction(s)`。
+    UndecodableCoboSafeCall {
+        to: Address,
+    },
+    ValueTooHigh {
+        to: Address,
+        value: U256,
+        max: U256,
+    },
+    FeeTooHigh {
+        field: &'static str,
+        fee: u128,
+        max: u128,
+    },
+    SpendLimitExceeded {
+        token: Address,
+        amount: U256,
+        spent: U256,
+        limit: U256,
+        period_secs: u64,
+    },
+    /// 裸 hash 看不出签的是什么，[`PolicySigner`] 一律拒绝。
+    RawHash,
+    /// personal_sign 消息，策略没有 `allow_messages`。
+    MessageNotAllowed,
+    /// `primaryType` 不在策略的 `typed_data` 里。
+    TypedDataNotAllowed {
+        primary_type: String,
+    },
+    /// 配了 `allowed_targets`，typed data 的 domain 却没有 `verifyingContract`。
+    MissingVerifyingContract {
+        primary_type: String,
+    },
+}
+
+impl std::fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KillSwitch { path } => {
+                write!(f, "kill switch {} is present", path.display())
+            }
+            Self::ContractCreation => write!(f, "contract creation is not allowed"),
+            Self::TargetNotAllowed { to } => write!(f, "target {to} is not allowed"),
+            Self::SelectorNotAllowed {
+                to,
+                selector: Some(sel),
+            } => {
+                write!(f, "selector {sel} is not allowed on {to}")
+            }
+            Self::SelectorNotAllowed { to, selector: None } => {
+                write!(f, "call to {to} without selector is not allowed")
+            }
+            Self::UndecodableCoboSafeCall { to } => {
+                write!(f, "call to CoboSafe {to} is not execTransaction(s)")
+            }
+            Self::ValueTooHigh { to, value, max } => {
+                write!(f, "value {value} to {to} exceeds max {max}")
+            }
+            Self::FeeTooHigh { field, fee, max } => write!(f, "{field} {fee} exceeds max {max}"),
+            Self::SpendLimitExceeded {
+                token,
+                amount,
+                spent,
+                limit,
+                period_secs,
+            } => write!(
+                f,
+                "spending {amount} of {token} exceeds limit {limit} per {period_secs}s \
+                 ({spent} already spent)"
+            ),
+            Self::RawHash => write!(f, "raw hash signing is not allowed under a policy"),
+            Self::MessageNotAllowed => write!(f, "message signing is not allowed by the policy"),
+            Self::TypedDataNotAllowed { primary_type } => {
+                write!(f, "typed data {primary_type} is not allowed")
+            }
+            Self::MissingVerifyingContract { primary_type } => {
+                write!(f, "typed data {primary_type} has no verifyingContract")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolicyViolation {}
+
+impl TxPolicy {
+    /// 不含限额的检查（限额需要历史，见 [`PolicySigner`]），返回交易里的 token 支出。
+    pub fn check(&self, tx: &TxEip1559) -> Result<Vec<TokenSpend>, PolicyViolation> {
+        self.check_kill_switch()?;
+        if let Some(max) = self.max_fee_per_gas
+            && tx.max_fee_per_gas > max
+        {
+            return Err(PolicyViolation::FeeTooHigh {
+                field: "max_fee_per_gas",
+                fee: tx.max_fee_per_gas,
+                max,
+            });
+        }
+        if let Some(max) = self.max_priority_fee_per_gas
+            && tx.max_priority_fee_per_gas > max
+        {
+            return Err(PolicyViolation::FeeTooHigh {
+                field: "max_priority_fee_per_gas",
+                fee: tx.max_priority_fee_per_gas,
+                max,
+            });
+        }
+
+        let TxKind::Call(to) = tx.to else {
+            return Err(PolicyViolation::ContractCreation);
+        };
+        let calls = if self.cobosafe.contains(&to) {
+            self.check_value(to, tx.value)?;
+            decode_inner_calls(&tx.input).ok_or(PolicyViolation::UndecodableCoboSafeCall { to })?
+        } else {
+            vec![(to, tx.value, tx.input.clone())]
+        };
+
+        let mut spends = Vec::new();
+        for (to, value, data) in calls {
+            spends.extend(self.check_call(to, value, &data)?);
+        }
+        Ok(spends)
+    }
+
+    /// typed data 的检查（不含限额）：`primaryType` 须在 `typed_data` 里，`verifyingContract`
+    /// 须在 `allowed_targets` 里。`Permit` 的金额记为对 `verifyingContract` 的支出。
+    pub fn check_typed_data(&self, data: &TypedData) -> Result<Vec<TokenSpend>, PolicyViolation> {
+        self.check_kill_switch()?;
+        let primary_type = &data.primary_type;
+        if !self.typed_data.contains(primary_type) {
+            return Err(PolicyViolation::TypedDataNotAllowed {
+                primary_type: primary_type.clone(),
+            });
+        }
+        let verifying_contract = data.domain.verifying_contract;
+        if !self.allowed_targets.is_empty() {
+            let to =
+                verifying_contract.ok_or_else(|| PolicyViolation::MissingVerifyingContract {
+                    primary_type: primary_type.clone(),
+                })?;
+            if !self.allowed_targets.contains(&to) {
+                return Err(PolicyViolation::TargetNotAllowed { to });
+            }
+        }
+        match verifying_contract {
+            Some(token) if primary_type == "Permit" => Ok(vec![TokenSpend {
+                token,
+                amount: permit_amount(&data.message),
+            }]),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn check_message(&self) -> Result<(), PolicyViolation> {
+        self.check_kill_switch()?;
+        match self.allow_messages {
+            true => Ok(()),
+            false => Err(PolicyViolation::MessageNotAllowed),
+        }
+    }
+
+    fn check_kill_switch(&self) -> Result<(), PolicyViolation> {
+        match &self.kill_switch {
+            Some(path) if path.exists() => Err(PolicyViolation::KillSwitch { path: path.clone() }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_value(&self, to: Address, value: U256) -> Result<(), PolicyViolation> {
+        match self.max_value_wei {
+            Some(max) if value > max => Err(PolicyViolation::ValueTooHigh { to, value, max }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_call(
+        &self,
+        to: Address,
+        value: U256,
+        data: &Bytes,
+    ) -> Result<Option<TokenSpend>, PolicyViolation> {
+        if !self.allowed_targets.is_empty() && !self.allowed_targets.contains(&to) {
+            return Err(PolicyViolation::TargetNotAllowed { to });
+        }
+        self.check_value(to, value)?;
+        let selector = data.get(..4).map(Selector::from_slice);
+        if let Some(allowed) = self.selectors.get(&to)
+            && !selector.is_some_and(|s| allowed.contains(&s))
+        {
+            return Err(PolicyViolation::SelectorNotAllowed { to, selector });
+        }
+
+        let amount = if let Ok(call) = transferCall::abi_decode(data) {
+            call.amount
+        } else if let Ok(call) = approveCall::abi_decode(data) {
+            call.amount
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(TokenSpend { token: to, amount }))
+    }
+}
+
+/// EIP-2612 `Permit.value`；DAI 式 `Permit`（`allowed: true`）是无限授权。
+/// 解析不了时按无限算，有限额的 token 会因此被拒。
+fn permit_amount(message: &serde_json::Value) -> U256 {
+    match (message.get("value"), message.get("allowed")) {
+        (Some(serde_json::Value::String(v)), _) => v.parse().unwrap_or(U256::MAX),
+        (Some(serde_json::Value::Number(v)), _) => v.as_u64().map_or(U256::MAX, U256::from),
+        (None, Some(serde_json::Value::Bool(false))) => U256::ZERO,
+        _ => U256::MAX,
+    }
+}
+
+/// 带策略检查的签名器：[`TxPolicy::check`] + 限额通过后才交给 `inner` 签名。
+///
+/// 限额按本进程签过的交易累计，不读链上历史；重启后清零。签名前先在账本里占额度
+/// （检查与占用在同一把锁下，并发签名不会一起超限），签名失败再退回。
+/// typed data 见 [`TxPolicy::check_typed_data`]，`Permit` 同样占额度；消息须策略
+/// 允许，裸 hash 签名直接拒绝。
+pub struct PolicySigner<S> {
+    inner: S,
+    policy: TxPolicy,
+    /// (token, 签名时间戳, 金额)
+    ledger: Mutex<Vec<LedgerEntry>>,
+}
+
+type LedgerEntry = (Address, u64, U256);
+
+impl<S: TxSigner> PolicySigner<S> {
+    pub fn new(inner: S, policy: TxPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            ledger: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn policy(&self) -> &TxPolicy {
+        &self.policy
+    }
+
+    /// 完整检查（含限额），不签名。
+    pub fn check(&self, tx: &TxEip1559) -> Result<Vec<TokenSpend>, PolicyViolation> {
+        let spends = self.policy.check(tx)?;
+        let ledger = self.ledger.lock().expect("policy ledger poisoned");
+        self.check_limits(&ledger, &spends, now())?;
+        Ok(spends)
+    }
+
+    /// 检查限额并把 `spends` 记进账本，返回记下的条目（签名失败时交给 [`Self::release`]）。
+    fn reserve(
+        &self,
+        spends: &[TokenSpend],
+        now: u64,
+    ) -> Result<Vec<LedgerEntry>, PolicyViolation> {
+        let mut ledger = self.ledger.lock().expect("policy ledger poisoned");
+        self.check_limits(&ledger, spends, now)?;
+        let entries: Vec<LedgerEntry> = spends.iter().map(|s| (s.token, now, s.amount)).collect();
+        ledger.extend_from_slice(&entries);
+        Ok(entries)
+    }
+
+    /// 占额度后等 `sign`，失败时退回。
+    async fn sign_reserved<T>(
+        &self,
+        spends: &[TokenSpend],
+        sign: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let reserved = self.reserve(spends, now())?;
+        let signed = sign.await;
+        if signed.is_err() {
+            self.release(&reserved);
+        }
+        signed
+    }
+
+    fn release(&self, entries: &[LedgerEntry]) {
+        let mut ledger = self.ledger.lock().expect("policy ledger poisoned");
+        for entry in entries {
+            if let Some(i) = ledger.iter().rposition(|e| e == entry) {
+                ledger.remove(i);
+            }
+        }
+    }
+
+    fn check_limits(
+        &self,
+        ledger: &[LedgerEntry],
+        spends: &[TokenSpend],
+        now: u64,
+    ) -> Result<(), PolicyViolation> {
+        for limit in &self.policy.spend_limits {
+            let since = now.saturating_sub(limit.period_secs);
+            let spent: U256 = ledger
+                .iter()
+                .filter(|(token, ts, _)| *token == limit.token && *ts > since)
+                .map(|(_, _, amount)| *amount)
+                .fold(U256::ZERO, |a, b| a.saturating_add(b));
+            let amount: U256 = spends
+                .iter()
+                .filter(|s| s.token == limit.token)
+                .map(|s| s.amount)
+                .fold(U256::ZERO, |a, b| a.saturating_add(b));
+            if amount > U256::ZERO && spent.saturating_add(amount) > limit.max_amount {
+                return Err(PolicyViolation::SpendLimitExceeded {
+                    token: limit.token,
+                    amount,
+                    spent,
+                    limit: limit.max_amount,
+                    period_secs: limit.period_secs,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_secs()
+}
+
+impl<S: TxSigner> TxSigner for PolicySigner<S> {
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    async fn sign(&self, tx: TxEip1559) -> Result<RawTx> {
+        let spends = self.policy.check(&tx)?;
+        self.sign_reserved(&spends, self.inner.sign(tx)).await
+    }
+
+    async fn sign_hash(&self, _hash: B256) -> Result<Signature> {
+        Err(PolicyViolation::RawHash.into())
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        self.policy.check_message()?;
+        self.inner.sign_message(message).await
+    }
+
+    async fn sign_typed_data(&self, data: &TypedData) -> Result<Signature> {
+        let spends = self.policy.check_typed_data(data)?;
+        self.sign_reserved(&spends, self.inner.sign_typed_data(data))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utils::testing::testing_delegate, CoboSafeBuilder, LocalSigner, TxBuilder, TxRequest,
+    };
+    use alloy::primitives::address;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const COBOSAFE: Address = address!("c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0");
+    const USDC: Address = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+    const PROTOCOL: Address = address!("1111111111111111111111111111111111111111");
+
+    fn policy() -> TxPolicy {
+        serde_json::from_value(serde_json::json!({
+            "allowed_targets": [COBOSAFE, USDC, PROTOCOL],
+            "selectors": { USDC.to_string(): ["0x095ea7b3", "0xa9059cbb"] },
+            "cobosafe": [COBOSAFE],
+            "max_value_wei": "0",
+            "max_fee_per_gas": 100,
+            "spend_limits": [{ "token": USDC, "max_amount": "1000", "period_secs": 3600 }],
+        }))
+        .unwrap()
+    }
+
+    fn via_cobosafe(requests: &[TxRequest]) -> TxEip1559 {
+        CoboSafeBuilder::new(COBOSAFE, 1)
+            .build_txs(requests, 0, 50, 1)
+            .unwrap()
+            .remove(0)
+    }
+
+    fn approve(amount: u64) -> TxRequest {
+        TxRequest {
+            to: USDC,
+            value: U256::ZERO,
+            data: approveCall {
+                spender: PROTOCOL,
+                amount: U256::from(amount),
+            }
+            .abi_encode()
+            .into(),
+            gas_limit: 60_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn checks_inner_calls_and_spend_limits() {
+        let signer = PolicySigner::new(testing_delegate().0, policy());
+
+        let deposit = TxRequest {
+            to: PROTOCOL,
+            value: U256::ZERO,
+            data: Bytes::from(vec![0xb6, 0xb5, 0x5f, 0x25]),
+            gas_limit: 100_000,
+        };
+        signer
+            .sign(via_cobosafe(&[approve(600), deposit.clone()]))
+            .await
+            .unwrap();
+
+        // 限额：1 小时内已 approve 600，再 approve 600 超限
+        let err = signer
+            .sign(via_cobosafe(&[approve(600)]))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::SpendLimitExceeded {
+                token: USDC,
+                amount: U256::from(600),
+                spent: U256::from(600),
+                limit: U256::from(1000),
+                period_secs: 3600,
+            })
+        );
+        signer.sign(via_cobosafe(&[approve(400)])).await.unwrap();
+
+        let check = |tx: &TxEip1559| signer.check(tx).unwrap_err();
+        let stranger = address!("2222222222222222222222222222222222222222");
+        assert_eq!(
+            check(&via_cobosafe(&[TxRequest {
+                to: stranger,
+                ..deposit.clone()
+            }])),
+            PolicyViolation::TargetNotAllowed { to: stranger }
+        );
+        let balance_of = TxRequest {
+            data: Bytes::from(vec![0x70, 0xa0, 0x82, 0x31]),
+            ..approve(0)
+        };
+        assert!(matches!(
+            check(&via_cobosafe(&[balance_of])),
+            PolicyViolation::SelectorNotAllowed { to: USDC, .. }
+        ));
+        assert!(matches!(
+            check(&via_cobosafe(&[TxRequest {
+                value: U256::from(1),
+                ..deposit.clone()
+            }])),
+            PolicyViolation::ValueTooHigh { to: PROTOCOL, .. }
+        ));
+        assert!(matches!(
+            check(&TxEip1559 {
+                max_fee_per_gas: 101,
+                ..via_cobosafe(std::slice::from_ref(&deposit))
+            }),
+            PolicyViolation::FeeTooHigh {
+                field: "max_fee_per_gas",
+                ..
+            }
+        ));
+        assert_eq!(
+            check(&TxEip1559 {
+                input: Bytes::new(),
+                ..via_cobosafe(&[deposit])
+            }),
+            PolicyViolation::UndecodableCoboSafeCall { to: COBOSAFE }
+        );
+    }
+
+    /// 签名前先睡一会儿（模拟远程签名服务），`fail` 打开时签名失败。
+    struct SlowSigner {
+        inner: LocalSigner,
+        fail: AtomicBool,
+    }
+
+    impl TxSigner for SlowSigner {
+        fn address(&self) -> Address {
+            self.inner.address()
+        }
+
+        async fn sign(&self, tx: TxEip1559) -> Result<RawTx> {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            eyre::ensure!(
+                !self.fail.load(Ordering::SeqCst),
+                "signing service unavailable"
+            );
+            self.inner.sign(tx).await
+        }
+    }
+
+    #[tokio::test]
+    async fn reserves_spend_before_signing() {
+        let signer = PolicySigner::new(
+            SlowSigner {
+                inner: testing_delegate().0,
+                fail: AtomicBool::new(false),
+            },
+            policy(),
+        );
+
+        // 两笔并发的 approve(600)：只能有一笔拿到额度
+        let (a, b) = tokio::join!(
+            signer.sign(via_cobosafe(&[approve(600)])),
+            signer.sign(via_cobosafe(&[approve(600)])),
+        );
+        let errs: Vec<_> = [a, b].into_iter().filter_map(|r| r.err()).collect();
+        assert_eq!(errs.len(), 1);
+        assert!(matches!(
+            errs[0].downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::SpendLimitExceeded { .. })
+        ));
+
+        // 签名失败退回额度
+        signer.inner.fail.store(true, Ordering::SeqCst);
+        let err = signer
+            .sign(via_cobosafe(&[approve(400)]))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("unavailable"), "{err}");
+        signer.inner.fail.store(false, Ordering::SeqCst);
+        signer.sign(via_cobosafe(&[approve(400)])).await.unwrap();
+
+        let err = signer.sign_hash(B256::ZERO).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::RawHash)
+        );
+    }
+
+    fn typed_data(primary_type: &str, verifying_contract: Address, value: &str) -> TypedData {
+        serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" },
+                ],
+                primary_type: [
+                    { "name": "owner", "type": "address" },
+                    { "name": "spender", "type": "address" },
+                    { "name": "value", "type": "uint256" },
+                    { "name": "nonce", "type": "uint256" },
+                    { "name": "deadline", "type": "uint256" },
+                ],
+            },
+            "primaryType": primary_type,
+            "domain": { "name": "USD Coin", "chainId": 1, "verifyingContract": verifying_contract },
+            "message": {
+                "owner": testing_delegate().1,
+                "spender": PROTOCOL,
+                "value": value,
+                "nonce": 0,
+                "deadline": 1_900_000_000u64,
+            },
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn checks_typed_data_and_messages() {
+        let signer = PolicySigner::new(
+            testing_delegate().0,
+            TxPolicy {
+                typed_data: vec!["Permit".into()],
+                ..policy()
+            },
+        );
+        let violation = |err: eyre::Report| err.downcast::<PolicyViolation>().unwrap();
+
+        // Permit 计入 USDC 限额
+        signer
+            .sign_typed_data(&typed_data("Permit", USDC, "800"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            violation(signer.sign(via_cobosafe(&[approve(600)])).await.err().unwrap()),
+            PolicyViolation::SpendLimitExceeded { spent, .. } if spent == U256::from(800)
+        ));
+        assert!(matches!(
+            violation(
+                signer
+                    .sign_typed_data(&typed_data("Permit", USDC, "0x100"))
+                    .await
+                    .unwrap_err()
+            ),
+            PolicyViolation::SpendLimitExceeded { amount, .. } if amount == U256::from(0x100)
+        ));
+
+        let stranger = address!("2222222222222222222222222222222222222222");
+        assert_eq!(
+            violation(
+                signer
+                    .sign_typed_data(&typed_data("Permit", stranger, "1"))
+                    .await
+                    .unwrap_err()
+            ),
+            PolicyViolation::TargetNotAllowed { to: stranger }
+        );
+        assert_eq!(
+            violation(
+                signer
+                    .sign_typed_data(&typed_data("SafeOp", PROTOCOL, "0"))
+                    .await
+                    .unwrap_err()
+            ),
+            PolicyViolation::TypedDataNotAllowed {
+                primary_type: "SafeOp".into()
+            }
+        );
+        assert_eq!(
+            violation(signer.sign_message(b"hello").await.unwrap_err()),
+            PolicyViolation::MessageNotAllowed
+        );
+    }
+
+    #[tokio::test]
+    async fn kill_switch_blocks_all_signing() {
+        let path = std::env::temp_dir().join(format!("flashseal-kill-{}", std::process::id()));
+        let policy = TxPolicy {
+            kill_switch: Some(path.clone()),
+            allow_messages: true,
+            ..Default::default()
+        };
+        let signer = PolicySigner::new(testing_delegate().0, policy);
+
+        signer.sign_message(b"ok").await.unwrap();
+        std::fs::write(&path, b"").unwrap();
+        let err = signer.sign_message(b"stop").await.unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::KillSwitch { .. })
+        ));
+    }
+}
@@ -0,0 +1,511 @@
+mod transport;
+
+pub use transport::RemoteSignerOptions;
+
+use std::sync::Arc;
+
+use alloy::{
+    consensus::{transaction::SignerRecoverable, TxEip1559, TxEnvelope, TxLegacy},
+    dyn_abi::TypedData,
+    eips::Decodable2718,
+    primitives::{eip191_hash_message, Address, Signature, TxKind, B256},
+};
+use eyre::Result;
+
+use super::TxSigner;
+use crate::{
+    utils::signer_json::{message_content, typed_data_content},
+    RawTx,
+};
+use transport::Transport;
+
+/// cs-signer 的 EIP-191 personal_sign 端点。
+const SIGN_MESSAGE_PATH: &str = "/v1/sign/message";
+
+/// cs-signer 的 EIP-712 typed data 签名端点。
+const SIGN_TYPED_DATA_PATH: &str = "/v1/sign/typed_data";
+
+/// 远程签名器：通过 HTTP 调用签名服务获取 address 和签名
+///
+/// 认证方式：Ed25519 签名 SHA256(timestamp + data)
+///
+/// 支持交易（`/v1/sign/transaction`）、EIP-191 消息和 EIP-712 typed data 签名；
+/// 不支持裸 hash 签名（[`TxSigner::sign_hash`] 返回错误），避免绕过 rule.js 审核。
+///
+/// 不信任签名服务的返回：签好的交易会被解码，逐字段与请求比对并校验签名者是
+/// `self.account`，任何不一致都直接报错，不会把被篡改的交易交给 sender。
+///
+/// 超时 / 重试 / 时间同步 / TLS 见 [`RemoteSignerOptions`]。clone 出来的句柄和
+/// [`account`](Self::account) 派生的其它账户共享同一个连接池和地址缓存。
+#[derive(Clone)]
+pub struct RemoteSigner {
+    transport: Arc<Transport>,
+    account: Address,
+    account_index: i64,
+}
+
+impl RemoteSigner {
+    /// 用默认 [`RemoteSignerOptions`] 连接。
+    pub async fn new(
+        base_url: String,
+        project: String,
+        ed25519_seed: [u8; 32],
+        account_index: i64,
+    ) -> Result<Self> {
+        Self::with_options(
+            base_url,
+            project,
+            ed25519_seed,
+            account_index,
+            RemoteSignerOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn with_options(
+        base_url: String,
+        project: String,
+        ed25519_seed: [u8; 32],
+        account_index: i64,
+        options: RemoteSignerOptions,
+    ) -> Result<Self> {
+        let transport = Arc::new(Transport::new(base_url, project, ed25519_seed, &options)?);
+        let account = transport.address(account_index).await?;
+        Ok(Self {
+            transport,
+            account,
+            account_index,
+        })
+    }
+
+    pub fn account_index(&self) -> i64 {
+        self.account_index
+    }
+
+    /// 同一签名服务上第 `index` 个账户的句柄（地址查过一次后缓存）。
+    pub async fn account(&self, index: i64) -> Result<Self> {
+        Ok(Self {
+            transport: self.transport.clone(),
+            account: self.transport.address(index).await?,
+            account_index: index,
+        })
+    }
+
+    /// 批量取多个账户的句柄，每个 operator 一个，按 `indices` 顺序返回。
+    pub async fn accounts(&self, indices: &[i64]) -> Result<Vec<Self>> {
+        let mut out = Vec::with_capacity(indices.len());
+        for &index in indices {
+            out.push(self.account(index).await?);
+        }
+        Ok(out)
+    }
+
+    async fn post<T: serde::de::DeserializeOwned>(&self, path: &str, data: &str) -> Result<T> {
+        self.transport.post(path, data).await
+    }
+
+    /// 签名 legacy (type 0x0) 交易，使用 `gasPrice` 字段替代 maxFee/priority。
+    pub async fn sign_legacy(&self, tx: TxLegacy) -> Result<RawTx> {
+        let to = match tx.to {
+            TxKind::Call(addr) => addr.to_string(),
+            TxKind::Create => {
+                return Err(eyre::eyre!("RemoteSigner does not support contract creation"))
+            }
+        };
+        let chain_id = tx.chain_id.unwrap_or(0);
+        let go_tx = serde_json::json!({
+            "chainId": format!("0x{:x}", chain_id),
+            "type": "0x0",
+            "nonce": format!("0x{:x}", tx.nonce),
+            "to": to,
+            "value": format!("0x{:x}", tx.value),
+            "gas": format!("0x{:x}", tx.gas_limit),
+            "gasPrice": format!("0x{:x}", tx.gas_price),
+            "input": format!("0x{}", alloy::hex::encode(&tx.input)),
+            "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        });
+        let data = serde_json::json!({
+            "chain_id": chain_id,
+            "account": self.account.to_string(),
+            "transaction": go_tx.to_string(),
+        })
+        .to_string();
+
+        #[derive(serde::Deserialize)]
+        struct Resp {
+            tx_hex: String,
+        }
+
+        let resp: Resp = self.post("/v1/sign/transaction", &data).await?;
+        let raw = RawTx::try_from(resp.tx_hex.as_str())?;
+        let TxEnvelope::Legacy(signed) = self.decode_signed(&raw)? else {
+            return Err(eyre::eyre!("signer service returned a non-legacy tx"));
+        };
+        let got = signed.tx();
+        ensure_field("chain id", &tx.chain_id, &got.chain_id)?;
+        ensure_field("nonce", &tx.nonce, &got.nonce)?;
+        ensure_field("to", &tx.to, &got.to)?;
+        ensure_field("value", &tx.value, &got.value)?;
+        ensure_field("gas limit", &tx.gas_limit, &got.gas_limit)?;
+        ensure_field("gas price", &tx.gas_price, &got.gas_price)?;
+        ensure_field("input", &tx.input, &got.input)?;
+        Ok(raw)
+    }
+
+    /// 解码签名服务返回的交易，并校验签名者是 `self.account`。
+    fn decode_signed(&self, raw: &RawTx) -> Result<TxEnvelope> {
+        let envelope = TxEnvelope::decode_2718(&mut raw.0.as_ref())
+            .map_err(|e| eyre::eyre!("signer service returned an undecodable tx: {e}"))?;
+        let recovered = envelope.recover_signer()?;
+        eyre::ensure!(
+            recovered == self.account,
+            "signer service returned tx signed by {recovered}, expected {}",
+            self.account
+        );
+        Ok(envelope)
+    }
+
+    /// 调消息签名端点拿 65 字节签名，并校验 recover 出来的地址就是 `self.account`。
+    async fn post_signature(&self, path: &str, data: &str, digest: B256) -> Result<Signature> {
+        #[derive(serde::Deserialize)]
+        struct Resp {
+            signature: String,
+        }
+
+        let resp: Resp = self.post(path, data).await?;
+        let hex = resp.signature.strip_prefix("0x").unwrap_or(&resp.signature);
+        let sig = Signature::try_from(alloy::hex::decode(hex)?.as_slice())
+            .map_err(|e| eyre::eyre!("signer returned malformed signature: {e}"))?;
+        let recovered = sig.recover_address_from_prehash(&digest)?;
+        eyre::ensure!(
+            recovered == self.account,
+            "signer returned signature from {recovered}, expected {}",
+            self.account
+        );
+        Ok(sig)
+    }
+}
+
+fn ensure_field<T: PartialEq + std::fmt::Debug>(field: &str, requested: &T, got: &T) -> Result<()> {
+    eyre::ensure!(
+        requested == got,
+        "signer service returned tx with different {field}: requested {requested:?}, got {got:?}"
+    );
+    Ok(())
+}
+
+impl TxSigner for RemoteSigner {
+    fn address(&self) -> Address {
+        self.account
+    }
+
+    async fn sign(&self, tx: TxEip1559) -> Result<RawTx> {
+        let to = match tx.to {
+            TxKind::Call(addr) => addr.to_string(),
+            TxKind::Create => {
+                return Err(eyre::eyre!("RemoteSigner does not support contract creation"))
+            }
+        };
+
+        // 转换为签名服务期望的 Go 风格交易 JSON（所有数值为 hex 字符串）
+        let go_tx = serde_json::json!({
+            "chainId": format!("0x{:x}", tx.chain_id),
+            "type": "0x2",
+            "nonce": format!("0x{:x}", tx.nonce),
+            "to": to,
+            "value": format!("0x{:x}", tx.value),
+            "gas": format!("0x{:x}", tx.gas_limit),
+            "maxPriorityFeePerGas": format!("0x{:x}", tx.max_priority_fee_per_gas),
+            "maxFeePerGas": format!("0x{:x}", tx.max_fee_per_gas),
+            "input": format!("0x{}", alloy::hex::encode(&tx.input)),
+            // Go 端要求的占位哈希
+            "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "accessList": tx.access_list,
+        });
+
+        let data = serde_json::json!({
+            "chain_id": tx.chain_id,
+            "account": self.account.to_string(),
+            "transaction": go_tx.to_string(),
+        })
+        .to_string();
+
+        #[derive(serde::Deserialize)]
+        struct Resp {
+            tx_hex: String,
+        }
+
+        let resp: Resp = self.post("/v1/sign/transaction", &data).await?;
+        let raw = RawTx::try_from(resp.tx_hex.as_str())?;
+        let TxEnvelope::Eip1559(signed) = self.decode_signed(&raw)? else {
+            return Err(eyre::eyre!("signer service returned a non-EIP-1559 tx"));
+        };
+        let got = signed.tx();
+        ensure_field("chain id", &tx.chain_id, &got.chain_id)?;
+        ensure_field("nonce", &tx.nonce, &got.nonce)?;
+        ensure_field("to", &tx.to, &got.to)?;
+        ensure_field("value", &tx.value, &got.value)?;
+        ensure_field("gas limit", &tx.gas_limit, &got.gas_limit)?;
+        ensure_field("max fee", &tx.max_fee_per_gas, &got.max_fee_per_gas)?;
+        ensure_field(
+            "priority fee",
+            &tx.max_priority_fee_per_gas,
+            &got.max_priority_fee_per_gas,
+        )?;
+        ensure_field("input", &tx.input, &got.input)?;
+        ensure_field("access list", &tx.access_list, &got.access_list)?;
+        Ok(raw)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        let data = message_content(message, self.account).to_string();
+        self.post_signature(SIGN_MESSAGE_PATH, &data, eip191_hash_message(message))
+            .await
+    }
+
+    async fn sign_typed_data(&self, typed: &TypedData) -> Result<Signature> {
+        let digest = typed.eip712_signing_hash()?;
+        let data = typed_data_content(typed, self.account)?.to_string();
+        self.post_signature(SIGN_TYPED_DATA_PATH, &data, digest).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utils::testing::{
+            serve_http, testing_delegate, HttpResponse, MockCsSigner, TESTING_DELEGATE_ADDRESS,
+            TESTING_DELEGATE_PRIVKEY, TESTING_SIGNER_AUTH_SEED,
+        },
+        LocalSigner,
+    };
+    use alloy::{
+        eips::eip2930::{AccessList, AccessListItem},
+        primitives::{address, U256},
+        signers::local::PrivateKeySigner,
+    };
+    use serde_json::json;
+
+    fn tx() -> TxEip1559 {
+        TxEip1559 {
+            chain_id: 1,
+            nonce: 3,
+            gas_limit: 50_000,
+            max_fee_per_gas: 30,
+            max_priority_fee_per_gas: 2,
+            to: TxKind::Call(address!("1111111111111111111111111111111111111111")),
+            value: U256::from(5),
+            access_list: AccessList(vec![AccessListItem {
+                address: address!("2222222222222222222222222222222222222222"),
+                storage_keys: vec![B256::repeat_byte(0x01)],
+            }]),
+            ..Default::default()
+        }
+    }
+
+    /// 地址返回 delegate，签名端点返回 `tampered` 签好的交易。
+    async fn dishonest_signer(tampered: RawTx) -> crate::utils::testing::StubServer {
+        let tx_hex = format!("0x{}", alloy::hex::encode(&tampered.0));
+        serve_http(move |req| match req.path.as_str() {
+            "/v1/address" => {
+                HttpResponse::json(200, &json!({ "data": TESTING_DELEGATE_ADDRESS.to_string() }))
+            }
+            _ => HttpResponse::json(200, &json!({ "tx_hex": tx_hex })),
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn accepts_matching_tx_with_access_list() {
+        let cs = MockCsSigner::start("test").await.unwrap();
+        let signer = RemoteSigner::new(cs.url().into(), "test".into(), TESTING_SIGNER_AUTH_SEED, 0)
+            .await
+            .unwrap();
+        let raw = signer.sign(tx()).await.unwrap();
+        let (local, _) = testing_delegate();
+        assert_eq!(raw.0, local.sign(tx()).await.unwrap().0);
+    }
+
+    #[tokio::test]
+    async fn rejects_swapped_fields_and_foreign_signer() {
+        let (delegate, _) = testing_delegate();
+
+        let swapped = TxEip1559 {
+            to: TxKind::Call(address!("3333333333333333333333333333333333333333")),
+            ..tx()
+        };
+        let stub = dishonest_signer(delegate.sign(swapped).await.unwrap()).await;
+        let signer = RemoteSigner::new(stub.url().into(), "test".into(), TESTING_SIGNER_AUTH_SEED, 0)
+            .await
+            .unwrap();
+        let err = signer.sign(tx()).await.err().unwrap();
+        assert!(err.to_string().contains("different to"), "{err}");
+
+        let cheaper = TxEip1559 { access_list: AccessList::default(), ..tx() };
+        let stub = dishonest_signer(delegate.sign(cheaper).await.unwrap()).await;
+        let signer = RemoteSigner::new(stub.url().into(), "test".into(), TESTING_SIGNER_AUTH_SEED, 0)
+            .await
+            .unwrap();
+        let err = signer.sign(tx()).await.err().unwrap();
+        assert!(err.to_string().contains("different access list"), "{err}");
+
+        let other = LocalSigner::new(PrivateKeySigner::random());
+        let stub = dishonest_signer(other.sign(tx()).await.unwrap()).await;
+        let signer = RemoteSigner::new(stub.url().into(), "test".into(), TESTING_SIGNER_AUTH_SEED, 0)
+            .await
+            .unwrap();
+        let err = signer.sign(tx()).await.err().unwrap();
+        assert!(err.to_string().contains("signed by"), "{err}");
+    }
+
+    /// 按 cs-signer 的请求格式断言：`typed_data` 是 JSON 字符串、`account` 为 checksum 地址。
+    #[tokio::test]
+    async fn typed_data_request_uses_cs_signer_format() {
+        use alloy::signers::SignerSync;
+
+        let seen = Arc::new(std::sync::Mutex::new(None::<serde_json::Value>));
+        let recorded = seen.clone();
+        let key = PrivateKeySigner::from_bytes(&TESTING_DELEGATE_PRIVKEY).unwrap();
+        let stub = serve_http(move |req| {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            let data: serde_json::Value =
+                serde_json::from_str(body["data"].as_str().unwrap()).unwrap();
+            match req.path.as_str() {
+                "/v1/address" => HttpResponse::json(
+                    200,
+                    &json!({ "data": TESTING_DELEGATE_ADDRESS.to_string() }),
+                ),
+                _ => {
+                    let typed: TypedData =
+                        serde_json::from_str(data["typed_data"].as_str().unwrap_or("null"))
+                            .unwrap();
+                    let sig = key.sign_hash_sync(&typed.eip712_signing_hash().unwrap()).unwrap();
+                    *recorded.lock().unwrap() = Some(data);
+                    HttpResponse::json(
+                        200,
+                        &json!({ "signature": format!("0x{}", alloy::hex::encode(sig.as_bytes())) }),
+                    )
+                }
+            }
+        })
+        .await
+        .unwrap();
+        let signer = RemoteSigner::new(stub.url().into(), "test".into(), TESTING_SIGNER_AUTH_SEED, 0)
+            .await
+            .unwrap();
+
+        let typed: TypedData = serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Permit": [
+                    { "name": "spender", "type": "address" },
+                    { "name": "value", "type": "uint256" }
+                ]
+            },
+            "primaryType": "Permit",
+            "domain": { "chainId": 5, "verifyingContract": "0x3333333333333333333333333333333333333333" },
+            "message": { "spender": "0x4444444444444444444444444444444444444444", "value": "1000" }
+        }))
+        .unwrap();
+        let sig = signer.sign_typed_data(&typed).await.unwrap();
+        assert_eq!(
+            sig.recover_address_from_prehash(&typed.eip712_signing_hash().unwrap())
+                .unwrap(),
+            TESTING_DELEGATE_ADDRESS
+        );
+
+        let data = seen.lock().unwrap().take().unwrap();
+        assert_eq!(data["chain_id"], 5);
+        assert_eq!(data["account"], TESTING_DELEGATE_ADDRESS.to_checksum(None));
+        let sent: serde_json::Value =
+            serde_json::from_str(data["typed_data"].as_str().expect("typed_data is a string"))
+                .unwrap();
+        assert_eq!(sent["primaryType"], "Permit");
+        assert_eq!(
+            data,
+            crate::utils::signer_json::typed_data_to_signer_json(&typed, TESTING_DELEGATE_ADDRESS)
+                .unwrap()["content"]
+        );
+    }
+
+    fn fast_retry(retries: u32) -> RemoteSignerOptions {
+        RemoteSignerOptions {
+            retries,
+            retry_backoff: std::time::Duration::from_millis(10),
+            timeout: std::time::Duration::from_millis(300),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures_and_times_out() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let h = hits.clone();
+        let flaky = serve_http(move |_| match h.fetch_add(1, Ordering::SeqCst) {
+            0 => HttpResponse::json(503, &json!({ "msg": "overloaded" })),
+            _ => HttpResponse::json(200, &json!({ "data": TESTING_DELEGATE_ADDRESS.to_string() })),
+        })
+        .await
+        .unwrap();
+        let url = flaky.url().to_string();
+        let seed = TESTING_SIGNER_AUTH_SEED;
+
+        let signer = RemoteSigner::with_options(url.clone(), "test".into(), seed, 0, fast_retry(2))
+            .await
+            .unwrap();
+        assert_eq!(signer.address(), TESTING_DELEGATE_ADDRESS);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        hits.store(0, Ordering::SeqCst);
+        let err = RemoteSigner::with_options(url, "test".into(), seed, 0, fast_retry(0))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("overloaded"), "{err}");
+
+        let slow = serve_http(|_| {
+            HttpResponse::json(200, &json!({})).with_delay(std::time::Duration::from_secs(2))
+        })
+        .await
+        .unwrap();
+        let err = RemoteSigner::with_options(slow.url().into(), "test".into(), seed, 0, fast_retry(0))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("unreachable"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn syncs_server_clock_and_shares_accounts() {
+        let cs = MockCsSigner::start("test").await.unwrap();
+        cs.set_clock_offset_secs(600);
+        let seed = TESTING_SIGNER_AUTH_SEED;
+
+        let err = RemoteSigner::with_options(cs.url().into(), "test".into(), seed, 0, fast_retry(0))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("out of range"), "{err}");
+
+        let options = RemoteSignerOptions { sync_server_time: true, ..fast_retry(1) };
+        let signer = RemoteSigner::with_options(cs.url().into(), "test".into(), seed, 0, options)
+            .await
+            .unwrap();
+        signer.sign(tx()).await.unwrap();
+
+        let operators = signer.accounts(&[1, 0]).await.unwrap();
+        assert_eq!(operators[0].address(), cs.account(1));
+        assert_eq!(operators[0].account_index(), 1);
+        assert_eq!(operators[1].address(), TESTING_DELEGATE_ADDRESS);
+        let raw = operators[0].sign(tx()).await.unwrap();
+        let envelope = TxEnvelope::decode_2718(&mut raw.0.as_ref()).unwrap();
+        assert_eq!(envelope.recover_signer().unwrap(), cs.account(1));
+    }
+}
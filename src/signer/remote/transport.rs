@@ -0,0 +1,256 @@
+//! cs-signer 的 HTTP 传输：认证、超时、重试、服务端时间同步、TLS。
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::Address;
+use ed25519_dalek::{Signer as _, SigningKey};
+use eyre::{eyre, Result, WrapErr};
+use reqwest::{Certificate, Client, Identity, StatusCode};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// 服务端时间与本地时间相差超过这么多秒才校正。
+const CLOCK_SYNC_THRESHOLD_SECS: i64 = 2;
+
+/// [`RemoteSigner`](super::RemoteSigner) 的传输配置。
+///
+/// 签名请求可以安全重试：同一笔交易 / 消息签出来的结果是确定的（RFC 6979），
+/// 签名服务也不会广播，所以超时、连接错误、5xx、429 都会按指数退避重试；
+/// 其它 4xx（认证失败、rule.js 拒签）直接返回错误。
+#[derive(Debug, Clone)]
+pub struct RemoteSignerOptions {
+    /// 单次请求的总超时。
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    /// 失败后额外重试的次数。
+    pub retries: u32,
+    /// 第一次重试前的等待，之后每次翻倍。
+    pub retry_backoff: Duration,
+    /// 用响应的 `Date` header 校正认证时间戳（本机时钟不准时打开）。
+    /// 校正后时间差导致的 4xx 按可重试处理（占用 `retries`）。
+    pub sync_server_time: bool,
+    /// 额外信任的 CA 证书（PEM）。
+    pub ca_cert_pem: Option<Vec<u8>>,
+    /// mTLS 客户端证书：同一个 PEM 里放证书链和私钥。
+    pub client_identity_pem: Option<Vec<u8>>,
+}
+
+impl Default for RemoteSignerOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            connect_timeout: Duration::from_secs(5),
+            retries: 2,
+            retry_backoff: Duration::from_millis(200),
+            sync_server_time: false,
+            ca_cert_pem: None,
+            client_identity_pem: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AuthRequest {
+    project: String,
+    signature: String,
+    public_key: String,
+    timestamp: i64,
+    data: String,
+}
+
+/// 一次请求的失败，区分能否重试。
+enum Failure {
+    Retry(eyre::Report),
+    Fatal(eyre::Report),
+}
+
+/// 多个 [`RemoteSigner`](super::RemoteSigner) 句柄共享的连接、认证 key 和地址缓存。
+pub(super) struct Transport {
+    base_url: String,
+    project: String,
+    signing_key: SigningKey,
+    public_key_hex: String,
+    client: Client,
+    retries: u32,
+    retry_backoff: Duration,
+    sync_server_time: bool,
+    /// 服务端时间 - 本地时间（秒）。
+    clock_offset: AtomicI64,
+    addresses: Mutex<HashMap<i64, Address>>,
+}
+
+impl Transport {
+    pub(super) fn new(
+        base_url: String,
+        project: String,
+        ed25519_seed: [u8; 32],
+        options: &RemoteSignerOptions,
+    ) -> Result<Self> {
+        let signing_key = SigningKey::from_bytes(&ed25519_seed);
+        let public_key_hex = alloy::hex::encode(signing_key.verifying_key().as_bytes());
+        Ok(Self {
+            base_url,
+            project,
+            signing_key,
+            public_key_hex,
+            client: build_client(options)?,
+            retries: options.retries,
+            retry_backoff: options.retry_backoff,
+            sync_server_time: options.sync_server_time,
+            clock_offset: AtomicI64::new(0),
+            addresses: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn build_auth_request(&self, data: &str) -> AuthRequest {
+        // system clock always >= UNIX_EPOCH
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock before UNIX_EPOCH")
+            .as_secs() as i64
+            + self.clock_offset.load(Ordering::Relaxed);
+
+        let content = format!("{timestamp}{data}");
+        let hash = Sha256::digest(content.as_bytes());
+        let signature = self.signing_key.sign(&hash);
+
+        AuthRequest {
+            project: self.project.clone(),
+            signature: format!("0x{}", alloy::hex::encode(signature.to_bytes())),
+            public_key: self.public_key_hex.clone(),
+            timestamp,
+            data: data.to_string(),
+        }
+    }
+
+    /// 发送认证 POST 请求并解析响应，可重试的失败按退避重试。
+    pub(super) async fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        data: &str,
+    ) -> Result<T> {
+        let mut backoff = self.retry_backoff;
+        let mut attempt = 0;
+        let body = loop {
+            match self.post_once(path, data).await {
+                Ok(body) => break body,
+                Err(Failure::Retry(e)) if attempt < self.retries => {
+                    attempt += 1;
+                    tracing::warn!(
+                        "[signer] {path} failed, retry {attempt}/{}: {e:#}",
+                        self.retries
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(Failure::Retry(e) | Failure::Fatal(e)) => return Err(e),
+            }
+        };
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    async fn post_once(&self, path: &str, data: &str) -> std::result::Result<String, Failure> {
+        let req = self.build_auth_request(data);
+        let resp = self
+            .client
+            .post(format!("{}{path}", self.base_url))
+            .json(&req)
+            .send()
+            .await
+            .map_err(|e| Failure::Retry(eyre!("signer service unreachable: {e}")))?;
+
+        let clock_adjusted = self.sync_server_time && self.sync_clock(resp.headers());
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .map_err(|e| Failure::Retry(eyre!("failed to read signer response: {e}")))?;
+        if status.is_success() {
+            return Ok(body);
+        }
+
+        let msg = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v["msg"].as_str().map(String::from))
+            .unwrap_or(body);
+        let err = eyre!("signer service error ({status}): {msg}");
+        let retryable = status.is_server_error()
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || (clock_adjusted && status.is_client_error());
+        Err(if retryable {
+            Failure::Retry(err)
+        } else {
+            Failure::Fatal(err)
+        })
+    }
+
+    /// 按 `Date` header 更新时钟偏移，偏移有明显变化时返回 true。
+    fn sync_clock(&self, headers: &reqwest::header::HeaderMap) -> bool {
+        let Some(server) = headers
+            .get(reqwest::header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| chrono::DateTime::parse_from_rfc2822(v).ok())
+        else {
+            return false;
+        };
+        let local = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock before UNIX_EPOCH")
+            .as_secs() as i64;
+        let offset = server.timestamp() - local;
+        let previous = self.clock_offset.swap(offset, Ordering::Relaxed);
+        if (offset - previous).abs() < CLOCK_SYNC_THRESHOLD_SECS {
+            return false;
+        }
+        tracing::info!("[signer] clock offset to signer service: {offset}s");
+        true
+    }
+
+    /// 账户地址，按 index 缓存。
+    pub(super) async fn address(&self, index: i64) -> Result<Address> {
+        if let Some(addr) = self
+            .addresses
+            .lock()
+            .expect("address cache poisoned")
+            .get(&index)
+        {
+            return Ok(*addr);
+        }
+        let data = serde_json::json!({ "index": index }).to_string();
+
+        #[derive(serde::Deserialize)]
+        struct Resp {
+            data: String,
+        }
+
+        let resp: Resp = self.post("/v1/address", &data).await?;
+        let addr: Address = resp.data.parse()?;
+        self.addresses
+            .lock()
+            .expect("address cache poisoned")
+            .insert(index, addr);
+        Ok(addr)
+    }
+}
+
+fn build_client(options: &RemoteSignerOptions) -> Result<Client> {
+    let mut builder = Client::builder()
+        .timeout(options.timeout)
+        .connect_timeout(options.connect_timeout);
+    if let Some(pem) = &options.ca_cert_pem {
+        let cert = Certificate::from_pem(pem).wrap_err("invalid signer CA certificate")?;
+        builder = builder.use_rustls_tls().add_root_certificate(cert);
+    }
+    if let Some(pem) = &options.client_identity_pem {
+        let identity = Identity::from_pem(pem).wrap_err("invalid signer client certificate")?;
+        builder = builder.use_rustls_tls().identity(identity);
+    }
+    Ok(builder.build()?)
+}
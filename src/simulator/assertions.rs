@@ -26,7 +26,7 @@ use eyre::Result;
 use super::decoder::{AbiDecoder, DecodedEvent};
 
 /// 找第一条匹配 `address` + `topic0` 的 log。
-pub fn find_log<'a>(logs: &'a [Log], address: Address, topic0: B256) -> Option<&'a Log> {
+pub fn find_log(logs: &[Log], address: Address, topic0: B256) -> Option<&Log> {
     logs.iter()
         .find(|l| l.address == address && l.topics().first() == Some(&topic0))
 }
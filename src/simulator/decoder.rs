@@ -140,24 +140,22 @@ impl AbiDecoder {
             return None;
         }
         // Error(string) selector: 0x08c379a0
-        if data[..4] == [0x08, 0xc3, 0x79, 0xa0] {
-            if let Ok(s) =
+        if data[..4] == [0x08, 0xc3, 0x79, 0xa0]
+            && let Ok(s) =
                 <alloy::sol_types::sol_data::String as alloy::sol_types::SolType>::abi_decode(
                     &data[4..],
                 )
-            {
-                return Some(s);
-            }
+        {
+            return Some(s);
         }
         // Panic(uint256) selector: 0x4e487b71
-        if data[..4] == [0x4e, 0x48, 0x7b, 0x71] {
-            if let Ok(code) =
+        if data[..4] == [0x4e, 0x48, 0x7b, 0x71]
+            && let Ok(code) =
                 <alloy::sol_types::sol_data::Uint<256> as alloy::sol_types::SolType>::abi_decode(
                     &data[4..],
                 )
-            {
-                return Some(format!("Panic(0x{code:x})"));
-            }
+        {
+            return Some(format!("Panic(0x{code:x})"));
         }
         Some(format!("0x{}", alloy::hex::encode(data)))
     }
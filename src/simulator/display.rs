@@ -34,16 +34,15 @@ pub fn display_result(result: &SimulationResult, tx: &TxEnv, decoder: Option<&Ab
     if !calldata.is_empty() {
         println!("\n── Calldata ──");
         println!("Raw:       0x{}", alloy::hex::encode(calldata));
-        if let Some(dec) = decoder {
-            if let alloy::primitives::TxKind::Call(to) = &tx.kind {
-                if let Some(decoded) = dec.decode_calldata(to, calldata) {
-                    println!("Decoded:   {}(", decoded.name);
-                    for (name, val) in &decoded.params {
-                        println!("             {name}: {val}");
-                    }
-                    println!("           )");
-                }
+        if let Some(dec) = decoder
+            && let alloy::primitives::TxKind::Call(to) = &tx.kind
+            && let Some(decoded) = dec.decode_calldata(to, calldata)
+        {
+            println!("Decoded:   {}(", decoded.name);
+            for (name, val) in &decoded.params {
+                println!("             {name}: {val}");
             }
+            println!("           )");
         }
     }
 
@@ -53,10 +52,10 @@ pub fn display_result(result: &SimulationResult, tx: &TxEnv, decoder: Option<&Ab
     if result.gas_refunded > 0 {
         println!("Refunded:  {}", result.gas_refunded);
     }
-    if let Some(ref output) = result.output {
-        if !output.is_empty() {
-            println!("Output:    0x{}", alloy::hex::encode(output));
-        }
+    if let Some(ref output) = result.output
+        && !output.is_empty()
+    {
+        println!("Output:    0x{}", alloy::hex::encode(output));
     }
     if let Some(ref addr) = result.created_address {
         println!("Created:   {:?}", addr);
@@ -16,13 +16,16 @@ use revm::{
         block::BlobExcessGasAndPrice,
         result::{ExecutionResult, HaltReason, Output, ResultAndState},
     },
-    database::WrapDatabaseRef,
+    database::{CacheDB, WrapDatabaseRef},
     primitives::hardfork::SpecId,
     state::{Account, EvmState, EvmStorageSlot},
-    DatabaseRef,
+    DatabaseCommit, DatabaseRef,
 };
 
-use super::decoder::AbiDecoder;
+use super::{
+    decoder::AbiDecoder,
+    trace::{CallFrame, CallTracer},
+};
 
 /// 交易模拟结果
 pub struct SimulationResult {
@@ -127,6 +130,27 @@ impl ForkSimulator {
         Ok(into_simulation_result(res))
     }
 
+    /// 按顺序模拟一组交易（后一笔看到前一笔的状态变更），但不 commit 到 fork DB：
+    /// 在 fork 状态的临时副本上执行，结束即丢弃。`eth_callBundle` 语义。
+    pub fn simulate_bundle(&self, txs: Vec<TxEnv>) -> Result<Vec<SimulationResult>> {
+        let mut scratch = CacheDB::new(WrapDatabaseRef(self.shared.clone()));
+        let env = EvmEnv {
+            block_env: self.block_env.clone(),
+            cfg_env: self.cfg_env.clone(),
+        };
+        let mut results = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let tx = self.fill_tx_defaults(tx)?;
+            let mut evm = EthEvmBuilder::new(&mut scratch, env.clone()).build();
+            let res = evm.transact(tx).map_err(|e| eyre::eyre!("{e:?}"))?;
+            drop(evm);
+            let result = into_simulation_result(res);
+            scratch.commit(result.state_changes.clone());
+            results.push(result);
+        }
+        Ok(results)
+    }
+
     /// 模拟执行并 commit 状态变更到 fork DB（用于连续交易模拟）
     pub fn simulate_and_commit(&mut self, tx: TxEnv) -> Result<SimulationResult> {
         let tx = self.fill_tx_defaults(tx)?;
@@ -146,6 +170,37 @@ impl ForkSimulator {
     ///
     /// 不支持 contract creation（`TxKind::Create`）—— CoboSafe 场景下也不需要。
     pub fn simulate_raw_tx(&mut self, raw: &[u8]) -> Result<SimulationResult> {
+        let tx = Self::raw_tx_env(raw)?;
+        self.simulate_and_commit(tx)
+    }
+
+    /// 同 [`simulate_raw_tx`](Self::simulate_raw_tx)，额外返回调用树（见 [`super::trace`]）。
+    pub fn simulate_raw_tx_traced(
+        &mut self,
+        raw: &[u8],
+    ) -> Result<(SimulationResult, Vec<CallFrame>)> {
+        let (result, frames) = self.simulate_traced(Self::raw_tx_env(raw)?)?;
+        self.commit_state(&result.state_changes);
+        Ok((result, frames))
+    }
+
+    /// 同 [`simulate`](Self::simulate)（不 commit），额外返回调用树。
+    pub fn simulate_traced(&self, tx: TxEnv) -> Result<(SimulationResult, Vec<CallFrame>)> {
+        let tx = self.fill_tx_defaults(tx)?;
+        let env = EvmEnv {
+            block_env: self.block_env.clone(),
+            cfg_env: self.cfg_env.clone(),
+        };
+        let mut evm = EthEvmBuilder::new(WrapDatabaseRef(self.shared.clone()), env)
+            .activate_inspector(CallTracer::default())
+            .build();
+        let res = evm.transact(tx).map_err(|e| eyre::eyre!("{e:?}"))?;
+        let frames = std::mem::take(evm.components_mut().1).into_frames();
+        Ok((into_simulation_result(res), frames))
+    }
+
+    /// 把已签名的 2718 raw tx 转成 `TxEnv`（caller 为恢复出的 signer，gas_price 取 max_fee）。
+    pub fn raw_tx_env(raw: &[u8]) -> Result<TxEnv> {
         let mut buf = raw;
         let envelope = TxEnvelope::decode_2718(&mut buf)
             .map_err(|e| eyre::eyre!("decode 2718 envelope: {e}"))?;
@@ -166,7 +221,7 @@ impl ForkSimulator {
             gas_price: envelope.max_fee_per_gas(),
             ..Default::default()
         };
-        self.simulate_and_commit(tx)
+        Ok(tx)
     }
 
     /// 提交状态变更到 fork DB，修复 foundry-fork-db 的零值 slot 问题。
@@ -361,10 +416,10 @@ impl ForkSimulator {
         let res = evm.transact(tx).map_err(|e| eyre::eyre!("{e:?}"))?;
         let result = into_simulation_result(res);
 
-        if let Some(output) = &result.output {
-            if output.len() >= 32 {
-                return Ok(U256::from_be_slice(&output[..32]));
-            }
+        if let Some(output) = &result.output
+            && output.len() >= 32
+        {
+            return Ok(U256::from_be_slice(&output[..32]));
         }
         Ok(U256::ZERO)
     }
@@ -450,7 +505,7 @@ fn into_simulation_result(res: ResultAndState<HaltReason>) -> SimulationResult {
                 gas_used,
                 gas_refunded,
                 output: out_bytes,
-                logs: logs.into_iter().map(|l| l.into()).collect(),
+                logs,
                 revert_reason: None,
                 state_changes,
                 created_address: created_addr,
@@ -3,7 +3,10 @@ pub mod decoder;
 pub mod display;
 pub mod erc20;
 pub mod fork;
+pub mod trace;
+pub mod user_op;
 
 pub use decoder::{AbiDecoder, DecodedCall, DecodedEvent};
 pub use display::display_result;
 pub use fork::{ForkSimulator, SimulationResult};
+pub use trace::{format_trace, CallFrame, CallTracer};
@@ -0,0 +1,170 @@
+//! 调用树追踪：记录一笔交易执行过程中的每一层 CALL / CREATE，模拟失败时
+//! 用来定位是哪一层、哪个合约 revert。
+//!
+//! ```ignore
+//! let (result, trace) = sim.simulate_raw_tx_traced(&raw.0)?;
+//! if !result.success {
+//!     eprintln!("{}", format_trace(&trace, Some(&decoder)));
+//! }
+//! ```
+
+use alloy::primitives::{Address, Bytes, U256};
+use revm::{
+    context_interface::ContextTr,
+    interpreter::{CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome},
+    Inspector,
+};
+
+use super::decoder::AbiDecoder;
+
+/// 调用树里的一帧，按进入顺序排列（先序遍历），`depth` 0 为交易本身。
+#[derive(Debug, Clone)]
+pub struct CallFrame {
+    pub depth: usize,
+    /// `CALL` / `STATICCALL` / `DELEGATECALL` / `CALLCODE` / `CREATE`。
+    pub kind: &'static str,
+    pub from: Address,
+    /// CREATE 时为新合约地址（失败时为零地址）。
+    pub to: Address,
+    pub value: U256,
+    pub input: Bytes,
+    pub output: Bytes,
+    pub success: bool,
+    pub gas_used: u64,
+}
+
+impl CallFrame {
+    /// 失败帧的 revert 原因（`Error(string)` / `Panic(uint256)`）。
+    pub fn revert_reason(&self) -> Option<String> {
+        if self.success {
+            return None;
+        }
+        AbiDecoder::decode_revert(&self.output)
+    }
+}
+
+/// 收集 [`CallFrame`] 的 revm inspector。
+#[derive(Debug, Default)]
+pub struct CallTracer {
+    frames: Vec<CallFrame>,
+    /// 尚未结束的帧在 `frames` 里的下标。
+    open: Vec<usize>,
+}
+
+impl CallTracer {
+    pub fn into_frames(self) -> Vec<CallFrame> {
+        self.frames
+    }
+
+    fn enter(&mut self, frame: CallFrame) {
+        self.open.push(self.frames.len());
+        self.frames.push(frame);
+    }
+
+    fn exit(&mut self, success: bool, output: &Bytes, gas_used: u64) -> Option<&mut CallFrame> {
+        let frame = &mut self.frames[self.open.pop()?];
+        frame.success = success;
+        frame.output = output.clone();
+        frame.gas_used = gas_used;
+        Some(frame)
+    }
+}
+
+impl<CTX: ContextTr> Inspector<CTX> for CallTracer {
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let kind = match inputs.scheme {
+            CallScheme::Call => "CALL",
+            CallScheme::CallCode => "CALLCODE",
+            CallScheme::DelegateCall => "DELEGATECALL",
+            CallScheme::StaticCall => "STATICCALL",
+        };
+        self.enter(CallFrame {
+            depth: self.open.len(),
+            kind,
+            from: inputs.caller,
+            to: inputs.target_address,
+            value: inputs.value.get(),
+            input: inputs.input.bytes(context),
+            output: Bytes::new(),
+            success: false,
+            gas_used: 0,
+        });
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        let result = &outcome.result;
+        self.exit(result.result.is_ok(), &result.output, result.gas.spent());
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.enter(CallFrame {
+            depth: self.open.len(),
+            kind: "CREATE",
+            from: inputs.caller(),
+            to: Address::ZERO,
+            value: inputs.value(),
+            input: inputs.init_code().clone(),
+            output: Bytes::new(),
+            success: false,
+            gas_used: 0,
+        });
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        let result = &outcome.result;
+        if let Some(frame) = self.exit(result.result.is_ok(), &result.output, result.gas.spent())
+            && let Some(addr) = outcome.address
+        {
+            frame.to = addr;
+        }
+    }
+}
+
+/// 把调用树格式化成缩进文本，有 `decoder` 时解码 calldata。
+///
+/// ```text
+/// [0] CALL 0xc0c0…c0 execTransactions(...)  gas=51234 REVERT
+///   [1] CALL 0xa0b8…48 approve(spender: 0x…, amount: 1000)  gas=24000 REVERT: nope
+/// ```
+pub fn format_trace(frames: &[CallFrame], decoder: Option<&AbiDecoder>) -> String {
+    let mut out = String::new();
+    for frame in frames {
+        let call = decoder
+            .and_then(|d| d.decode_calldata(&frame.to, &frame.input))
+            .map(|c| {
+                let params: Vec<String> =
+                    c.params.iter().map(|(n, v)| format!("{n}: {v}")).collect();
+                format!("{}({})", c.name, params.join(", "))
+            })
+            .unwrap_or_else(|| match frame.input.get(..4) {
+                Some(selector) => format!("0x{}", alloy::hex::encode(selector)),
+                None => "fallback".to_string(),
+            });
+        let value = if frame.value.is_zero() {
+            String::new()
+        } else {
+            format!(" value={}", frame.value)
+        };
+        let status = match (frame.success, frame.revert_reason()) {
+            (true, _) => String::new(),
+            (false, Some(reason)) => format!(" REVERT: {reason}"),
+            (false, None) => " REVERT".to_string(),
+        };
+        out.push_str(&format!(
+            "{}[{}] {} {} {call}{value}  gas={}{status}\n",
+            "  ".repeat(frame.depth),
+            frame.depth,
+            frame.kind,
+            frame.to,
+            frame.gas_used,
+        ));
+    }
+    out
+}
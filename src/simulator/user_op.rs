@@ -0,0 +1,265 @@
+//! fork 场景下的 ERC-4337 EntryPoint 调用：以本地 bundler 身份执行 `handleOps`。
+//!
+//! 注意 `handleOps` 的语义：validation 失败整笔 revert（`FailedOp`），
+//! 但 execution 失败**不会** revert，只在 `UserOperationEvent.success = false`
+//! 里体现。断言时请用 [`user_op_events`] 检查每个 op 的结果。
+
+use alloy::{
+    primitives::{aliases::U192, Address, Bytes, Log, TxKind, B256, U256},
+    sol,
+    sol_types::{SolCall, SolError, SolEvent},
+};
+use eyre::Result;
+use revm::context::TxEnv;
+
+use super::{ForkSimulator, SimulationResult};
+use crate::builder::user_op::{getNonceCall, handleOpsCall, UserOperation};
+
+sol! {
+    error FailedOp(uint256 opIndex, string reason);
+    error FailedOpWithRevert(uint256 opIndex, string reason, bytes inner);
+
+    event UserOperationEvent(
+        bytes32 indexed userOpHash,
+        address indexed sender,
+        address indexed paymaster,
+        uint256 nonce,
+        bool success,
+        uint256 actualGasCost,
+        uint256 actualGasUsed
+    );
+    event UserOperationRevertReason(
+        bytes32 indexed userOpHash,
+        address indexed sender,
+        uint256 nonce,
+        bytes revertReason
+    );
+}
+
+/// `handleOps` 外层调用在 op 自身 gas 之外额外预留的 gas。
+const HANDLE_OPS_OVERHEAD_GAS: u64 = 200_000;
+
+/// 单个 UserOperation 在 EntryPoint 里的执行结果（来自 `UserOperationEvent`）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserOpOutcome {
+    pub user_op_hash: B256,
+    pub sender: Address,
+    pub paymaster: Address,
+    pub nonce: U256,
+    pub success: bool,
+    pub actual_gas_cost: U256,
+    pub actual_gas_used: U256,
+    /// `success = false` 时 `UserOperationRevertReason` 里的原始 revert data。
+    pub revert_reason: Option<Bytes>,
+}
+
+/// 在 fork 上以 `bundler` 为 caller 执行 `EntryPoint.handleOps(ops, bundler)` 并 commit。
+///
+/// `bundler` 同时是 beneficiary（收取 gas 补偿）。validation 失败时
+/// `revert_reason` 会被解码成 `FailedOp(#i): AA23 reverted ...` 这种可读格式。
+pub fn handle_ops(
+    sim: &mut ForkSimulator,
+    entry_point: Address,
+    ops: &[UserOperation],
+    bundler: Address,
+) -> Result<SimulationResult> {
+    eyre::ensure!(!ops.is_empty(), "ops must not be empty");
+
+    let gas_limit = handle_ops_gas_limit(ops);
+    let data = handleOpsCall {
+        ops: ops.iter().map(UserOperation::pack).collect(),
+        beneficiary: bundler,
+    }
+    .abi_encode();
+
+    let nonce = sim.get_nonce(bundler)?;
+    let tx = TxEnv {
+        caller: bundler,
+        nonce,
+        kind: TxKind::Call(entry_point),
+        data: Bytes::from(data),
+        gas_limit,
+        ..Default::default()
+    };
+    let mut result = sim.simulate_and_commit(tx)?;
+    if !result.success
+        && let Some(reason) = result.output.as_ref().and_then(|o| decode_failed_op(o))
+    {
+        result.revert_reason = Some(reason);
+    }
+    Ok(result)
+}
+
+/// 外层 `handleOps` 的 gas limit：各 op 的 gas 字段之和加上固定开销。
+///
+/// op 来自调用方，字段可能大得离谱，一律饱和相加，封顶 `u64::MAX`。
+fn handle_ops_gas_limit(ops: &[UserOperation]) -> u64 {
+    ops.iter().fold(HANDLE_OPS_OVERHEAD_GAS, |acc, op| {
+        let op_gas = op
+            .verification_gas_limit
+            .saturating_add(op.call_gas_limit)
+            .saturating_add(op.paymaster_verification_gas_limit)
+            .saturating_add(op.paymaster_post_op_gas_limit);
+        let pre = u64::try_from(op.pre_verification_gas).unwrap_or(u64::MAX);
+        acc.saturating_add(u64::try_from(op_gas).unwrap_or(u64::MAX))
+            .saturating_add(pre)
+    })
+}
+
+/// 在 fork 上查 `EntryPoint.getNonce(sender, key)`。`key = 0` 即默认 nonce 序列。
+pub fn get_nonce(
+    sim: &ForkSimulator,
+    entry_point: Address,
+    sender: Address,
+    key: U192,
+) -> Result<U256> {
+    let tx = TxEnv {
+        caller: Address::ZERO,
+        kind: TxKind::Call(entry_point),
+        data: Bytes::from(getNonceCall { sender, key }.abi_encode()),
+        gas_limit: 100_000,
+        ..Default::default()
+    };
+    let result = sim.simulate(tx)?;
+    let output = result
+        .output
+        .ok_or_else(|| eyre::eyre!("getNonce({sender}) returned no output"))?;
+    Ok(getNonceCall::abi_decode_returns(&output)?)
+}
+
+/// 从 `handleOps` 的 logs 里按顺序提取每个 op 的结果。
+pub fn user_op_events(logs: &[Log], entry_point: Address) -> Vec<UserOpOutcome> {
+    let mut outcomes: Vec<UserOpOutcome> = Vec::new();
+    let mut reasons: Vec<(B256, Bytes)> = Vec::new();
+    for log in logs.iter().filter(|l| l.address == entry_point) {
+        if let Ok(ev) = UserOperationEvent::decode_log_data(&log.data) {
+            outcomes.push(UserOpOutcome {
+                user_op_hash: ev.userOpHash,
+                sender: ev.sender,
+                paymaster: ev.paymaster,
+                nonce: ev.nonce,
+                success: ev.success,
+                actual_gas_cost: ev.actualGasCost,
+                actual_gas_used: ev.actualGasUsed,
+                revert_reason: None,
+            });
+        } else if let Ok(ev) = UserOperationRevertReason::decode_log_data(&log.data) {
+            reasons.push((ev.userOpHash, ev.revertReason));
+        }
+    }
+    for (hash, reason) in reasons {
+        if let Some(o) = outcomes.iter_mut().find(|o| o.user_op_hash == hash) {
+            o.revert_reason = Some(reason);
+        }
+    }
+    outcomes
+}
+
+/// 解码 EntryPoint 的 `FailedOp` / `FailedOpWithRevert`。
+fn decode_failed_op(data: &[u8]) -> Option<String> {
+    if let Ok(e) = FailedOp::abi_decode(data) {
+        return Some(format!("FailedOp(#{}): {}", e.opIndex, e.reason));
+    }
+    if let Ok(e) = FailedOpWithRevert::abi_decode(data) {
+        let inner = super::AbiDecoder::decode_revert(&e.inner).unwrap_or_default();
+        return Some(format!("FailedOp(#{}): {} [{inner}]", e.opIndex, e.reason));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::MockChain;
+    use alloy::primitives::address;
+
+    const ENTRY_POINT: Address = address!("0000000071727de22e5e9d8baf0edac6f37da032");
+    const BUNDLER: Address = address!("b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0");
+
+    /// 把 `data` 原样 revert 出去的合约代码（空 data 时就是正常返回）。
+    fn revert_with(data: &[u8]) -> Bytes {
+        let len = u16::try_from(data.len()).unwrap().to_be_bytes();
+        let mut code = if data.is_empty() {
+            vec![0x00]
+        } else {
+            vec![
+                0x61, len[0], len[1], 0x60, 0x0e, 0x60, 0x00, 0x39, // CODECOPY(0, 14, len)
+                0x61, len[0], len[1], 0x60, 0x00, 0xfd, // REVERT(0, len)
+            ]
+        };
+        code.extend_from_slice(data);
+        code.into()
+    }
+
+    #[test]
+    fn gas_limit_saturates_on_absurd_ops() {
+        let op = UserOperation {
+            call_gas_limit: 100_000,
+            verification_gas_limit: 50_000,
+            pre_verification_gas: U256::from(21_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            handle_ops_gas_limit(std::slice::from_ref(&op)),
+            HANDLE_OPS_OVERHEAD_GAS + 171_000
+        );
+
+        let absurd = UserOperation {
+            call_gas_limit: u128::MAX,
+            verification_gas_limit: u128::MAX,
+            pre_verification_gas: U256::MAX,
+            ..Default::default()
+        };
+        assert_eq!(handle_ops_gas_limit(&[op, absurd]), u64::MAX);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn handle_ops_executes_on_fork() {
+        let chain = MockChain::start(1).await.unwrap();
+        let failed = FailedOp {
+            opIndex: U256::ZERO,
+            reason: "AA23 reverted".into(),
+        }
+        .abi_encode();
+        chain.set_code(ENTRY_POINT, revert_with(&failed));
+        let mut sim = ForkSimulator::fork_for_simulation(chain.url(), None)
+            .await
+            .unwrap();
+        let op = UserOperation {
+            sender: address!("5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe"),
+            call_gas_limit: 100_000,
+            verification_gas_limit: 100_000,
+            pre_verification_gas: U256::from(50_000),
+            ..Default::default()
+        };
+
+        // validation 失败：整笔 revert，FailedOp 解码成可读原因，但 bundler 的 nonce 照样前进
+        let result = handle_ops(&mut sim, ENTRY_POINT, std::slice::from_ref(&op), BUNDLER).unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            result.revert_reason.as_deref(),
+            Some("FailedOp(#0): AA23 reverted")
+        );
+        assert_eq!(sim.get_nonce(BUNDLER).unwrap(), 1);
+
+        sim.set_code(ENTRY_POINT, revert_with(&[])).unwrap();
+        let result = handle_ops(&mut sim, ENTRY_POINT, &[op], BUNDLER).unwrap();
+        assert!(result.success, "{:?}", result.revert_reason);
+        assert_eq!(sim.get_nonce(BUNDLER).unwrap(), 2);
+        assert!(handle_ops(&mut sim, ENTRY_POINT, &[], BUNDLER).is_err());
+    }
+
+    #[test]
+    fn failed_op_is_readable() {
+        let data = FailedOp {
+            opIndex: U256::from(1),
+            reason: "AA21 didn't pay prefund".into(),
+        }
+        .abi_encode();
+        assert_eq!(
+            decode_failed_op(&data).as_deref(),
+            Some("FailedOp(#1): AA21 didn't pay prefund")
+        );
+        assert!(decode_failed_op(&[0x08, 0xc3, 0x79, 0xa0]).is_none());
+    }
+}
@@ -0,0 +1,697 @@
+//! 链上 ACL 与链下 rule.js 的差分 fuzz。
+//!
+//! 以一组"应当放行"的 `TxRequest` 为种子，随机变异目标地址、selector、calldata
+//! 参数字和 value，每个变体包成 CoboSafe `execTransaction`：
+//!
+//! - **ACL**：delegate 签名后在 fork 上执行（不 commit）。inner call 打到目标合约之前
+//!   就 revert 的（CoboSafe / ACL 拒绝）算拒绝；目标合约执行后才 revert、或 gas 耗尽
+//!   的算不确定（见 [`AclOutcome`]），不参与比较
+//! - **rule**：同一笔 tx 的 [`tx_to_signer_json`] 交给 rule.js（或 Rust 闭包）
+//!
+//! 两边结论不一致的变体会被最小化（逐个去掉变异，直到去掉任何一个都不再不一致），
+//! 报告里给出可直接复现的 `TxRequest`；不确定的用例单独列出。
+//!
+//! ```ignore
+//! use flashseal_rs::utils::{acl_fuzz::AclFuzzer, rule_js::RuleJs};
+//! let mut sim = ForkSimulator::fork_for_simulation(&rpc, None).await?;
+//! let setup = cobosafe::setup_fork_test_env(&mut sim, ...)?;
+//! let report = AclFuzzer::new(cobosafe, 1, vec![deposit_request])
+//!     .with_targets(vec![protocol, token])
+//!     .with_cases(500)
+//!     .run(&sim, &RuleJs::load("signer/projects/TEST/rule.js")?.into())
+//!     .await?;
+//! assert!(report.disagreements.is_empty(), "{}", report.summary());
+//! ```
+//!
+//! 随机数用固定种子的 splitmix64，同一 `seed` 生成的用例序列不变。
+
+use alloy::{
+    consensus::TxEip1559,
+    primitives::{Address, B256, U256},
+};
+use eyre::Result;
+use serde_json::Value;
+
+use super::{rule_js::RuleJs, signer_json::tx_to_signer_json, testing::testing_delegate};
+use crate::{
+    simulator::CallFrame, CoboSafeBuilder, ForkSimulator, LocalSigner, RawTx, SimulationResult,
+    TxBuilder, TxRequest, TxSigner,
+};
+
+const DEFAULT_CASES: usize = 200;
+const DEFAULT_SEED: u64 = 0x5eed;
+
+/// rule 侧的判定：rule.js 文件或等价的 Rust 闭包（参数为 `jsStruct`）。
+pub enum RuleCheck {
+    Js(RuleJs),
+    Fn(Box<dyn Fn(&Value) -> bool + Send + Sync>),
+}
+
+impl From<RuleJs> for RuleCheck {
+    fn from(rule: RuleJs) -> Self {
+        Self::Js(rule)
+    }
+}
+
+impl RuleCheck {
+    pub fn func(f: impl Fn(&Value) -> bool + Send + Sync + 'static) -> Self {
+        Self::Fn(Box::new(f))
+    }
+
+    fn check_all(&self, inputs: &[Value]) -> Result<Vec<bool>> {
+        match self {
+            Self::Js(rule) => Ok(rule
+                .check_all(inputs)?
+                .into_iter()
+                .map(|v| v.allowed)
+                .collect()),
+            Self::Fn(f) => Ok(inputs.iter().map(f).collect()),
+        }
+    }
+}
+
+/// 对种子请求的一处改动。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mutation {
+    Target(Address),
+    Selector([u8; 4]),
+    /// 覆盖 selector 之后第 `index` 个 32 字节参数字；calldata 不够长时不生效。
+    Word {
+        index: usize,
+        value: B256,
+    },
+    Value(U256),
+}
+
+impl Mutation {
+    fn apply(&self, req: &mut TxRequest) {
+        match self {
+            Self::Target(to) => req.to = *to,
+            Self::Value(v) => req.value = *v,
+            Self::Selector(sel) => {
+                if req.data.len() >= 4 {
+                    let mut data = req.data.to_vec();
+                    data[..4].copy_from_slice(sel);
+                    req.data = data.into();
+                }
+            }
+            Self::Word { index, value } => {
+                let start = 4 + index * 32;
+                if req.data.len() >= start + 32 {
+                    let mut data = req.data.to_vec();
+                    data[start..start + 32].copy_from_slice(value.as_slice());
+                    req.data = data.into();
+                }
+            }
+        }
+    }
+}
+
+/// ACL 侧一次执行的结论。
+#[derive(Debug, Clone)]
+pub struct AclVerdict {
+    pub outcome: AclOutcome,
+    pub revert_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AclOutcome {
+    Allowed,
+    /// revert 时 inner call 还没打到目标合约：CoboSafe / ACL 拒绝。
+    Rejected,
+    /// 目标合约执行之后才 revert（目标自身的检查、余额不足等）或 gas 耗尽，
+    /// 说明不了 ACL 放不放行。
+    Inconclusive,
+}
+
+impl AclVerdict {
+    /// 按调用树判定：失败时只有目标合约没被调用过才算 ACL 拒绝。
+    fn classify(req: &TxRequest, result: SimulationResult, trace: &[CallFrame]) -> Self {
+        let outcome = if result.success {
+            AclOutcome::Allowed
+        } else {
+            let halted = result
+                .revert_reason
+                .as_deref()
+                .is_some_and(|r| r.starts_with("HALT"));
+            let reached = trace
+                .iter()
+                .any(|f| f.depth > 0 && f.to == req.to && f.kind != "STATICCALL");
+            if halted || reached {
+                AclOutcome::Inconclusive
+            } else {
+                AclOutcome::Rejected
+            }
+        };
+        Self {
+            outcome,
+            revert_reason: result.revert_reason,
+        }
+    }
+
+    /// 与 rule 的结论是否不一致；不确定时不算。
+    fn disagrees(&self, rule_allowed: bool) -> bool {
+        match self.outcome {
+            AclOutcome::Allowed => !rule_allowed,
+            AclOutcome::Rejected => rule_allowed,
+            AclOutcome::Inconclusive => false,
+        }
+    }
+}
+
+/// 一个不一致的（已最小化的）用例。
+#[derive(Debug, Clone)]
+pub struct Disagreement {
+    pub seed_index: usize,
+    /// 最小化后仍然导致不一致的变异。为空表示种子本身就不一致。
+    pub mutations: Vec<Mutation>,
+    /// 可直接复现的 inner 请求。
+    pub request: TxRequest,
+    pub acl: AclVerdict,
+    pub rule_allowed: bool,
+    pub signer_json: Value,
+}
+
+impl Disagreement {
+    /// 一行复现说明。
+    pub fn reproducer(&self) -> String {
+        let (acl, rule) = match self.acl.outcome {
+            AclOutcome::Allowed => ("allowed", "rejected"),
+            _ => ("rejected", "allowed"),
+        };
+        let reason = self
+            .acl
+            .revert_reason
+            .as_deref()
+            .map(|r| format!(" (revert: {r})"))
+            .unwrap_or_default();
+        format!(
+            "ACL {acl}{reason}, rule {rule}: seed #{} + {:?} => to={} value={} data=0x{}",
+            self.seed_index,
+            self.mutations,
+            self.request.to,
+            self.request.value,
+            alloy::hex::encode(&self.request.data)
+        )
+    }
+}
+
+/// ACL 侧结论不确定、没有参与比较的用例（未最小化）。
+#[derive(Debug, Clone)]
+pub struct InconclusiveCase {
+    pub seed_index: usize,
+    pub mutations: Vec<Mutation>,
+    pub request: TxRequest,
+    pub revert_reason: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzReport {
+    pub cases: usize,
+    /// 按最小化结果去重。
+    pub disagreements: Vec<Disagreement>,
+    pub inconclusive: Vec<InconclusiveCase>,
+}
+
+impl FuzzReport {
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "{} cases, {} disagreements, {} inconclusive",
+            self.cases,
+            self.disagreements.len(),
+            self.inconclusive.len()
+        )];
+        lines.extend(self.disagreements.iter().map(|d| d.reproducer()));
+        lines.join("\n  ")
+    }
+}
+
+/// 差分 fuzz 的配置与入口，见模块文档。
+pub struct AclFuzzer {
+    cobosafe: Address,
+    chain_id: u64,
+    seeds: Vec<TxRequest>,
+    signer: LocalSigner,
+    targets: Vec<Address>,
+    selectors: Vec<[u8; 4]>,
+    cases: usize,
+    seed: u64,
+}
+
+/// 生成的一个用例：种子 + 变异。
+#[derive(Clone)]
+struct Case {
+    seed_index: usize,
+    mutations: Vec<Mutation>,
+}
+
+impl AclFuzzer {
+    /// `seeds` 是 ACL 与 rule 都应放行的 inner 请求。签名者默认是 [`testing_delegate`]。
+    pub fn new(cobosafe: Address, chain_id: u64, seeds: Vec<TxRequest>) -> Self {
+        Self {
+            cobosafe,
+            chain_id,
+            seeds,
+            signer: testing_delegate().0,
+            targets: Vec::new(),
+            selectors: Vec::new(),
+            cases: DEFAULT_CASES,
+            seed: DEFAULT_SEED,
+        }
+    }
+
+    /// 已授权为 CoboSafe delegate 的签名者。
+    pub fn with_signer(mut self, signer: LocalSigner) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// 变异时候选的目标地址（也会被当作地址参数填进参数字）。种子的 `to` 总在候选里。
+    pub fn with_targets(mut self, targets: Vec<Address>) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    /// 变异时候选的 selector。种子的 selector 总在候选里。
+    pub fn with_selectors(mut self, selectors: Vec<[u8; 4]>) -> Self {
+        self.selectors = selectors;
+        self
+    }
+
+    pub fn with_cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// 在 `sim` 上跑 ACL（不 commit），与 `rule` 比较。
+    ///
+    /// `sim` 需已完成 CoboSafe 授权（见 [`crate::utils::cobosafe::setup_fork_test_env`]），
+    /// delegate 无 ETH 时用 [`ForkSimulator::fork_for_simulation`] 关掉余额检查。
+    pub async fn run(&self, sim: &ForkSimulator, rule: &RuleCheck) -> Result<FuzzReport> {
+        let nonce = sim.get_nonce(self.signer.address())?;
+        let basefee = sim.block_env().basefee as u128;
+        self.run_with(nonce, basefee + 1, rule, |req, raw| {
+            let (result, trace) = sim.simulate_traced(ForkSimulator::raw_tx_env(&raw.0)?)?;
+            Ok(AclVerdict::classify(req, result, &trace))
+        })
+        .await
+    }
+
+    async fn run_with<A>(
+        &self,
+        nonce: u64,
+        max_fee: u128,
+        rule: &RuleCheck,
+        mut acl: A,
+    ) -> Result<FuzzReport>
+    where
+        A: FnMut(&TxRequest, &RawTx) -> Result<AclVerdict>,
+    {
+        eyre::ensure!(
+            !self.seeds.is_empty(),
+            "acl fuzz needs at least one seed request"
+        );
+        let mut rng = SplitMix64(self.seed);
+        let cases: Vec<Case> = (0..self.cases).map(|_| self.gen_case(&mut rng)).collect();
+
+        let mut acl_verdicts = Vec::with_capacity(cases.len());
+        let mut payloads = Vec::with_capacity(cases.len());
+        for case in &cases {
+            let (verdict, payload) = self.eval_acl(case, nonce, max_fee, &mut acl).await?;
+            acl_verdicts.push(verdict);
+            payloads.push(payload);
+        }
+        let rule_verdicts = rule.check_all(&payloads)?;
+
+        let mut disagreements: Vec<Disagreement> = Vec::new();
+        let mut inconclusive = Vec::new();
+        for (case, (acl_v, rule_v)) in cases
+            .iter()
+            .zip(acl_verdicts.into_iter().zip(rule_verdicts))
+        {
+            if acl_v.outcome == AclOutcome::Inconclusive {
+                inconclusive.push(InconclusiveCase {
+                    seed_index: case.seed_index,
+                    mutations: case.mutations.clone(),
+                    request: self.request(case),
+                    revert_reason: acl_v.revert_reason,
+                });
+                continue;
+            }
+            if !acl_v.disagrees(rule_v) {
+                continue;
+            }
+            let d = self.minimise(case, nonce, max_fee, rule, &mut acl).await?;
+            let dup = disagreements
+                .iter()
+                .any(|x| x.seed_index == d.seed_index && x.mutations == d.mutations);
+            if !dup {
+                tracing::warn!("[acl-fuzz] {}", d.reproducer());
+                disagreements.push(d);
+            }
+        }
+        Ok(FuzzReport {
+            cases: cases.len(),
+            disagreements,
+            inconclusive,
+        })
+    }
+
+    /// 逐个去掉变异，只要仍不一致就保留删减。
+    async fn minimise<A>(
+        &self,
+        case: &Case,
+        nonce: u64,
+        max_fee: u128,
+        rule: &RuleCheck,
+        acl: &mut A,
+    ) -> Result<Disagreement>
+    where
+        A: FnMut(&TxRequest, &RawTx) -> Result<AclVerdict>,
+    {
+        let mut current = case.clone();
+        let mut i = 0;
+        while i < current.mutations.len() {
+            let mut candidate = current.clone();
+            candidate.mutations.remove(i);
+            let (acl_v, payload) = self.eval_acl(&candidate, nonce, max_fee, acl).await?;
+            if acl_v.disagrees(rule.check_all(std::slice::from_ref(&payload))?[0]) {
+                current = candidate;
+            } else {
+                i += 1;
+            }
+        }
+        let (acl_v, signer_json) = self.eval_acl(&current, nonce, max_fee, acl).await?;
+        let rule_allowed = rule.check_all(std::slice::from_ref(&signer_json))?[0];
+        Ok(Disagreement {
+            seed_index: current.seed_index,
+            request: self.request(&current),
+            mutations: current.mutations,
+            acl: acl_v,
+            rule_allowed,
+            signer_json,
+        })
+    }
+
+    async fn eval_acl<A>(
+        &self,
+        case: &Case,
+        nonce: u64,
+        max_fee: u128,
+        acl: &mut A,
+    ) -> Result<(AclVerdict, Value)>
+    where
+        A: FnMut(&TxRequest, &RawTx) -> Result<AclVerdict>,
+    {
+        let req = self.request(case);
+        let tx: TxEip1559 = CoboSafeBuilder::new(self.cobosafe, self.chain_id)
+            .build_txs(std::slice::from_ref(&req), nonce, max_fee, 1)?
+            .remove(0);
+        let payload = tx_to_signer_json(&tx, self.signer.address(), self.chain_id);
+        let raw = self.signer.sign(tx).await?;
+        Ok((acl(&req, &raw)?, payload))
+    }
+
+    fn request(&self, case: &Case) -> TxRequest {
+        let mut req = self.seeds[case.seed_index].clone();
+        for m in &case.mutations {
+            m.apply(&mut req);
+        }
+        req
+    }
+
+    fn gen_case(&self, rng: &mut SplitMix64) -> Case {
+        let seed_index = rng.below(self.seeds.len());
+        let seed = &self.seeds[seed_index];
+        let mutations = (0..1 + rng.below(3))
+            .map(|_| self.gen_mutation(seed, rng))
+            .collect();
+        Case {
+            seed_index,
+            mutations,
+        }
+    }
+
+    fn gen_mutation(&self, seed: &TxRequest, rng: &mut SplitMix64) -> Mutation {
+        let words = seed.data.len().saturating_sub(4) / 32;
+        match rng.below(4) {
+            0 => {
+                let mut targets = self.targets.clone();
+                targets.push(seed.to);
+                targets.push(Address::from_word(rng.word()));
+                Mutation::Target(targets[rng.below(targets.len())])
+            }
+            1 if seed.data.len() >= 4 => {
+                let mut selectors = self.selectors.clone();
+                selectors.push(seed.data[..4].try_into().expect("4 bytes"));
+                selectors.push(rng.word()[..4].try_into().expect("4 bytes"));
+                Mutation::Selector(selectors[rng.below(selectors.len())])
+            }
+            2 if words > 0 => {
+                let index = rng.below(words);
+                let start = 4 + index * 32;
+                let orig = U256::from_be_slice(&seed.data[start..start + 32]);
+                let value = self.word_near(orig, rng);
+                Mutation::Word {
+                    index,
+                    value: value.into(),
+                }
+            }
+            _ => Mutation::Value(match rng.below(3) {
+                0 => U256::ZERO,
+                1 => seed.value + U256::from(1),
+                _ => U256::from(rng.next()),
+            }),
+        }
+    }
+
+    /// 允许值附近的参数：±1、0、最大值、放大、地址候选、随机。
+    fn word_near(&self, orig: U256, rng: &mut SplitMix64) -> U256 {
+        match rng.below(7) {
+            0 => orig.wrapping_add(U256::from(1)),
+            1 => orig.wrapping_sub(U256::from(1)),
+            2 => U256::ZERO,
+            3 => U256::MAX,
+            4 => orig.saturating_mul(U256::from(2 + rng.below(1000))),
+            5 if !self.targets.is_empty() => {
+                let addr = self.targets[rng.below(self.targets.len())];
+                U256::from_be_slice(addr.into_word().as_slice())
+            }
+            _ => U256::from_be_bytes(rng.word().0),
+        }
+    }
+}
+
+/// 可复现的 splitmix64。
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn word(&mut self) -> B256 {
+        let mut w = [0u8; 32];
+        for chunk in w.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next().to_be_bytes());
+        }
+        B256::from(w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::MockChain;
+    use alloy::primitives::{address, Bytes};
+
+    const TARGET: Address = address!("1111111111111111111111111111111111111111");
+    const SELECTOR: [u8; 4] = [0xb6, 0xb5, 0x5f, 0x25];
+
+    fn deposit(amount: u64) -> TxRequest {
+        let mut data = SELECTOR.to_vec();
+        data.extend_from_slice(&U256::from(amount).to_be_bytes::<32>());
+        TxRequest {
+            to: TARGET,
+            value: U256::ZERO,
+            data: Bytes::from(data),
+            gas_limit: 100_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn finds_and_minimises_missing_amount_check() {
+        // ACL：只允许 TARGET.deposit(amount <= 100)；rule 漏掉了金额上限
+        let acl = |req: &TxRequest, _: &RawTx| {
+            let amount = U256::from_be_slice(&req.data[4..36]);
+            let allowed =
+                req.to == TARGET && req.data[..4] == SELECTOR && amount <= U256::from(100);
+            Ok(AclVerdict {
+                outcome: match allowed {
+                    true => AclOutcome::Allowed,
+                    false => AclOutcome::Rejected,
+                },
+                revert_reason: (!allowed).then(|| "Unauthorized".to_string()),
+            })
+        };
+        let target_hex = alloy::hex::encode(TARGET);
+        let selector_hex = alloy::hex::encode(SELECTOR);
+        let rule = RuleCheck::func(move |js| {
+            let input = js["content"]["transaction"]["input"].as_str().unwrap();
+            input.contains(&target_hex) && input.contains(&selector_hex)
+        });
+
+        let fuzzer = AclFuzzer::new(Address::repeat_byte(0xc0), 1, vec![deposit(50)])
+            .with_targets(vec![address!("2222222222222222222222222222222222222222")])
+            .with_cases(100);
+        let report = fuzzer.run_with(0, 10, &rule, acl).await.unwrap();
+
+        assert_eq!(report.cases, 100);
+        assert!(!report.disagreements.is_empty(), "{}", report.summary());
+        for d in &report.disagreements {
+            assert!(
+                d.acl.outcome == AclOutcome::Rejected && d.rule_allowed,
+                "{}",
+                d.reproducer()
+            );
+            assert!(
+                matches!(d.mutations.as_slice(), [Mutation::Word { index: 0, .. }]),
+                "{}",
+                d.reproducer()
+            );
+            assert!(U256::from_be_slice(&d.request.data[4..36]) > U256::from(100));
+        }
+
+        // 同一种子，结果可复现
+        let again = fuzzer.run_with(0, 10, &rule, acl).await.unwrap();
+        assert_eq!(again.summary(), report.summary());
+    }
+
+    const COBOSAFE: Address = address!("c0b0c0b0c0b0c0b0c0b0c0b0c0b0c0b0c0b0c0b0");
+
+    /// `revert Error(msg)`（`msg` 不超过 32 字节）。
+    fn revert_error(code: &mut Vec<u8>, msg: &str) {
+        let mut word = [0u8; 32];
+        word[..msg.len()].copy_from_slice(msg.as_bytes());
+        code.extend([0x7f, 0x08, 0xc3, 0x79, 0xa0]);
+        code.extend([0u8; 28]);
+        code.extend([0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x04, 0x52]);
+        code.extend([0x60, msg.len() as u8, 0x60, 0x24, 0x52, 0x7f]);
+        code.extend(word);
+        code.extend([0x60, 0x44, 0x52, 0x60, 0x64, 0x60, 0x00, 0xfd]);
+    }
+
+    /// `PUSH2 <dest> JUMPI`，返回待 [`jumpdest`] 回填的位置。
+    fn jumpi(code: &mut Vec<u8>) -> usize {
+        code.push(0x61);
+        let at = code.len();
+        code.extend([0x00, 0x00, 0x57]);
+        at
+    }
+
+    fn jumpdest(code: &mut Vec<u8>, at: usize) {
+        let pc = u16::try_from(code.len()).unwrap().to_be_bytes();
+        code[at..at + 2].copy_from_slice(&pc);
+        code.push(0x5b);
+    }
+
+    /// 桩 CoboSafe，只认单笔 `execTransaction`、inner data 为 `deposit(uint256)`：
+    /// 内置 ACL（inner `to` 须为 TARGET，金额 ≤ 100），通过后 CALL TARGET，
+    /// TARGET 失败时把它的 revert 原样抛出。
+    fn stub_cobosafe() -> Bytes {
+        // CALLDATALOAD(0x44) == TARGET（inner `to`）
+        let mut code = vec![0x60, 0x44, 0x35, 0x73];
+        code.extend(TARGET);
+        code.push(0x14);
+        let ok = jumpi(&mut code);
+        revert_error(&mut code, "target not allowed");
+        jumpdest(&mut code, ok);
+        // !(CALLDATALOAD(0x108) > 100)（deposit 金额）
+        code.extend([0x61, 0x01, 0x08, 0x35, 0x60, 0x64, 0x10, 0x15]);
+        let ok = jumpi(&mut code);
+        revert_error(&mut code, "amount out of range");
+        jumpdest(&mut code, ok);
+        // CALLDATACOPY(0, 0x104, 0x24); CALL(gas, TARGET, 0, 0, 0x24, 0, 0)
+        code.extend([0x60, 0x24, 0x61, 0x01, 0x04, 0x60, 0x00, 0x37]);
+        code.extend([
+            0x60, 0x00, 0x60, 0x00, 0x60, 0x24, 0x60, 0x00, 0x60, 0x00, 0x73,
+        ]);
+        code.extend(TARGET);
+        code.extend([0x5a, 0xf1]);
+        let ok = jumpi(&mut code);
+        // RETURNDATACOPY 后 REVERT
+        code.extend([0x3d, 0x60, 0x00, 0x60, 0x00, 0x3e, 0x3d, 0x60, 0x00, 0xfd]);
+        jumpdest(&mut code, ok);
+        code.push(0x00);
+        code.into()
+    }
+
+    /// 桩目标合约：`deposit(0)` revert，其余直接返回。
+    fn stub_target() -> Bytes {
+        let mut code = vec![0x60, 0x04, 0x35];
+        let ok = jumpi(&mut code);
+        revert_error(&mut code, "zero amount");
+        jumpdest(&mut code, ok);
+        code.push(0x00);
+        code.into()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn runs_rule_js_against_acl_on_fork() {
+        let chain = MockChain::start(1).await.unwrap();
+        chain.set_code(COBOSAFE, stub_cobosafe());
+        chain.set_code(TARGET, stub_target());
+        let sim = ForkSimulator::fork_for_simulation(chain.url(), None)
+            .await
+            .unwrap();
+
+        // rule.js 只检查 inner `to`，漏掉了金额上限
+        let rule = RuleJs::from_source(
+            r#"
+function check(dataStr) {
+    const input = JSON.parse(dataStr).content.transaction.input;
+    const to = input.slice(2 + 2 * 0x44 + 24, 2 + 2 * 0x64);
+    if (to.toLowerCase() !== "1111111111111111111111111111111111111111") return false;
+    return true;
+}
+"#,
+        );
+        let report = AclFuzzer::new(COBOSAFE, 1, vec![deposit(50)])
+            .with_targets(vec![address!("2222222222222222222222222222222222222222")])
+            .with_cases(100)
+            .run(&sim, &rule.into())
+            .await
+            .unwrap();
+
+        assert!(!report.disagreements.is_empty(), "{}", report.summary());
+        for d in &report.disagreements {
+            assert_eq!(d.acl.outcome, AclOutcome::Rejected, "{}", d.reproducer());
+            assert_eq!(
+                d.acl.revert_reason.as_deref(),
+                Some("amount out of range"),
+                "{}",
+                d.reproducer()
+            );
+            assert!(U256::from_be_slice(&d.request.data[4..36]) > U256::from(100));
+        }
+        // TARGET 自己 revert 的不算 ACL 拒绝
+        assert!(!report.inconclusive.is_empty(), "{}", report.summary());
+        for c in &report.inconclusive {
+            assert_eq!(c.revert_reason.as_deref(), Some("zero amount"));
+            assert_eq!(U256::from_be_slice(&c.request.data[4..36]), U256::ZERO);
+        }
+    }
+}
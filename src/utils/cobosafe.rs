@@ -1,7 +1,10 @@
 //! CoboSafe + FlatRoleManager + Safe module 的辅助函数。
 //!
 //! - **RPC 查询**：`query_safe`（查 CoboSafe 后面的 Safe 地址）
-//! - **生产流水线**：`submit_transactions`（build → sign → send 一条龙）
+//! - **生产流水线**：`submit_transactions`（build → sign → send 一条龙，基于
+//!   [`Pipeline`]；要换 builder / gas 策略 / dry-run 时直接用 [`Pipeline`]）、
+//!   `submit_transactions_with_nonces`（多 task 并发提交，共享 [`NonceManager`]）；
+//!   广播前要先 fork 模拟、跑断言时用 [`crate::pipeline::SimulationGate::submit`]
 //! - **Fork 辅助**（按 "admin 调 setter" 的模式，caller 传谁就由谁发起）：
 //!   - `set_authorizer` / `add_delegate` / `get_owner` / `get_safe`（CoboSafe）
 //!   - `add_roles` / `grant_roles`（FlatRoleManager）
@@ -10,7 +13,6 @@
 //!     setAuthorizer + addDelegate + enableModule）
 
 use alloy::{
-    consensus::TxEip1559,
     network::{AnyNetwork, TransactionBuilder},
     primitives::{Address, Bytes, TxKind, B256, U256},
     providers::{DynProvider, Provider},
@@ -22,7 +24,11 @@ use eyre::Result;
 use revm::context::TxEnv;
 use sha2::{Digest, Sha256};
 
-use crate::{CoboSafeBuilder, ForkSimulator, TxBuilder, TxRequest, TxSender, TxSigner};
+use crate::{
+    pipeline::{Fees, FixedNonce, NonceSource, Pipeline},
+    sender::NonceManager,
+    CoboSafeBuilder, ForkSimulator, TxRequest, TxSender, TxSigner,
+};
 
 sol! {
     // CoboSafeAccount（Cobo Argus）接口
@@ -66,6 +72,8 @@ pub async fn query_safe(
 ///
 /// nonce 自动从 `provider.get_transaction_count(signer.address())` 取。
 /// 返回 `sender` 的 hashes（Flashbots bundle 场景按目标区块数返回多个）。
+/// 失败时返回 [`PipelineError`](crate::pipeline::PipelineError)：`stage` 表明失败在哪一步（`Send` 说明可能已有交易发出），
+/// `source` 是原始错误（如 [`PolicyViolation`](crate::PolicyViolation)）。
 #[allow(clippy::too_many_arguments)]
 pub async fn submit_transactions<S: TxSender, Sg: TxSigner>(
     provider: &DynProvider<AnyNetwork>,
@@ -76,26 +84,70 @@ pub async fn submit_transactions<S: TxSender, Sg: TxSigner>(
     max_fee_wei: u128,
     priority_fee_wei: u128,
 ) -> Result<Vec<B256>> {
-    let operator = signer.address();
+    let nonce = provider.get_transaction_count(signer.address()).await?;
+    build_sign_send(
+        provider,
+        signer,
+        cobosafe,
+        sender,
+        requests,
+        FixedNonce(nonce),
+        max_fee_wei,
+        priority_fee_wei,
+    )
+    .await
+}
+
+/// 同 [`submit_transactions`]，但 nonce 从共享的 [`NonceManager`] 分配。
+///
+/// 同一 operator 的多个 task 并发提交时用这个：nonce 按 pending 计数初始化、互不冲突；
+/// 发送前（build / 签名）失败时 nonce 自动归还给下一次提交复用，进入发送阶段后不再归还，
+/// 见 [`Pipeline`]。
+#[allow(clippy::too_many_arguments)]
+pub async fn submit_transactions_with_nonces<S: TxSender, Sg: TxSigner>(
+    provider: &DynProvider<AnyNetwork>,
+    nonces: &NonceManager,
+    signer: &Sg,
+    cobosafe: Address,
+    sender: &S,
+    requests: &[TxRequest],
+    max_fee_wei: u128,
+    priority_fee_wei: u128,
+) -> Result<Vec<B256>> {
+    build_sign_send(
+        provider,
+        signer,
+        cobosafe,
+        sender,
+        requests,
+        nonces.clone(),
+        max_fee_wei,
+        priority_fee_wei,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn build_sign_send<S: TxSender, Sg: TxSigner, N: NonceSource>(
+    provider: &DynProvider<AnyNetwork>,
+    signer: &Sg,
+    cobosafe: Address,
+    sender: &S,
+    requests: &[TxRequest],
+    nonces: N,
+    max_fee_wei: u128,
+    priority_fee_wei: u128,
+) -> Result<Vec<B256>> {
     let chain_id = provider.get_chain_id().await?;
     let builder = CoboSafeBuilder::new(cobosafe, chain_id);
-    let nonce = provider.get_transaction_count(operator).await?;
-    let unsigned: Vec<TxEip1559> =
-        builder.build_txs(requests, nonce, max_fee_wei, priority_fee_wei)?;
-    let tx = unsigned
-        .into_iter()
-        .next()
-        .expect("CoboSafeBuilder must return one tx");
-    tracing::info!("nonce={nonce}, gas_limit={}", tx.gas_limit);
-    tracing::info!("signing ...");
-    let raw = signer.sign(tx).await?;
-    tracing::info!("raw tx:     0x{}", alloy::hex::encode(&raw.0));
-    tracing::info!("broadcasting ...");
-    let hashes = sender.send_txs(&[raw]).await?;
-    for h in &hashes {
-        tracing::info!("tx hash: {h}");
-    }
-    Ok(hashes)
+    let fees = Fees {
+        max_fee_per_gas: max_fee_wei,
+        max_priority_fee_per_gas: priority_fee_wei,
+    };
+    let outcome = Pipeline::new(&builder, signer, sender, fees, nonces)
+        .submit(requests)
+        .await?;
+    Ok(outcome.hashes)
 }
 
 // ── Fork 辅助 ──
@@ -353,4 +405,54 @@ mod tests {
         let hashed = Sha256::digest(name.as_bytes());
         assert_eq!(&b[..], &hashed[..]);
     }
+
+    /// relay 超时：不知道交易有没有发出去。
+    struct TimeoutSender;
+
+    impl TxSender for TimeoutSender {
+        async fn send_txs(&self, _txs: &[crate::RawTx]) -> Result<Vec<B256>> {
+            eyre::bail!("relay timed out")
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_send_keeps_nonce_and_reports_stage() {
+        use crate::{
+            pipeline::{PipelineError, Stage},
+            utils::testing::{testing_delegate, MockChain},
+        };
+        use alloy::providers::ProviderBuilder;
+
+        let chain = MockChain::start(1).await.unwrap();
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_http(chain.url().parse().unwrap())
+            .erased();
+        let (signer, from) = testing_delegate();
+        let nonces = NonceManager::new(provider.clone());
+        let request = TxRequest {
+            to: Address::repeat_byte(0x11),
+            value: U256::ZERO,
+            data: Bytes::new(),
+            gas_limit: 50_000,
+        };
+
+        let err = submit_transactions_with_nonces(
+            &provider,
+            &nonces,
+            &signer,
+            Address::repeat_byte(0xc0),
+            &TimeoutSender,
+            &[request],
+            2_000_000_000,
+            1_000_000_000,
+        )
+        .await
+        .unwrap_err();
+        let err = err.downcast_ref::<PipelineError>().unwrap();
+        assert_eq!(err.stage, Stage::Send);
+        assert!(err.source.to_string().contains("timed out"));
+        // nonce 0 可能已经在路上，不能再分出去
+        assert_eq!(nonces.reserve(from).await.unwrap().nonce(), 1);
+    }
 }
@@ -1,7 +1,9 @@
+pub mod acl_fuzz;
 pub mod cobosafe;
 pub mod decimal;
 pub mod erc20;
 pub mod foundry;
+pub mod rule_js;
 pub mod safe_tx_builder;
 pub mod signer_json;
 pub mod testing;
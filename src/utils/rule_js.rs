@@ -0,0 +1,400 @@
+//! 在 Rust 里直接跑 cs-signer 的 rule.js `check(dataStr)`，不再需要单独的
+//! `test_rule.js` 脚本。
+//!
+//! 执行器是内嵌的 [boa](https://boajs.dev) 引擎（纯 Rust，不依赖 node），输入是
+//! [`crate::utils::signer_json`] 生成的 `jsStruct`。一批输入共用一个 JS context，
+//! 全局只有 ECMAScript 内置对象和 `console`，没有 node 的 `require` / `Buffer`。
+//!
+//! ```ignore
+//! use flashseal_rs::utils::{rule_js::RuleJs, signer_json::tx_to_signer_json};
+//! let rule = RuleJs::load("signer/projects/TEST/rule.js")?;
+//! rule.expect(
+//!     &[tx_to_signer_json(&deposit, delegate, 1)],
+//!     &[tx_to_signer_json(&drain, delegate, 1)],
+//! )?;
+//! ```
+//!
+//! 拒签分支：加载前先用 boa 的 parser 解析 rule.js，找出 AST 里所有
+//! `return false` / `return (false)`，把其中的 `false` 字面量原位换成记录行号的调用，
+//! 所以 [`RuleVerdict::branch`] 能指出是哪一行拒的；字符串、注释里的同样文本不受影响。
+//! `console.log` 的输出一并收集。其它形式的拒绝（`return x && y`、抛异常）只能拿到
+//! 日志 / 异常信息。
+
+use std::{collections::BTreeSet, convert::Infallible, ops::ControlFlow, path::Path};
+
+use alloy::{consensus::TxEip1559, primitives::Address};
+use boa_engine::{
+    ast::{
+        expression::{literal::LiteralKind, Expression},
+        scope::Scope,
+        statement::Return,
+        visitor::{VisitWith, Visitor},
+        Script, Spanned,
+    },
+    interner::Interner,
+    js_string,
+    parser::Parser,
+    Context, JsError, JsString, JsValue, Source,
+};
+use eyre::{eyre, Result, WrapErr};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::signer_json::tx_to_signer_json;
+
+/// 在 rule.js 之前执行：提供 `console` 和拒签分支的记录函数。
+const PRELUDE: &str = r#"
+var __flashseal = { logs: [], branch: null };
+var console = (() => {
+    const log = (...a) => { __flashseal.logs.push(a.map(String).join(" ")); };
+    return { log, info: log, warn: log, error: log, debug: log };
+})();
+function __flashsealReject(line) { __flashseal.branch = line; return false; }
+"#;
+
+/// rule.js 拒签时命中的 `return false`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleBranch {
+    /// 1-based 行号。
+    pub line: usize,
+    /// 该行源码（去掉首尾空白）。
+    pub code: String,
+}
+
+/// 一次 `check` 的结果。
+#[derive(Debug, Clone)]
+pub struct RuleVerdict {
+    /// `check` 严格返回 `true` 才算放行（与 cs-signer 一致）。
+    pub allowed: bool,
+    pub logs: Vec<String>,
+    pub branch: Option<RuleBranch>,
+    /// `check` 抛出的异常。
+    pub error: Option<String>,
+}
+
+impl RuleVerdict {
+    /// 一行可读的拒签原因，用于断言失败信息。
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(b) = &self.branch {
+            parts.push(format!("line {}: `{}`", b.line, b.code));
+        }
+        if let Some(e) = &self.error {
+            parts.push(format!("threw {}", e.lines().next().unwrap_or_default()));
+        }
+        if !self.logs.is_empty() {
+            parts.push(format!("logs: {}", self.logs.join(" | ")));
+        }
+        if parts.is_empty() {
+            parts.push(if self.allowed { "allowed" } else { "rejected" }.into());
+        }
+        parts.join("; ")
+    }
+}
+
+/// 一次 `check` 之后 `__flashseal` 里的记录。
+#[derive(Deserialize)]
+struct CheckState {
+    logs: Vec<String>,
+    branch: Option<usize>,
+}
+
+/// 一份 rule.js。
+pub struct RuleJs {
+    name: String,
+    source: String,
+}
+
+impl RuleJs {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        Ok(Self {
+            name: path.display().to_string(),
+            ..Self::from_source(source)
+        })
+    }
+
+    pub fn from_source(source: impl Into<String>) -> Self {
+        Self {
+            name: "rule.js".into(),
+            source: source.into(),
+        }
+    }
+
+    pub fn check(&self, js_struct: &Value) -> Result<RuleVerdict> {
+        let mut verdicts = self.check_all(std::slice::from_ref(js_struct))?;
+        Ok(verdicts.remove(0))
+    }
+
+    /// 对 EIP-1559 交易跑 `check`，`account` 为签名者。
+    pub fn check_tx(&self, tx: &TxEip1559, account: Address) -> Result<RuleVerdict> {
+        self.check(&tx_to_signer_json(tx, account, tx.chain_id))
+    }
+
+    /// 在同一个 JS context 里依次检查 `inputs`，按顺序返回。
+    pub fn check_all(&self, inputs: &[Value]) -> Result<Vec<RuleVerdict>> {
+        let source = self.instrumented()?;
+        let mut ctx = Context::default();
+        eval(&mut ctx, PRELUDE).map_err(|e| eyre!("rule.js prelude failed: {e}"))?;
+        eval(&mut ctx, &source).map_err(|e| eyre!("failed to load {}: {e}", self.name))?;
+        let check = ctx
+            .global_object()
+            .get(js_string!("check"), &mut ctx)
+            .ok()
+            .and_then(|f| f.as_callable())
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to load {}: rule.js does not define check(dataStr)",
+                    self.name
+                )
+            })?;
+
+        let lines: Vec<&str> = self.source.lines().collect();
+        inputs
+            .iter()
+            .map(|input| {
+                eval(
+                    &mut ctx,
+                    "__flashseal.logs = []; __flashseal.branch = null;",
+                )
+                .map_err(|e| eyre!("rule.js harness failed: {e}"))?;
+                let data = JsValue::from(JsString::from(input.to_string().as_str()));
+                let (allowed, error) = match check.call(&JsValue::undefined(), &[data], &mut ctx) {
+                    Ok(ret) => (ret.as_boolean() == Some(true), None),
+                    Err(e) => (false, Some(describe_error(e, &mut ctx))),
+                };
+                let state = eval(&mut ctx, "JSON.stringify(__flashseal)")
+                    .and_then(|v| v.to_string(&mut ctx))
+                    .map_err(|e| eyre!("rule.js harness failed: {e}"))?;
+                let state: CheckState = serde_json::from_str(&state.to_std_string_escaped())
+                    .wrap_err("unexpected state from rule.js harness")?;
+                Ok(RuleVerdict {
+                    allowed,
+                    logs: state.logs,
+                    branch: state.branch.map(|line| RuleBranch {
+                        line,
+                        code: lines
+                            .get(line - 1)
+                            .map(|l| l.trim().to_string())
+                            .unwrap_or_default(),
+                    }),
+                    error,
+                })
+            })
+            .collect()
+    }
+
+    /// 断言 `accepted` 全部放行、`rejected` 全部拒签；不符时的错误列出每条的拒签原因。
+    pub fn expect(&self, accepted: &[Value], rejected: &[Value]) -> Result<()> {
+        let inputs: Vec<Value> = accepted.iter().chain(rejected).cloned().collect();
+        let verdicts = self.check_all(&inputs)?;
+        let (ok, bad) = verdicts.split_at(accepted.len());
+
+        let mut mismatches = Vec::new();
+        for (i, v) in ok.iter().enumerate().filter(|(_, v)| !v.allowed) {
+            mismatches.push(format!("accepted[{i}] was rejected ({})", v.describe()));
+        }
+        for (i, v) in bad.iter().enumerate().filter(|(_, v)| v.allowed) {
+            mismatches.push(format!("rejected[{i}] was allowed ({})", v.describe()));
+        }
+        eyre::ensure!(
+            mismatches.is_empty(),
+            "{} disagrees with expectations:\n  {}",
+            self.name,
+            mismatches.join("\n  ")
+        );
+        Ok(())
+    }
+
+    /// 把 AST 里每个 `return false` 的 `false` 换成带行号的 `__flashsealReject(..)`，
+    /// 其余源码（包括行号）不变。
+    fn instrumented(&self) -> Result<String> {
+        let mut interner = Interner::default();
+        let script: Script = Parser::new(Source::from_bytes(&self.source))
+            .parse_script(&Scope::new_global(), &mut interner)
+            .map_err(|e| eyre!("failed to load {}: {e}", self.name))?;
+        let mut finder = RejectFinder::default();
+        let _ = script.visit_with(&mut finder);
+
+        // 与 boa lexer 一致地数行列：列按 code point 计，1-based。
+        let mut out = String::with_capacity(self.source.len());
+        let (mut line, mut col) = (1u32, 1u32);
+        let mut chars = self.source.chars().peekable();
+        while let Some(c) = chars.next() {
+            if finder.positions.contains(&(line, col)) {
+                out.push_str(&format!("__flashsealReject({line})"));
+                // 跳过 `false` 剩下的 4 个字符
+                for _ in 0..4 {
+                    chars.next();
+                }
+                col += 5;
+                continue;
+            }
+            out.push(c);
+            match c {
+                '\r' if chars.peek() == Some(&'\n') => {
+                    out.push(chars.next().expect("peeked"));
+                    (line, col) = (line + 1, 1);
+                }
+                '\r' | '\n' | '\u{2028}' | '\u{2029}' => (line, col) = (line + 1, 1),
+                _ => col += 1,
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn eval(ctx: &mut Context, source: &str) -> std::result::Result<JsValue, JsError> {
+    ctx.eval(Source::from_bytes(source))
+}
+
+/// 异常转成 `Name: message`，JS 抛的是 Error 对象时后面接上它的 `stack`。
+fn describe_error(e: JsError, ctx: &mut Context) -> String {
+    let fallback = e.to_string();
+    let Ok(value) = e.into_opaque(ctx) else {
+        return fallback;
+    };
+    let Ok(head) = value.to_string(ctx) else {
+        return fallback;
+    };
+    let mut out = head.to_std_string_escaped();
+    let stack = value
+        .as_object()
+        .and_then(|o| o.get(js_string!("stack"), ctx).ok())
+        .and_then(|s| s.as_string().map(|s| s.to_std_string_escaped()));
+    if let Some(stack) = stack.filter(|s| !s.trim().is_empty()) {
+        out.push('\n');
+        out.push_str(stack.trim_end());
+    }
+    out
+}
+
+/// 收集 AST 里 `return false`（可带括号）中 `false` 字面量的起始行列。
+#[derive(Default)]
+struct RejectFinder {
+    positions: BTreeSet<(u32, u32)>,
+}
+
+impl<'ast> Visitor<'ast> for RejectFinder {
+    type BreakTy = Infallible;
+
+    fn visit_return(&mut self, node: &'ast Return) -> ControlFlow<Infallible> {
+        let mut target = node.target();
+        while let Some(Expression::Parenthesized(p)) = target {
+            target = Some(p.expression());
+        }
+        if let Some(Expression::Literal(lit)) = target
+            && matches!(lit.kind(), LiteralKind::Bool(false))
+        {
+            let pos = lit.span().start();
+            self.positions
+                .insert((pos.line_number(), pos.column_number()));
+        }
+        node.visit_with(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{address, TxKind, U256};
+    use serde_json::json;
+
+    const RULE: &str = r#"
+const TARGET = "0x1111111111111111111111111111111111111111";
+function check(dataStr) {
+    const data = JSON.parse(dataStr);
+    if (data.type !== "transaction") return false;
+    const tx = data.content.transaction;
+    if (tx.to.toLowerCase() !== TARGET) {
+        console.log("to != target");
+        return false;
+    }
+    if (BigInt(tx.value) > 0n) return false;
+    return true;
+}
+"#;
+
+    fn tx(to: Address, value: u64) -> Value {
+        let tx = TxEip1559 {
+            chain_id: 1,
+            to: TxKind::Call(to),
+            value: U256::from(value),
+            ..Default::default()
+        };
+        tx_to_signer_json(&tx, Address::ZERO, 1)
+    }
+
+    #[test]
+    fn reports_rejecting_branch() {
+        let rule = RuleJs::from_source(RULE);
+        let target = address!("1111111111111111111111111111111111111111");
+        let other = address!("2222222222222222222222222222222222222222");
+
+        rule.expect(&[tx(target, 0)], &[tx(target, 1), tx(other, 0)])
+            .unwrap();
+
+        let v = rule.check(&tx(other, 0)).unwrap();
+        assert!(!v.allowed);
+        assert_eq!(v.branch.as_ref().unwrap().line, 9);
+        assert_eq!(v.logs, vec!["to != target"]);
+
+        let err = rule.expect(&[tx(target, 1)], &[]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("line 11: `if (BigInt(tx.value) > 0n) return false;`"),
+            "{err}"
+        );
+
+        let err = RuleJs::from_source("function nope() {}")
+            .check(&tx(target, 0))
+            .unwrap_err();
+        assert!(err.to_string().contains("does not define check"), "{err}");
+    }
+
+    #[test]
+    fn instruments_only_ast_return_false() {
+        // 字符串 / 注释 / 模板里的 "return false" 不能被改写；中文注释不影响列号。
+        let rule = RuleJs::from_source(
+            "function check(dataStr) {\r\n\
+             \x20   const msg = \"return false\"; // 不要 return false\r\n\
+             \x20   /* return false */ const t = `return false`;\r\n\
+             \x20   if (dataStr.length > 100) { /* 太长 */ return (false); }\r\n\
+             \x20   if (dataStr.includes(\"boom\")) throw new TypeError(\"boom\");\r\n\
+             \x20   console.log(msg, t);\r\n\
+             \x20   return dataStr === '\"ok\"';\r\n\
+             }",
+        );
+        let source = rule.instrumented().unwrap();
+        assert_eq!(source.matches("__flashsealReject").count(), 1, "{source}");
+        assert!(
+            source.contains("return (__flashsealReject(4)); }"),
+            "{source}"
+        );
+
+        let verdicts = rule
+            .check_all(&[json!("ok"), json!("x".repeat(200)), json!("boom")])
+            .unwrap();
+        assert!(verdicts[0].allowed);
+        assert_eq!(verdicts[0].logs, vec!["return false return false"]);
+        assert_eq!(verdicts[1].branch.as_ref().unwrap().line, 4);
+        assert!(verdicts[1].logs.is_empty());
+        assert!(!verdicts[2].allowed && verdicts[2].branch.is_none());
+        assert!(
+            verdicts[2]
+                .error
+                .as_deref()
+                .unwrap()
+                .contains("TypeError: boom"),
+            "{:?}",
+            verdicts[2].error
+        );
+
+        let err = RuleJs::from_source("function check( {")
+            .check(&json!(1))
+            .unwrap_err();
+        assert!(err.to_string().contains("failed to load rule.js"), "{err}");
+    }
+}
@@ -32,14 +32,28 @@
 //!
 //! 典型用途：在 Rust fork 测试里构造一堆 tx，dump 成这种 JSON 交给
 //! `node test_rule.js` 去验证 rule.js 是否符合预期（rule.js 与 ACL 的一致性
-//! 校验）。
+//! 校验），或直接用 [`crate::utils::rule_js::RuleJs`] 在测试里断言。
 //!
-//! 当前只覆盖 EIP-1559（`type=0x02`）。legacy / EIP-2930 / EIP-4844 待需再加。
+//! 交易当前只覆盖 EIP-1559（`type=0x02`）。legacy / EIP-2930 / EIP-4844 待需再加。
+//!
+//! 消息签名的 `jsStruct` 与交易不同，`content` 就是 [`crate::RemoteSigner`] 发给
+//! `/v1/sign/message` / `/v1/sign/typed_data` 的 `data` 原文（两边都调用
+//! [`message_content`] / [`typed_data_content`]）：`account` 为 checksum 地址，
+//! `typed_data` 与交易请求里的 `transaction` 一样是 JSON **字符串**，rule.js 需要
+//! 自己 `JSON.parse`。
+//!
+//! ```json
+//! { "type": "message",    "content": { "account": "0xAbC...", "message": "0x..." } }
+//! { "type": "typed_data", "content": { "chain_id": 1, "account": "0xAbC...",
+//!                                      "typed_data": "{\"types\":...,\"primaryType\":...}" } }
+//! ```
 
 use alloy::{
     consensus::TxEip1559,
+    dyn_abi::TypedData,
     primitives::{Address, TxKind},
 };
+use eyre::{eyre, Result};
 use serde_json::{json, Value};
 
 /// 构造 rule.js 一次 check 所需的顶层 `jsStruct`。
@@ -57,6 +71,41 @@ pub fn tx_to_signer_json(tx: &TxEip1559, account: Address, chain_id: u64) -> Val
     })
 }
 
+/// EIP-191 personal_sign 的 `jsStruct`。`message` 以 0x hex 传给 rule.js。
+pub fn message_to_signer_json(message: &[u8], account: Address) -> Value {
+    json!({ "type": "message", "content": message_content(message, account) })
+}
+
+/// `/v1/sign/message` 请求的 `data`，即 message `jsStruct` 的 `content`。
+pub fn message_content(message: &[u8], account: Address) -> Value {
+    json!({
+        "account": account.to_string(),
+        "message": format!("0x{}", alloy::hex::encode(message)),
+    })
+}
+
+/// EIP-712 typed data 的 `jsStruct`。`typed_data` 是标准 `eth_signTypedData_v4`
+/// JSON 序列化后的字符串（rule.js 解析后按 `primaryType` /
+/// `domain.verifyingContract` / `message` 做判断）。
+pub fn typed_data_to_signer_json(typed: &TypedData, account: Address) -> Result<Value> {
+    Ok(json!({ "type": "typed_data", "content": typed_data_content(typed, account)? }))
+}
+
+/// `/v1/sign/typed_data` 请求的 `data`，即 typed data `jsStruct` 的 `content`。
+///
+/// `chain_id` 取自 `domain.chainId`，domain 没带时为 0；超出 u64 时报错。
+pub fn typed_data_content(typed: &TypedData, account: Address) -> Result<Value> {
+    let chain_id = match typed.domain.chain_id {
+        Some(c) => u64::try_from(c).map_err(|_| eyre!("typed data chainId {c} exceeds u64"))?,
+        None => 0,
+    };
+    Ok(json!({
+        "chain_id": chain_id,
+        "account": account.to_string(),
+        "typed_data": serde_json::to_string(typed)?,
+    }))
+}
+
 /// EIP-1559 tx 的内层 JSON。与 cs-signer `Transaction` struct 字段对齐。
 fn tx_inner(tx: &TxEip1559, account: Address) -> Value {
     let to = match tx.to {
@@ -138,4 +187,53 @@ mod tests {
         let v = tx_to_signer_json(&tx, account, 1);
         assert_eq!(v["content"]["transaction"]["to"], "");
     }
+
+    #[test]
+    fn typed_data_payload_keeps_primary_type_and_domain() {
+        let typed: TypedData = serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Permit": [
+                    { "name": "spender", "type": "address" },
+                    { "name": "value", "type": "uint256" }
+                ]
+            },
+            "primaryType": "Permit",
+            "domain": {
+                "chainId": 1,
+                "verifyingContract": "0x3333333333333333333333333333333333333333"
+            },
+            "message": {
+                "spender": "0x4444444444444444444444444444444444444444",
+                "value": "1000"
+            }
+        }))
+        .unwrap();
+        let account = address!("1111111111111111111111111111111111111111");
+        let v = typed_data_to_signer_json(&typed, account).unwrap();
+
+        assert_eq!(v["type"], "typed_data");
+        assert_eq!(v["content"]["chain_id"], 1);
+        assert_eq!(v["content"]["account"], account.to_string());
+        let t: Value = serde_json::from_str(v["content"]["typed_data"].as_str().unwrap()).unwrap();
+        assert_eq!(t["primaryType"], "Permit");
+        assert_eq!(
+            t["domain"]["verifyingContract"],
+            "0x3333333333333333333333333333333333333333"
+        );
+        assert_eq!(t["message"]["spender"], "0x4444444444444444444444444444444444444444");
+
+        // 调用方给的 chainId 超出 u64：报错而不是 panic
+        let mut hostile = typed.clone();
+        hostile.domain.chain_id = Some(U256::MAX);
+        let err = typed_data_to_signer_json(&hostile, account).unwrap_err();
+        assert!(err.to_string().contains("exceeds u64"), "{err}");
+
+        let m = message_to_signer_json(b"hi", account);
+        assert_eq!(m["type"], "message");
+        assert_eq!(m["content"]["message"], "0x6869");
+    }
 }
@@ -0,0 +1,194 @@
+//! 进程内的最小链节点桩：够 [`ForkSimulator::fork`](crate::ForkSimulator::fork) 拉状态、
+//! 够 [`RpcSender`](crate::RpcSender) 广播，用来离线测试 "fork → 模拟 → 发送" 的流程。
+//!
+//! ```ignore
+//! use flashseal_rs::utils::testing::MockChain;
+//! let chain = MockChain::start(1).await?;
+//! chain.set_balance(delegate, U256::from(10).pow(U256::from(18)));
+//! chain.set_code(target, runtime_bytecode);
+//! let sim = ForkSimulator::fork(chain.url(), None).await?;
+//! ```
+//!
+//! 只有一个区块（所有 block tag 返回同一个），状态不会因为广播而执行，
+//! 只有发送方 nonce 加一。
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use alloy::{
+    consensus::{transaction::SignerRecoverable, TxEnvelope},
+    eips::Decodable2718,
+    primitives::{keccak256, Address, Bytes, B256, U256},
+};
+use eyre::Result;
+use serde_json::{json, Value};
+
+use super::http::{serve_json_rpc, StubServer};
+
+#[derive(Default, Clone)]
+struct Account {
+    balance: U256,
+    nonce: u64,
+    code: Bytes,
+}
+
+struct ChainState {
+    chain_id: u64,
+    block_number: u64,
+    base_fee: u64,
+    accounts: HashMap<Address, Account>,
+    storage: HashMap<(Address, U256), U256>,
+    sent: Vec<Bytes>,
+}
+
+/// 单区块的链节点桩，状态由 `set_*` 预置。
+pub struct MockChain {
+    server: StubServer,
+    state: Arc<Mutex<ChainState>>,
+}
+
+impl MockChain {
+    /// 区块号 100、basefee 1 gwei 起步。
+    pub async fn start(chain_id: u64) -> Result<Self> {
+        let state = Arc::new(Mutex::new(ChainState {
+            chain_id,
+            block_number: 100,
+            base_fee: 1_000_000_000,
+            accounts: HashMap::new(),
+            storage: HashMap::new(),
+            sent: Vec::new(),
+        }));
+        let handler_state = state.clone();
+        let server = serve_json_rpc(move |method, params| {
+            let mut state = handler_state.lock().expect("mock chain poisoned");
+            handle(&mut state, method, params)
+        })
+        .await?;
+        Ok(Self { server, state })
+    }
+
+    pub fn url(&self) -> &str {
+        self.server.url()
+    }
+
+    pub fn set_balance(&self, addr: Address, balance: U256) {
+        self.lock().accounts.entry(addr).or_default().balance = balance;
+    }
+
+    pub fn set_nonce(&self, addr: Address, nonce: u64) {
+        self.lock().accounts.entry(addr).or_default().nonce = nonce;
+    }
+
+    pub fn set_code(&self, addr: Address, code: Bytes) {
+        self.lock().accounts.entry(addr).or_default().code = code;
+    }
+
+    pub fn set_storage(&self, addr: Address, slot: U256, value: U256) {
+        self.lock().storage.insert((addr, slot), value);
+    }
+
+    pub fn set_base_fee(&self, base_fee: u64) {
+        self.lock().base_fee = base_fee;
+    }
+
+    /// `eth_sendRawTransaction` 收到的交易，按到达顺序。
+    pub fn sent_txs(&self) -> Vec<Bytes> {
+        self.lock().sent.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ChainState> {
+        self.state.lock().expect("mock chain poisoned")
+    }
+}
+
+fn handle(
+    state: &mut ChainState,
+    method: &str,
+    params: &Value,
+) -> std::result::Result<Value, (i64, String)> {
+    let addr = || -> std::result::Result<Address, (i64, String)> {
+        params[0]
+            .as_str()
+            .and_then(|s| s.parse().ok())
+            .ok_or((-32602, "invalid address".to_string()))
+    };
+    let account = |addr| state.accounts.get(&addr).cloned().unwrap_or_default();
+    match method {
+        "eth_chainId" => Ok(json!(format!("{:#x}", state.chain_id))),
+        "eth_blockNumber" => Ok(json!(format!("{:#x}", state.block_number))),
+        "eth_getBlockByNumber" | "eth_getBlockByHash" => Ok(block_json(state)),
+        "eth_feeHistory" => Ok(json!({
+            "oldestBlock": format!("{:#x}", state.block_number),
+            "baseFeePerGas": [
+                format!("{:#x}", state.base_fee),
+                format!("{:#x}", state.base_fee),
+            ],
+            "gasUsedRatio": [0.5],
+        })),
+        "eth_getBalance" => Ok(json!(account(addr()?).balance)),
+        "eth_getTransactionCount" => Ok(json!(format!("{:#x}", account(addr()?).nonce))),
+        "eth_getCode" => Ok(json!(account(addr()?).code)),
+        "eth_getStorageAt" => {
+            let slot: U256 = params[1]
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .ok_or((-32602, "invalid slot".to_string()))?;
+            let value = state
+                .storage
+                .get(&(addr()?, slot))
+                .copied()
+                .unwrap_or_default();
+            Ok(json!(B256::from(value)))
+        }
+        "eth_sendRawTransaction" => {
+            let raw: Bytes = params[0]
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .ok_or((-32602, "invalid raw tx".to_string()))?;
+            let envelope = TxEnvelope::decode_2718(&mut raw.as_ref())
+                .map_err(|e| (-32602, format!("decode tx: {e}")))?;
+            let from = envelope
+                .recover_signer()
+                .map_err(|e| (-32602, format!("recover signer: {e:?}")))?;
+            state.accounts.entry(from).or_default().nonce += 1;
+            let hash = keccak256(&raw);
+            state.sent.push(raw);
+            Ok(json!(hash))
+        }
+        _ => Err((-32601, format!("method {method} not supported"))),
+    }
+}
+
+fn block_json(state: &ChainState) -> Value {
+    let hash = keccak256(state.block_number.to_be_bytes());
+    json!({
+        "hash": hash,
+        "parentHash": B256::ZERO,
+        "sha3Uncles": B256::ZERO,
+        "miner": Address::ZERO,
+        "stateRoot": B256::ZERO,
+        "transactionsRoot": B256::ZERO,
+        "receiptsRoot": B256::ZERO,
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "difficulty": "0x0",
+        "number": format!("{:#x}", state.block_number),
+        "gasLimit": "0x1c9c380",
+        "gasUsed": "0x0",
+        "timestamp": "0x6700000",
+        "extraData": "0x",
+        "mixHash": B256::ZERO,
+        "nonce": "0x0000000000000000",
+        "baseFeePerGas": format!("{:#x}", state.base_fee),
+        "withdrawalsRoot": B256::ZERO,
+        "blobGasUsed": "0x0",
+        "excessBlobGas": "0x0",
+        "parentBeaconBlockRoot": B256::ZERO,
+        "requestsHash": B256::ZERO,
+        "withdrawals": [],
+        "size": "0x0",
+        "uncles": [],
+        "transactions": [],
+    })
+}
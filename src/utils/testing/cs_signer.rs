@@ -0,0 +1,502 @@
+//! 进程内的 cs-signer HTTP 桩，Phase 3 测试不再需要装 `cs-evm-signer` /
+//! 跑 `start_local_signer.sh`。
+//!
+//! ```ignore
+//! use flashseal_rs::utils::testing::{MockCsSigner, TESTING_SIGNER_AUTH_SEED};
+//! let cs = MockCsSigner::start("test").await?;
+//! cs.set_rule(|js| rule_allows(js));
+//! let signer = RemoteSigner::new(cs.url().into(), "test".into(), TESTING_SIGNER_AUTH_SEED, 0).await?;
+//! ```
+//!
+//! 与真实 cs-signer 一致的部分：
+//! - 认证：ed25519 签名 `sha256(timestamp + data)`、时间戳偏差、project、公钥白名单
+//! - 端点：`/v1/address`、`/v1/sign/transaction`、`/v1/sign/message`、`/v1/sign/typed_data`
+//! - 签名前把请求转成 rule.js 的 `jsStruct`（见 [`crate::utils::signer_json`]）交给规则回调
+//!
+//! 账户 0 / 1 分别是 [`TESTING_DELEGATE_PRIVKEY`] / [`TESTING_BUNDLER_PRIVKEY`]。
+//! 错误响应为 `{"msg": ...}`：认证失败 401，规则拒绝 403，请求格式错误 400。
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    consensus::{SignableTransaction, TxEip1559, TxEnvelope, TxLegacy},
+    dyn_abi::TypedData,
+    eips::Encodable2718,
+    primitives::{Address, Bytes, Signature, TxKind, U256},
+    signers::{local::PrivateKeySigner, SignerSync},
+};
+use ed25519_dalek::{Verifier, VerifyingKey};
+use eyre::{eyre, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use super::{
+    http::{serve_http, HttpRequest, HttpResponse, StubServer},
+    testing_signer_auth_pubkey_hex, TESTING_BUNDLER_PRIVKEY, TESTING_DELEGATE_PRIVKEY,
+};
+use crate::utils::signer_json::{
+    message_to_signer_json, tx_to_signer_json, typed_data_to_signer_json,
+};
+
+/// 默认允许的客户端时间戳偏差（秒）。
+const DEFAULT_MAX_SKEW_SECS: i64 = 30;
+
+/// 规则回调：参数是 rule.js `check` 收到的 `jsStruct`，返回 `Err(原因)` 表示拒签。
+type RuleFn = dyn Fn(&Value) -> std::result::Result<(), String> + Send + Sync;
+
+/// 桩收到的一次签名请求（`/v1/address` 不记录）。
+#[derive(Debug, Clone)]
+pub struct SignRequestRecord {
+    pub path: String,
+    /// 交给规则回调的 `jsStruct`。
+    pub signer_json: Value,
+    /// 规则拒绝的原因；`None` 表示已签名。
+    pub rejected: Option<String>,
+}
+
+struct SignerState {
+    project: String,
+    public_keys: Vec<String>,
+    max_skew_secs: i64,
+    /// 桩的时钟相对本机的偏移（秒），模拟时钟不同步的签名服务。
+    clock_offset_secs: i64,
+    rule: Option<Arc<RuleFn>>,
+    requests: Vec<SignRequestRecord>,
+}
+
+/// cs-signer 的进程内替身，见模块文档。
+pub struct MockCsSigner {
+    server: StubServer,
+    state: Arc<Mutex<SignerState>>,
+    accounts: Arc<Vec<PrivateKeySigner>>,
+}
+
+#[derive(Deserialize)]
+struct AuthRequest {
+    project: String,
+    signature: String,
+    public_key: String,
+    timestamp: i64,
+    data: String,
+}
+
+/// 处理失败时的 (HTTP 状态码, msg)。
+type Reject = (u16, String);
+
+fn bad_request(e: impl std::fmt::Display) -> Reject {
+    (400, e.to_string())
+}
+
+impl MockCsSigner {
+    /// 以 `project` 为项目名启动，公钥白名单只有 [`testing_signer_auth_pubkey_hex`]。
+    pub async fn start(project: &str) -> Result<Self> {
+        let accounts = [TESTING_DELEGATE_PRIVKEY, TESTING_BUNDLER_PRIVKEY]
+            .iter()
+            .map(|k| PrivateKeySigner::from_slice(k.as_slice()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let accounts = Arc::new(accounts);
+        let state = Arc::new(Mutex::new(SignerState {
+            project: project.to_string(),
+            public_keys: vec![testing_signer_auth_pubkey_hex()],
+            max_skew_secs: DEFAULT_MAX_SKEW_SECS,
+            clock_offset_secs: 0,
+            rule: None,
+            requests: Vec::new(),
+        }));
+        let (s, a) = (state.clone(), accounts.clone());
+        let server = serve_http(move |req| {
+            let (status, body) = match handle(&req, &s, &a) {
+                Ok(body) => (200, body),
+                Err((status, msg)) => (status, json!({ "msg": msg })),
+            };
+            let now = now_secs(&s.lock().expect("signer state mutex poisoned"));
+            let date = chrono::DateTime::from_timestamp(now, 0)
+                .expect("valid timestamp")
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string();
+            HttpResponse::json(status, &body).with_header("Date", &date)
+        })
+        .await?;
+        Ok(Self {
+            server,
+            state,
+            accounts,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        self.server.url()
+    }
+
+    /// 第 `index` 个账户的地址（即 `RemoteSigner::new` 的 `account_index`）。
+    pub fn account(&self, index: usize) -> Address {
+        self.accounts[index].address()
+    }
+
+    /// 把 ed25519 公钥（hex，无 0x）加进白名单。
+    pub fn allow_public_key(&self, hex: &str) {
+        self.lock().public_keys.push(hex.to_ascii_lowercase());
+    }
+
+    pub fn set_max_skew_secs(&self, secs: i64) {
+        self.lock().max_skew_secs = secs;
+    }
+
+    /// 让桩的时钟比本机快 `secs` 秒（负数为慢），响应的 `Date` header 同步偏移。
+    pub fn set_clock_offset_secs(&self, secs: i64) {
+        self.lock().clock_offset_secs = secs;
+    }
+
+    /// 签名前对 `jsStruct` 跑一遍 `rule`，相当于 rule.js 的 `check`。
+    pub fn set_rule<F>(&self, rule: F)
+    where
+        F: Fn(&Value) -> std::result::Result<(), String> + Send + Sync + 'static,
+    {
+        self.lock().rule = Some(Arc::new(rule));
+    }
+
+    pub fn requests(&self) -> Vec<SignRequestRecord> {
+        self.lock().requests.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SignerState> {
+        self.state.lock().expect("signer state mutex poisoned")
+    }
+}
+
+fn handle(
+    req: &HttpRequest,
+    state: &Mutex<SignerState>,
+    accounts: &[PrivateKeySigner],
+) -> std::result::Result<Value, Reject> {
+    let auth: AuthRequest = serde_json::from_slice(&req.body).map_err(bad_request)?;
+    let rule = {
+        let st = state.lock().expect("signer state mutex poisoned");
+        authenticate(&auth, &st).map_err(|e| (401, e.to_string()))?;
+        st.rule.clone()
+    };
+    let data: Value = serde_json::from_str(&auth.data).map_err(bad_request)?;
+
+    if req.path == "/v1/address" {
+        let index = data["index"]
+            .as_u64()
+            .ok_or_else(|| bad_request("missing index"))?;
+        let account = accounts
+            .get(index as usize)
+            .ok_or_else(|| bad_request(format!("no account at index {index}")))?;
+        return Ok(json!({ "data": account.address().to_string() }));
+    }
+
+    let account: Address = data["account"]
+        .as_str()
+        .and_then(|a| a.parse().ok())
+        .ok_or_else(|| bad_request("missing account"))?;
+    let key = accounts
+        .iter()
+        .find(|k| k.address() == account)
+        .ok_or_else(|| bad_request(format!("unknown account {account}")))?;
+
+    let request = match req.path.as_str() {
+        "/v1/sign/transaction" => SignRequest::transaction(&data, account)?,
+        "/v1/sign/message" => SignRequest::message(&data, account)?,
+        "/v1/sign/typed_data" => SignRequest::typed_data(&data, account)?,
+        p => return Err((404, format!("unknown path {p}"))),
+    };
+
+    let verdict = rule.map_or(Ok(()), |rule| rule(&request.signer_json));
+    state
+        .lock()
+        .expect("signer state mutex poisoned")
+        .requests
+        .push(SignRequestRecord {
+            path: req.path.clone(),
+            signer_json: request.signer_json.clone(),
+            rejected: verdict.clone().err(),
+        });
+    verdict.map_err(|msg| (403, format!("rule check failed: {msg}")))?;
+
+    request.sign(key).map_err(bad_request)
+}
+
+fn authenticate(auth: &AuthRequest, st: &SignerState) -> Result<()> {
+    eyre::ensure!(
+        auth.project == st.project,
+        "unknown project {}",
+        auth.project
+    );
+    eyre::ensure!(
+        st.public_keys
+            .contains(&auth.public_key.to_ascii_lowercase()),
+        "public key {} not allowed",
+        auth.public_key
+    );
+    let now = now_secs(st);
+    eyre::ensure!(
+        (now - auth.timestamp).abs() <= st.max_skew_secs,
+        "timestamp {} out of range",
+        auth.timestamp
+    );
+
+    let pubkey: [u8; 32] = alloy::hex::decode(&auth.public_key)?
+        .try_into()
+        .map_err(|_| eyre!("public key must be 32 bytes"))?;
+    let sig: [u8; 64] = alloy::hex::decode(&auth.signature)?
+        .try_into()
+        .map_err(|_| eyre!("signature must be 64 bytes"))?;
+    let hash = Sha256::digest(format!("{}{}", auth.timestamp, auth.data).as_bytes());
+    VerifyingKey::from_bytes(&pubkey)?
+        .verify(&hash, &ed25519_dalek::Signature::from_bytes(&sig))
+        .map_err(|_| eyre!("invalid signature"))
+}
+
+fn now_secs(st: &SignerState) -> i64 {
+    let local = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_secs() as i64;
+    local + st.clock_offset_secs
+}
+
+/// 已解析、待规则检查的签名请求。
+struct SignRequest {
+    kind: SignKind,
+    signer_json: Value,
+}
+
+enum SignKind {
+    Eip1559(TxEip1559),
+    Legacy(TxLegacy),
+    Message(Bytes),
+    TypedData(Box<TypedData>),
+}
+
+impl SignRequest {
+    fn transaction(data: &Value, account: Address) -> std::result::Result<Self, Reject> {
+        let chain_id = data["chain_id"].as_u64().unwrap_or_default();
+        let tx: Value = data["transaction"]
+            .as_str()
+            .ok_or_else(|| bad_request("missing transaction"))
+            .and_then(|t| serde_json::from_str(t).map_err(bad_request))?;
+        let to = match tx["to"].as_str() {
+            Some(to) if !to.is_empty() => TxKind::Call(to.parse().map_err(bad_request)?),
+            _ => TxKind::Create,
+        };
+        let input: Bytes = serde_json::from_value(tx["input"].clone()).map_err(bad_request)?;
+        let (nonce, gas_limit, value) = (hex_u64(&tx["nonce"])?, hex_u64(&tx["gas"])?, {
+            let v = tx["value"].as_str().unwrap_or("0x0");
+            v.parse::<U256>().map_err(bad_request)?
+        });
+
+        match tx["type"].as_str() {
+            Some("0x2" | "0x02") => {
+                let tx = TxEip1559 {
+                    chain_id: hex_u64(&tx["chainId"])?,
+                    nonce,
+                    gas_limit,
+                    max_fee_per_gas: hex_u64(&tx["maxFeePerGas"])? as u128,
+                    max_priority_fee_per_gas: hex_u64(&tx["maxPriorityFeePerGas"])? as u128,
+                    to,
+                    value,
+                    input,
+                    access_list: serde_json::from_value(tx["accessList"].clone())
+                        .unwrap_or_default(),
+                };
+                let signer_json = tx_to_signer_json(&tx, account, chain_id);
+                Ok(Self {
+                    kind: SignKind::Eip1559(tx),
+                    signer_json,
+                })
+            }
+            Some("0x0" | "0x00") => {
+                // signer_json 只覆盖 EIP-1559，legacy 原样带上 Go 风格 JSON
+                let mut inner = tx.clone();
+                inner["from"] = json!(format!("{account:#x}"));
+                let signer_json = json!({
+                    "type": "transaction",
+                    "content": { "chain_id": chain_id, "account": format!("{account:#x}"),
+                                 "transaction": inner },
+                });
+                let tx = TxLegacy {
+                    chain_id: Some(hex_u64(&tx["chainId"])?).filter(|c| *c != 0),
+                    nonce,
+                    gas_price: hex_u64(&tx["gasPrice"])? as u128,
+                    gas_limit,
+                    to,
+                    value,
+                    input,
+                };
+                Ok(Self {
+                    kind: SignKind::Legacy(tx),
+                    signer_json,
+                })
+            }
+            t => Err(bad_request(format!("unsupported tx type {t:?}"))),
+        }
+    }
+
+    fn message(data: &Value, account: Address) -> std::result::Result<Self, Reject> {
+        let message: Bytes =
+            serde_json::from_value(data["message"].clone()).map_err(bad_request)?;
+        Ok(Self {
+            signer_json: message_to_signer_json(&message, account),
+            kind: SignKind::Message(message),
+        })
+    }
+
+    fn typed_data(data: &Value, account: Address) -> std::result::Result<Self, Reject> {
+        let typed: TypedData = data["typed_data"]
+            .as_str()
+            .ok_or_else(|| bad_request("missing typed_data"))
+            .and_then(|t| serde_json::from_str(t).map_err(bad_request))?;
+        Ok(Self {
+            signer_json: typed_data_to_signer_json(&typed, account).map_err(bad_request)?,
+            kind: SignKind::TypedData(Box::new(typed)),
+        })
+    }
+
+    fn sign(self, key: &PrivateKeySigner) -> Result<Value> {
+        let hex_sig = |sig: Signature| format!("0x{}", alloy::hex::encode(sig.as_bytes()));
+        Ok(match self.kind {
+            SignKind::Eip1559(tx) => {
+                let sig = key.sign_hash_sync(&tx.signature_hash())?;
+                let raw = TxEnvelope::Eip1559(tx.into_signed(sig)).encoded_2718();
+                json!({ "tx_hex": format!("0x{}", alloy::hex::encode(raw)) })
+            }
+            SignKind::Legacy(tx) => {
+                let sig = key.sign_hash_sync(&tx.signature_hash())?;
+                let raw = TxEnvelope::Legacy(tx.into_signed(sig)).encoded_2718();
+                json!({ "tx_hex": format!("0x{}", alloy::hex::encode(raw)) })
+            }
+            SignKind::Message(message) => {
+                json!({ "signature": hex_sig(key.sign_message_sync(&message)?) })
+            }
+            SignKind::TypedData(typed) => {
+                let digest = typed.eip712_signing_hash()?;
+                json!({ "signature": hex_sig(key.sign_hash_sync(&digest)?) })
+            }
+        })
+    }
+}
+
+fn hex_u64(v: &Value) -> std::result::Result<u64, Reject> {
+    let s = v
+        .as_str()
+        .ok_or_else(|| bad_request(format!("expected hex string, got {v}")))?;
+    u64::from_str_radix(s.trim_start_matches("0x"), 16).map_err(bad_request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utils::testing::{TESTING_DELEGATE_ADDRESS, TESTING_SIGNER_AUTH_SEED},
+        RemoteSigner, TxSigner,
+    };
+    use alloy::{
+        consensus::{transaction::SignerRecoverable, Transaction},
+        eips::Decodable2718,
+        primitives::{address, eip191_hash_message},
+    };
+    use ed25519_dalek::{Signer as _, SigningKey};
+
+    fn tx(value: u64) -> TxEip1559 {
+        TxEip1559 {
+            chain_id: 1,
+            nonce: 7,
+            gas_limit: 21_000,
+            max_fee_per_gas: 30,
+            max_priority_fee_per_gas: 2,
+            to: TxKind::Call(address!("1111111111111111111111111111111111111111")),
+            value: U256::from(value),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn remote_signer_signs_through_mock() {
+        let cs = MockCsSigner::start("test").await.unwrap();
+        cs.set_rule(|js| match js["content"]["transaction"]["value"].as_str() {
+            Some("0x0") | None => Ok(()),
+            Some(v) => Err(format!("value {v} not allowed")),
+        });
+        let signer = RemoteSigner::new(cs.url().into(), "test".into(), TESTING_SIGNER_AUTH_SEED, 0)
+            .await
+            .unwrap();
+        assert_eq!(signer.address(), TESTING_DELEGATE_ADDRESS);
+
+        let raw = signer.sign(tx(0)).await.unwrap();
+        let envelope = TxEnvelope::decode_2718(&mut raw.0.as_ref()).unwrap();
+        assert_eq!(envelope.nonce(), 7);
+        assert_eq!(envelope.recover_signer().unwrap(), TESTING_DELEGATE_ADDRESS);
+
+        let err = signer.sign(tx(1)).await.err().unwrap();
+        assert!(err.to_string().contains("value 0x1 not allowed"), "{err}");
+
+        let sig = signer.sign_message(b"hello").await.unwrap();
+        assert_eq!(
+            sig.recover_address_from_prehash(&eip191_hash_message(b"hello"))
+                .unwrap(),
+            TESTING_DELEGATE_ADDRESS
+        );
+
+        let requests = cs.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].signer_json["type"], "transaction");
+        assert!(requests[0].rejected.is_none());
+        assert!(requests[1].rejected.is_some());
+        assert_eq!(requests[2].path, "/v1/sign/message");
+
+        let bundler =
+            RemoteSigner::new(cs.url().into(), "test".into(), TESTING_SIGNER_AUTH_SEED, 1)
+                .await
+                .unwrap();
+        assert_eq!(bundler.address(), cs.account(1));
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_auth() {
+        let cs = MockCsSigner::start("test").await.unwrap();
+
+        let err = RemoteSigner::new(cs.url().into(), "other".into(), TESTING_SIGNER_AUTH_SEED, 0)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("unknown project"), "{err}");
+
+        let err = RemoteSigner::new(cs.url().into(), "test".into(), [0x09; 32], 0)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("not allowed"), "{err}");
+
+        let other = SigningKey::from_bytes(&[0x09; 32]);
+        cs.allow_public_key(&alloy::hex::encode(other.verifying_key().as_bytes()));
+        RemoteSigner::new(cs.url().into(), "test".into(), [0x09; 32], 0)
+            .await
+            .unwrap();
+
+        // 过期时间戳
+        let key = SigningKey::from_bytes(&TESTING_SIGNER_AUTH_SEED);
+        let (timestamp, data) = (1_000_000_000i64, r#"{"index":0}"#);
+        let sig = key.sign(&Sha256::digest(format!("{timestamp}{data}").as_bytes()));
+        let resp = reqwest::Client::new()
+            .post(format!("{}/v1/address", cs.url()))
+            .json(&json!({
+                "project": "test",
+                "signature": format!("0x{}", alloy::hex::encode(sig.to_bytes())),
+                "public_key": testing_signer_auth_pubkey_hex(),
+                "timestamp": timestamp,
+                "data": data,
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status().as_u16(), 401);
+        let body: Value = resp.json().await.unwrap();
+        assert!(body["msg"].as_str().unwrap().contains("out of range"));
+    }
+}
@@ -0,0 +1,273 @@
+//! 进程内 HTTP / JSON-RPC 桩服务，给 bundler / relay / signer 客户端做离线测试。
+//!
+//! 只实现测试够用的 HTTP/1.1 子集：每个连接处理一个请求（`Connection: close`），
+//! 按 `Content-Length` 读 body，不支持 chunked。
+//!
+//! ```ignore
+//! use flashseal_rs::utils::testing::serve_json_rpc;
+//! let stub = serve_json_rpc(|method, _params| match method {
+//!     "eth_chainId" => Ok(serde_json::json!("0x1")),
+//!     m => Err((-32601, format!("method {m} not found"))),
+//! })
+//! .await?;
+//! let client = BundlerSender::new(stub.url(), ENTRY_POINT_V07)?;
+//! ```
+
+use std::{sync::Arc, time::Duration};
+
+use alloy::primitives::{keccak256, Address, Signature};
+use eyre::Result;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// 桩服务收到的一次 HTTP 请求。
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    /// header 名统一转成小写。
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// 按名字（大小写不敏感）取 header。
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn json(&self) -> Result<Value> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+
+    /// 校验 `X-Flashbots-Signature` 并返回签名者地址；缺失或签名不匹配返回 `None`。
+    pub fn flashbots_signer(&self) -> Option<Address> {
+        let (addr, sig) = self.header("x-flashbots-signature")?.split_once(':')?;
+        let addr: Address = addr.parse().ok()?;
+        let sig: Signature = sig.parse().ok()?;
+        let hash_hex = format!("{:#x}", keccak256(&self.body));
+        let recovered = sig.recover_address_from_msg(hash_hex.as_bytes()).ok()?;
+        (recovered == addr).then_some(addr)
+    }
+}
+
+/// 桩服务的响应。`delay` 用于模拟慢节点 / 超时。
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+    pub delay: Option<Duration>,
+    /// 额外的响应 header（`Content-Type` / `Content-Length` 由桩自动写）。
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpResponse {
+    pub fn json(status: u16, body: &Value) -> Self {
+        Self { status, body: body.to_string(), delay: None, headers: Vec::new() }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+/// 正在监听的桩服务。drop 时停止。
+pub struct StubServer {
+    url: String,
+    handle: JoinHandle<()>,
+}
+
+impl StubServer {
+    /// `http://127.0.0.1:<port>`，不带结尾 `/`。
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for StubServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// 在 `127.0.0.1` 随机端口起一个 HTTP 桩服务，每个请求交给 `handler`。
+pub async fn serve_http<F>(handler: F) -> Result<StubServer>
+where
+    F: Fn(HttpRequest) -> HttpResponse + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?);
+    let handler = Arc::new(handler);
+    let handle = tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_conn(stream, handler.as_ref()).await {
+                    tracing::debug!("[stub] connection error: {e}");
+                }
+            });
+        }
+    });
+    Ok(StubServer { url, handle })
+}
+
+/// JSON-RPC 2.0 桩服务：`handler(method, params)` 返回 `result` 或 `(code, message)`。
+/// 支持 batch 请求。
+pub async fn serve_json_rpc<F>(handler: F) -> Result<StubServer>
+where
+    F: Fn(&str, &Value) -> std::result::Result<Value, (i64, String)> + Send + Sync + 'static,
+{
+    serve_http(move |req| {
+        let body = match req.json() {
+            Ok(v) => v,
+            Err(e) => {
+                return HttpResponse::json(400, &rpc_error(&Value::Null, -32700, &e.to_string()))
+            }
+        };
+        let reply = match &body {
+            Value::Array(calls) => Value::Array(calls.iter().map(|c| rpc_reply(c, &handler)).collect()),
+            call => rpc_reply(call, &handler),
+        };
+        HttpResponse::json(200, &reply)
+    })
+    .await
+}
+
+fn rpc_reply<F>(call: &Value, handler: &F) -> Value
+where
+    F: Fn(&str, &Value) -> std::result::Result<Value, (i64, String)>,
+{
+    let id = call.get("id").cloned().unwrap_or(Value::Null);
+    let method = call.get("method").and_then(Value::as_str).unwrap_or_default();
+    let params = call.get("params").cloned().unwrap_or(Value::Null);
+    match handler(method, &params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => rpc_error(&id, code, &message),
+    }
+}
+
+fn rpc_error(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+async fn handle_conn<F>(mut stream: TcpStream, handler: &F) -> Result<()>
+where
+    F: Fn(HttpRequest) -> HttpResponse,
+{
+    let mut buf = Vec::new();
+    let header_end = loop {
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await?;
+        eyre::ensure!(n > 0, "connection closed before headers");
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+    let content_length = headers
+        .iter()
+        .find(|(k, _)| k == "content-length")
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let mut chunk = vec![0u8; content_length - body.len()];
+        let n = stream.read(&mut chunk).await?;
+        eyre::ensure!(n > 0, "connection closed before body complete");
+        body.extend_from_slice(&chunk[..n]);
+    }
+
+    let resp = handler(HttpRequest { method, path, headers, body });
+    if let Some(d) = resp.delay {
+        tokio::time::sleep(d).await;
+    }
+    let extra: String = resp.headers.iter().map(|(k, v)| format!("{k}: {v}\r\n")).collect();
+    let out = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{extra}Connection: close\r\n\r\n{}",
+        resp.status,
+        reason_phrase(resp.status),
+        resp.body.len(),
+        resp.body,
+    );
+    stream.write_all(out.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn json_rpc_stub_roundtrip() {
+        let stub = serve_json_rpc(|method, params| match method {
+            "echo" => Ok(params.clone()),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+
+        let client = reqwest::Client::new();
+        let v: Value = client
+            .post(stub.url())
+            .json(&json!({ "jsonrpc": "2.0", "id": 7, "method": "echo", "params": [1, 2] }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(v["id"], 7);
+        assert_eq!(v["result"], json!([1, 2]));
+
+        let v: Value = client
+            .post(stub.url())
+            .json(&json!({ "jsonrpc": "2.0", "id": 8, "method": "nope", "params": [] }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(v["error"]["code"], -32601);
+    }
+}
@@ -2,6 +2,22 @@
 //!
 //! **仅限本地 fork / 本地 cs-signer 使用**。所有常量都是公开可见的固定私钥，
 //! 不可用于主网。
+//!
+//! 另含进程内 HTTP / JSON-RPC 桩服务（[`serve_http`] / [`serve_json_rpc`]），
+//! 用来离线测试各类 HTTP 客户端（bundler、relay、signer），以及基于它的
+//! Flashbots relay 桩 [`MockRelay`]、cs-signer 桩 [`MockCsSigner`]、可被 fork 的
+//! 链节点桩 [`MockChain`]。
+
+mod chain;
+mod cs_signer;
+mod http;
+mod relay;
+
+pub use chain::MockChain;
+pub use cs_signer::{MockCsSigner, SignRequestRecord};
+
+pub use http::{serve_http, serve_json_rpc, HttpRequest, HttpResponse, StubServer};
+pub use relay::{MockFailure, MockRelay, ReceivedBundle, ReceivedPrivateTx};
 
 use alloy::{
     primitives::{address, Address, B256},
@@ -38,6 +54,19 @@ pub fn testing_delegate() -> (LocalSigner, Address) {
     (LocalSigner::new(pk), addr)
 }
 
+/// fork 上模拟 ERC-4337 bundler 用的固定私钥：`0x0303...03`。
+///
+/// [`crate::simulator::user_op::handle_ops`] 以它为 caller / beneficiary，
+/// 避免用真实 bundler 地址时受其链上 nonce / 余额影响。
+pub const TESTING_BUNDLER_PRIVKEY: B256 = B256::new([0x03; 32]);
+
+/// [`TESTING_BUNDLER_PRIVKEY`] 对应的地址。
+pub fn testing_bundler_address() -> Address {
+    PrivateKeySigner::from_slice(TESTING_BUNDLER_PRIVKEY.as_slice())
+        .expect("constant privkey is valid secp256k1 scalar")
+        .address()
+}
+
 /// 测试客户端认证 cs-signer 用的 ed25519 seed：`0x0202...02`。
 ///
 /// cs-signer 的客户端（[`crate::RemoteSigner`]）需要一对 ed25519 keypair 做
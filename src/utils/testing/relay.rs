@@ -0,0 +1,525 @@
+//! 进程内的 Flashbots relay / builder 桩，给 `FlashbotsSender` / `PrivateSender` 做离线测试。
+//!
+//! ```ignore
+//! use flashseal_rs::utils::testing::MockRelay;
+//! let relay = MockRelay::start().await?;
+//! let sender = FlashbotsSender::new(auth, rpc_url)?
+//!     .with_relay(relay.url())
+//!     .with_builders(vec![relay.builder_endpoint("mock")]);
+//! sender.send_txs(&raws).await?;
+//! assert_eq!(relay.bundles()[0].txs.len(), raws.len());
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use alloy::{
+    consensus::{Transaction, TxEnvelope},
+    eips::Decodable2718,
+    primitives::{keccak256, Address, Bytes, B256, U256},
+};
+use eyre::Result;
+use serde_json::{json, Value};
+
+use super::http::{serve_http, HttpRequest, HttpResponse, StubServer};
+use crate::{sender::BuilderEndpoint, simulator::ForkSimulator};
+
+/// relay 收到的一次 `eth_sendBundle`。
+#[derive(Debug, Clone)]
+pub struct ReceivedBundle {
+    /// `X-Flashbots-Signature` 里验证通过的 searcher 地址。
+    pub signer: Address,
+    pub txs: Vec<Bytes>,
+    pub block_number: u64,
+    pub replacement_uuid: Option<String>,
+    /// 原始参数对象（revertingTxHashes / refundPercent 等从这里取）。
+    pub params: Value,
+    /// 配了模拟器时每笔交易的执行结果（true = 成功），否则为空。
+    /// 同一 bundle（bundleHash 相同）只执行一次，之后的副本沿用第一次的结果。
+    pub executed: Vec<bool>,
+}
+
+/// relay 收到的一次 `eth_sendPrivateTransaction`。
+#[derive(Debug, Clone)]
+pub struct ReceivedPrivateTx {
+    pub signer: Address,
+    pub tx: Bytes,
+    pub max_block_number: Option<u64>,
+    pub preferences: Option<Value>,
+}
+
+/// 让后续请求失败的方式，见 [`MockRelay::fail_with`]。
+#[derive(Debug, Clone)]
+pub enum MockFailure {
+    /// 返回 HTTP 200 + JSON-RPC error。
+    Rpc { code: i64, message: String },
+    /// 返回指定 HTTP 状态码。
+    Http(u16),
+    /// 正常处理，但延迟这么久才响应（用来触发客户端超时）。
+    Delay(Duration),
+}
+
+#[derive(Default)]
+struct RelayState {
+    bundles: Vec<ReceivedBundle>,
+    /// 已在模拟器上执行过的 bundleHash → 执行结果。
+    executed: HashMap<B256, Vec<bool>>,
+    cancelled: Vec<String>,
+    private_txs: Vec<ReceivedPrivateTx>,
+    bad_signatures: usize,
+    failure: Option<MockFailure>,
+}
+
+/// 实现 `eth_sendBundle` / `eth_callBundle` / `eth_cancelBundle` /
+/// `eth_sendPrivateTransaction` / `eth_cancelPrivateTransaction` 的 relay 桩。
+///
+/// - 每个请求都校验 `X-Flashbots-Signature`，不通过返回 403 并计数
+/// - 同一个 URL 既当 relay（`with_relay`）又当 builder（[`builder_endpoint`](Self::builder_endpoint)）
+/// - 配了 [`ForkSimulator`] 时：`eth_sendBundle` 把 bundle 按顺序执行并 commit
+///   （相当于被打包），同一 bundle 发往多个 target block 也只执行一次；
+///   `eth_callBundle` 在状态副本上按顺序执行，不 commit
+pub struct MockRelay {
+    server: StubServer,
+    state: Arc<Mutex<RelayState>>,
+    sim: Option<Arc<Mutex<ForkSimulator>>>,
+}
+
+impl MockRelay {
+    pub async fn start() -> Result<Self> {
+        Self::spawn(None).await
+    }
+
+    /// 用 `sim` 执行收到的 bundle。
+    ///
+    /// 模拟器在 handler 里同步拉 fork 状态，需要 multi_thread runtime 且至少两个
+    /// worker（`worker_threads = 2`），否则单核机器上会卡住。
+    pub async fn start_with_simulator(sim: ForkSimulator) -> Result<Self> {
+        Self::spawn(Some(Arc::new(Mutex::new(sim)))).await
+    }
+
+    async fn spawn(sim: Option<Arc<Mutex<ForkSimulator>>>) -> Result<Self> {
+        let state = Arc::new(Mutex::new(RelayState::default()));
+        let (s, fork) = (state.clone(), sim.clone());
+        let server = serve_http(move |req| handle(&req, &s, fork.as_deref())).await?;
+        Ok(Self { server, state, sim })
+    }
+
+    pub fn url(&self) -> &str {
+        self.server.url()
+    }
+
+    /// 指向本桩的 builder 端点，给 `FlashbotsSender::with_builders` 用。
+    pub fn builder_endpoint(&self, name: &str) -> BuilderEndpoint {
+        BuilderEndpoint::new(name, self.url())
+    }
+
+    pub fn bundles(&self) -> Vec<ReceivedBundle> {
+        self.lock().bundles.clone()
+    }
+
+    /// `eth_cancelBundle` 收到的 replacementUuid。
+    pub fn cancelled(&self) -> Vec<String> {
+        self.lock().cancelled.clone()
+    }
+
+    pub fn private_txs(&self) -> Vec<ReceivedPrivateTx> {
+        self.lock().private_txs.clone()
+    }
+
+    /// 签名缺失或校验失败的请求数。
+    pub fn bad_signatures(&self) -> usize {
+        self.lock().bad_signatures
+    }
+
+    /// 之后的请求都按 `failure` 失败，直到 [`recover`](Self::recover)。
+    pub fn fail_with(&self, failure: MockFailure) {
+        self.lock().failure = Some(failure);
+    }
+
+    pub fn recover(&self) {
+        self.lock().failure = None;
+    }
+
+    /// 读写模拟器状态（如在 bundle 执行后查余额）。未配模拟器时返回 `None`。
+    pub fn with_simulator<R>(&self, f: impl FnOnce(&mut ForkSimulator) -> R) -> Option<R> {
+        let sim = self.sim.as_ref()?;
+        Some(f(&mut sim.lock().expect("simulator mutex poisoned")))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RelayState> {
+        self.state.lock().expect("relay state mutex poisoned")
+    }
+}
+
+fn handle(
+    req: &HttpRequest,
+    state: &Mutex<RelayState>,
+    sim: Option<&Mutex<ForkSimulator>>,
+) -> HttpResponse {
+    let failure = state
+        .lock()
+        .expect("relay state mutex poisoned")
+        .failure
+        .clone();
+    let delay = match failure {
+        Some(MockFailure::Http(status)) => {
+            return HttpResponse::json(status, &json!({ "error": "mock failure" }));
+        }
+        Some(MockFailure::Rpc { code, message }) => {
+            return rpc_response(&Value::Null, Err((code, message)));
+        }
+        Some(MockFailure::Delay(d)) => Some(d),
+        None => None,
+    };
+
+    let Ok(body) = req.json() else {
+        return rpc_response(&Value::Null, Err((-32700, "parse error".into())));
+    };
+    let id = body.get("id").cloned().unwrap_or(Value::Null);
+    let Some(signer) = req.flashbots_signer() else {
+        state
+            .lock()
+            .expect("relay state mutex poisoned")
+            .bad_signatures += 1;
+        let err = json!({ "jsonrpc": "2.0", "id": id, "error": {
+            "code": -32600, "message": "missing or invalid X-Flashbots-Signature" } });
+        return HttpResponse::json(403, &err);
+    };
+    let method = body["method"].as_str().unwrap_or_default();
+    let params = &body["params"][0];
+
+    let result = match method {
+        "eth_sendBundle" => send_bundle(params, signer, state, sim),
+        "eth_callBundle" => call_bundle(params, sim),
+        "eth_cancelBundle" => {
+            let uuid = params["replacementUuid"].as_str().unwrap_or_default();
+            let mut st = state.lock().expect("relay state mutex poisoned");
+            st.cancelled.push(uuid.to_string());
+            Ok(Value::Null)
+        }
+        "eth_sendPrivateTransaction" => send_private_tx(params, signer, state),
+        "eth_cancelPrivateTransaction" => Ok(json!(true)),
+        m => Err((-32601, format!("method {m} not found"))),
+    };
+    let resp = rpc_response(&id, result);
+    match delay {
+        Some(d) => resp.with_delay(d),
+        None => resp,
+    }
+}
+
+fn rpc_response(id: &Value, result: std::result::Result<Value, (i64, String)>) -> HttpResponse {
+    let body = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => {
+            json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+        }
+    };
+    HttpResponse::json(200, &body)
+}
+
+type RpcResult = std::result::Result<Value, (i64, String)>;
+
+fn invalid(msg: impl std::fmt::Display) -> (i64, String) {
+    (-32602, msg.to_string())
+}
+
+fn parse_txs(params: &Value) -> std::result::Result<Vec<Bytes>, (i64, String)> {
+    serde_json::from_value(params["txs"].clone()).map_err(|e| invalid(format!("invalid txs: {e}")))
+}
+
+fn parse_block(v: &Value) -> Option<u64> {
+    u64::from_str_radix(v.as_str()?.strip_prefix("0x")?, 16).ok()
+}
+
+/// Flashbots 的 bundleHash：所有 tx hash 拼接后取 keccak256。
+fn bundle_hash(txs: &[Bytes]) -> B256 {
+    let hashes: Vec<u8> = txs.iter().flat_map(|t| keccak256(t).0).collect();
+    keccak256(hashes)
+}
+
+fn send_bundle(
+    params: &Value,
+    signer: Address,
+    state: &Mutex<RelayState>,
+    sim: Option<&Mutex<ForkSimulator>>,
+) -> RpcResult {
+    let txs = parse_txs(params)?;
+    let block_number =
+        parse_block(&params["blockNumber"]).ok_or_else(|| invalid("invalid blockNumber"))?;
+    let hash = bundle_hash(&txs);
+    // 持有 state 锁执行，并发到达的同一 bundle 的多个副本只会执行一次
+    let mut st = state.lock().expect("relay state mutex poisoned");
+    let executed = match (sim, st.executed.get(&hash)) {
+        (Some(_), Some(prev)) => prev.clone(),
+        (Some(sim), None) => {
+            let mut sim = sim.lock().expect("simulator mutex poisoned");
+            let executed = txs
+                .iter()
+                .map(|tx| sim.simulate_raw_tx(tx).map(|r| r.success))
+                .collect::<Result<Vec<_>>>()
+                .map_err(invalid)?;
+            st.executed.insert(hash, executed.clone());
+            executed
+        }
+        (None, _) => Vec::new(),
+    };
+    st.bundles.push(ReceivedBundle {
+        signer,
+        block_number,
+        replacement_uuid: params["replacementUuid"].as_str().map(str::to_string),
+        params: params.clone(),
+        txs,
+        executed,
+    });
+    Ok(json!({ "bundleHash": hash }))
+}
+
+fn call_bundle(params: &Value, sim: Option<&Mutex<ForkSimulator>>) -> RpcResult {
+    let txs = parse_txs(params)?;
+    let block_number =
+        parse_block(&params["blockNumber"]).ok_or_else(|| invalid("invalid blockNumber"))?;
+    let sim = sim.map(|s| s.lock().expect("simulator mutex poisoned"));
+    let basefee = sim.as_ref().map_or(0, |s| s.block_env().basefee);
+
+    let envs = txs
+        .iter()
+        .map(|raw| ForkSimulator::raw_tx_env(raw).map_err(invalid))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let executed = match &sim {
+        Some(sim) => Some(sim.simulate_bundle(envs.clone()).map_err(invalid)?),
+        None => None,
+    };
+
+    let mut results = Vec::new();
+    let (mut total_gas, mut total_fees) = (0u64, U256::ZERO);
+    for (i, (raw, env)) in txs.iter().zip(&envs).enumerate() {
+        let envelope = TxEnvelope::decode_2718(&mut raw.as_ref()).map_err(invalid)?;
+        let (gas_used, error, output) = match &executed {
+            Some(executed) => {
+                let r = &executed[i];
+                let error = (!r.success).then(|| "execution reverted".to_string());
+                (
+                    r.gas_used,
+                    error.map(|e| (e, r.revert_reason.clone())),
+                    r.output.clone(),
+                )
+            }
+            None => (21_000, None, None),
+        };
+        let gas_price = envelope.effective_gas_price(Some(basefee));
+        let fees = U256::from(gas_used) * U256::from(gas_price.saturating_sub(basefee as u128));
+        total_gas += gas_used;
+        total_fees += fees;
+        let mut r = json!({
+            "txHash": keccak256(raw),
+            "fromAddress": env.caller,
+            "toAddress": envelope.to(),
+            "gasUsed": gas_used,
+            "gasPrice": gas_price.to_string(),
+            "gasFees": fees.to_string(),
+            "coinbaseDiff": fees.to_string(),
+            "ethSentToCoinbase": "0",
+            "value": output.unwrap_or_default(),
+        });
+        if let Some((error, revert)) = error {
+            r["error"] = json!(error);
+            r["revert"] = json!(revert);
+        }
+        results.push(r);
+    }
+    let bundle_gas_price = if total_gas == 0 {
+        U256::ZERO
+    } else {
+        total_fees / U256::from(total_gas)
+    };
+    Ok(json!({
+        "bundleHash": bundle_hash(&txs),
+        "bundleGasPrice": bundle_gas_price.to_string(),
+        "coinbaseDiff": total_fees.to_string(),
+        "ethSentToCoinbase": "0",
+        "gasFees": total_fees.to_string(),
+        "results": results,
+        "stateBlockNumber": block_number.saturating_sub(1),
+        "totalGasUsed": total_gas,
+    }))
+}
+
+fn send_private_tx(params: &Value, signer: Address, state: &Mutex<RelayState>) -> RpcResult {
+    let tx: Bytes = serde_json::from_value(params["tx"].clone()).map_err(invalid)?;
+    let hash = keccak256(&tx);
+    state
+        .lock()
+        .expect("relay state mutex poisoned")
+        .private_txs
+        .push(ReceivedPrivateTx {
+            signer,
+            max_block_number: parse_block(&params["maxBlockNumber"]),
+            preferences: params.get("preferences").cloned(),
+            tx,
+        });
+    Ok(json!(hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sender::{BundleOptions, FlashbotsSender, PrivateSender, RawTx, TxSender},
+        utils::testing::{serve_json_rpc, testing_delegate, MockChain},
+        TxSigner,
+    };
+    use alloy::{
+        consensus::TxEip1559,
+        primitives::{address, TxKind},
+        signers::local::PrivateKeySigner,
+    };
+
+    async fn signed_tx(nonce: u64) -> RawTx {
+        let (signer, _) = testing_delegate();
+        signer
+            .sign(TxEip1559 {
+                chain_id: 1,
+                nonce,
+                gas_limit: 21_000,
+                max_fee_per_gas: 30,
+                max_priority_fee_per_gas: 2,
+                to: TxKind::Call(address!("1111111111111111111111111111111111111111")),
+                value: U256::from(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn records_bundles_and_private_txs() {
+        let rpc = serve_json_rpc(|method, _| match method {
+            "eth_blockNumber" => Ok(json!("0x10")),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let relay = MockRelay::start().await.unwrap();
+        let auth = PrivateKeySigner::random();
+        let txs = [signed_tx(0).await, signed_tx(1).await];
+
+        let sender = FlashbotsSender::new(auth.clone(), rpc.url())
+            .unwrap()
+            .with_relay(relay.url())
+            .with_builders(vec![relay.builder_endpoint("mock")])
+            .with_block_window(1)
+            .with_bundle_options(BundleOptions {
+                replacement_uuid: Some("u-1".into()),
+                ..Default::default()
+            });
+        let sim = sender.simulate_bundle(&txs, 0x11).await.unwrap();
+        assert_eq!(sim.total_gas_used, 42_000);
+        assert_eq!(sim.results[0].gas_price, U256::from(2));
+
+        let hashes = sender.send_txs(&txs).await.unwrap();
+        let tx_hashes: Vec<u8> = txs.iter().flat_map(|t| keccak256(&t.0).0).collect();
+        assert_eq!(hashes, vec![keccak256(tx_hashes)]);
+        sender.cancel_bundle("u-1").await.unwrap();
+
+        let bundles = relay.bundles();
+        let blocks: Vec<u64> = bundles.iter().map(|b| b.block_number).collect();
+        assert_eq!(blocks, vec![0x11]);
+        assert!(bundles.iter().all(|b| b.signer == auth.address()
+            && b.txs.len() == 2
+            && b.replacement_uuid.as_deref() == Some("u-1")));
+        assert_eq!(relay.cancelled(), vec!["u-1"]);
+
+        let private = PrivateSender::with_relay(auth.clone(), rpc.url(), relay.url()).unwrap();
+        private.send_txs(&txs[..1]).await.unwrap();
+        let received = relay.private_txs();
+        assert_eq!(received[0].tx, txs[0].0);
+        assert_eq!(received[0].max_block_number, Some(0x10 + 25));
+        assert_eq!(relay.bad_signatures(), 0);
+    }
+
+    #[tokio::test]
+    async fn injects_failures_and_rejects_bad_signatures() {
+        let rpc = serve_json_rpc(|method, _| match method {
+            "eth_blockNumber" => Ok(json!("0x10")),
+            m => Err((-32601, format!("method {m} not found"))),
+        })
+        .await
+        .unwrap();
+        let relay = MockRelay::start().await.unwrap();
+        let private =
+            PrivateSender::with_relay(PrivateKeySigner::random(), rpc.url(), relay.url()).unwrap();
+        let txs = [signed_tx(0).await];
+
+        relay.fail_with(MockFailure::Rpc {
+            code: -32000,
+            message: "bundle rejected".into(),
+        });
+        let err = private.send_txs(&txs).await.unwrap_err();
+        assert!(err.to_string().contains("bundle rejected"), "{err}");
+
+        relay.fail_with(MockFailure::Http(503));
+        assert!(private.send_txs(&txs).await.is_err());
+
+        // 响应比 builder 超时慢
+        relay.fail_with(MockFailure::Delay(Duration::from_secs(1)));
+        let mut slow = relay.builder_endpoint("slow");
+        slow.timeout_ms = 200;
+        let sender = FlashbotsSender::new(PrivateKeySigner::random(), rpc.url())
+            .unwrap()
+            .with_builders(vec![slow]);
+        let report = sender.send_bundle(&txs, 0x11).await.unwrap();
+        assert_eq!(report.accepted(), 0);
+        assert_eq!(report.results[0].status, None);
+
+        relay.recover();
+        let unsigned = reqwest::Client::new()
+            .post(relay.url())
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_sendBundle", "params": [{}] }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unsigned.status().as_u16(), 403);
+        assert_eq!(relay.bad_signatures(), 1);
+        // 超时的那次 relay 仍然收到了
+        assert_eq!(relay.bundles().len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn executes_bundles_on_simulator() {
+        let chain = MockChain::start(1).await.unwrap();
+        chain.set_base_fee(10);
+        let (_, delegate) = testing_delegate();
+        chain.set_balance(delegate, U256::from(10).pow(U256::from(18)));
+        let sim = ForkSimulator::fork(chain.url(), None).await.unwrap();
+        let relay = MockRelay::start_with_simulator(sim).await.unwrap();
+        let sender = FlashbotsSender::new(PrivateKeySigner::random(), chain.url())
+            .unwrap()
+            .with_relay(relay.url())
+            .with_builders(vec![relay.builder_endpoint("mock")])
+            .with_block_window(2);
+        let recipient = address!("1111111111111111111111111111111111111111");
+        let nonce = || relay.with_simulator(|s| s.get_nonce(delegate).unwrap());
+
+        // 同一 sender 的 nonce 0 / 1：第二笔要看到第一笔的状态才能执行
+        let txs = [signed_tx(0).await, signed_tx(1).await];
+        let sim = sender.simulate_bundle(&txs, 101).await.unwrap();
+        assert!(sim.results.iter().all(|r| r.error.is_none()), "{sim:?}");
+        assert_eq!(sim.total_gas_used, 42_000);
+        assert_eq!(nonce(), Some(0));
+
+        // 发往两个 target block，只执行一次
+        sender.send_txs(&txs).await.unwrap();
+        let bundles = relay.bundles();
+        assert_eq!(bundles.len(), 2);
+        assert!(bundles.iter().all(|b| b.executed == vec![true, true]));
+        assert_eq!(nonce(), Some(2));
+        assert_eq!(
+            relay.with_simulator(|s| s.get_balance(recipient).unwrap()),
+            Some(U256::from(2))
+        );
+    }
+}